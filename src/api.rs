@@ -18,14 +18,23 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use crate::connection::{self, ConnectionSettings};
 use crate::models::*;
+use crate::tokens::{self, TokenEvent, TokenManager};
+use crate::trust::{self, ServerTrust};
+use gtk::glib;
 use reqwest::blocking::Client;
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// Audiobookshelf API client
 #[derive(Debug, Clone)]
 pub struct AudiobookshelfClient {
     inner: Arc<Mutex<ClientInner>>,
+    tokens: Arc<TokenManager>,
 }
 
 #[derive(Debug)]
@@ -34,26 +43,61 @@ struct ClientInner {
     base_url: String,
     access_token: Option<String>,
     refresh_token: Option<String>,
+    connection: ConnectionSettings,
+    /// The access token is an API key, which never needs refreshing
+    api_key: bool,
+}
+
+impl Default for ClientInner {
+    fn default() -> Self {
+        Self {
+            client: build_http_client(None, &ConnectionSettings::default()),
+            base_url: String::new(),
+            access_token: None,
+            refresh_token: None,
+            connection: ConnectionSettings::default(),
+            api_key: false,
+        }
+    }
 }
 
 impl AudiobookshelfClient {
     pub fn new() -> Self {
         Self {
-            inner: Arc::new(Mutex::new(ClientInner {
-                client: Client::builder()
-                    .timeout(std::time::Duration::from_secs(30))
-                    .build()
-                    .expect("Failed to create HTTP client"),
-                base_url: String::new(),
-                access_token: None,
-                refresh_token: None,
-            })),
+            inner: Arc::new(Mutex::new(ClientInner::default())),
+            tokens: Arc::new(TokenManager::default()),
+        }
+    }
+
+    /// A separate client for requests outside the session, like signing in
+    /// or checking a server. It shares the token manager, so its refreshes
+    /// wait for the session's and reach the same subscribers.
+    pub fn detached(&self) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ClientInner::default())),
+            tokens: self.tokens.clone(),
         }
     }
 
     pub fn set_server(&self, url: &str) {
         let mut inner = self.inner.lock().unwrap();
         inner.base_url = url.trim_end_matches('/').to_string();
+        inner.connection = connection::load(&inner.base_url);
+        inner.client = build_http_client(trust::load(&inner.base_url).as_ref(), &inner.connection);
+    }
+
+    /// Rebuilds the HTTP client after the user changed what they trust or
+    /// how to connect to the server
+    pub fn reload_http_client(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.connection = connection::load(&inner.base_url);
+        inner.client = build_http_client(trust::load(&inner.base_url).as_ref(), &inner.connection);
+    }
+
+    /// Proxy, headers and client certificate for the current server, for
+    /// the audio stream and the OAuth view
+    pub fn connection(&self) -> ConnectionSettings {
+        self.inner.lock().unwrap().connection.clone()
     }
 
     pub fn server_url(&self) -> String {
@@ -85,6 +129,7 @@ impl AudiobookshelfClient {
 
     pub fn set_tokens(&self, access_token: &str, refresh_token: &str) {
         let mut inner = self.inner.lock().unwrap();
+        inner.api_key = false;
         inner.access_token = if access_token.is_empty() {
             None
         } else {
@@ -97,6 +142,19 @@ impl AudiobookshelfClient {
         };
     }
 
+    /// Authenticates with an API key instead of a session. Keys don't
+    /// rotate, so a rejected key ends the session instead of a refresh.
+    pub fn set_api_key(&self, key: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.access_token = (!key.is_empty()).then(|| key.to_string());
+        inner.refresh_token = None;
+        inner.api_key = true;
+    }
+
+    pub fn uses_api_key(&self) -> bool {
+        self.inner.lock().unwrap().api_key
+    }
+
     pub fn token(&self) -> Option<String> {
         self.access_token()
     }
@@ -111,6 +169,12 @@ impl AudiobookshelfClient {
         inner.refresh_token.clone()
     }
 
+    /// Refreshes and expiries, for persisting the tokens and asking the
+    /// user to sign in again.
+    pub fn token_events(&self) -> async_channel::Receiver<TokenEvent> {
+        self.tokens.subscribe()
+    }
+
     pub fn is_authenticated(&self) -> bool {
         let inner = self.inner.lock().unwrap();
         inner.access_token.is_some()
@@ -132,7 +196,7 @@ impl AudiobookshelfClient {
             .header("x-return-tokens", "true")
             .json(&body)
             .send()
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .map_err(ApiError::from_send)?;
 
         if resp.status().is_success() {
             let login_resp: LoginResponse =
@@ -146,6 +210,91 @@ impl AudiobookshelfClient {
         }
     }
 
+    /// GET /auth/openid — starts an OpenID sign-in that ends at
+    /// `redirect_uri` with a code for `openid_callback`. The server keeps
+    /// the PKCE challenge in its session, so the request is made here
+    /// rather than in the browser: this returns the identity provider's
+    /// URL to open and the session cookie the callback has to send.
+    pub fn openid_authorize(
+        &self,
+        code_challenge: &str,
+        redirect_uri: &str,
+        state: &str,
+    ) -> Result<(String, String), ApiError> {
+        let (base_url, connection) = {
+            let inner = self.inner.lock().unwrap();
+            (inner.base_url.clone(), inner.connection.clone())
+        };
+        let client = http_client_builder(trust::load(&base_url).as_ref(), &connection)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let url = format!(
+            "{}/auth/openid?code_challenge={}&code_challenge_method=S256&redirect_uri={}&client_id={}&response_type=code&state={}",
+            base_url,
+            glib::Uri::escape_string(code_challenge, None, false),
+            glib::Uri::escape_string(redirect_uri, None, false),
+            glib::Uri::escape_string("Shelfily Desktop", None, false),
+            glib::Uri::escape_string(state, None, false),
+        );
+
+        let resp = client.get(&url).send().map_err(ApiError::from_send)?;
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|location| resp.url().join(location).ok());
+        let Some(location) = location.filter(|_| resp.status().is_redirection()) else {
+            return Err(ApiError::Auth(format!(
+                "OpenID sign-in failed: HTTP {}",
+                resp.status()
+            )));
+        };
+        let cookie = resp
+            .headers()
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Ok((location.to_string(), cookie))
+    }
+
+    /// Exchanges the code from an OpenID redirect for a session
+    /// GET /auth/openid/callback
+    pub fn openid_callback(
+        &self,
+        state: &str,
+        code: &str,
+        code_verifier: &str,
+        cookie: &str,
+    ) -> Result<LoginResponse, ApiError> {
+        let (client, base_url, _, _) = self.connection_info();
+        let url = format!(
+            "{}/auth/openid/callback?state={}&code={}&code_verifier={}",
+            base_url,
+            glib::Uri::escape_string(state, None, false),
+            glib::Uri::escape_string(code, None, false),
+            glib::Uri::escape_string(code_verifier, None, false),
+        );
+
+        let mut req = client.get(&url).header("x-return-tokens", "true");
+        if !cookie.is_empty() {
+            req = req.header(reqwest::header::COOKIE, cookie);
+        }
+        let resp = req.send().map_err(ApiError::from_send)?;
+
+        if resp.status().is_success() {
+            resp.json().map_err(|e| ApiError::Parse(e.to_string()))
+        } else {
+            Err(ApiError::Auth(format!(
+                "OpenID sign-in failed: HTTP {}",
+                resp.status()
+            )))
+        }
+    }
+
     /// GET /status — check server status and available auth methods
     pub fn get_status(&self) -> Result<ServerStatus, ApiError> {
         let (client, base_url, _, _) = self.connection_info();
@@ -153,7 +302,7 @@ impl AudiobookshelfClient {
         let resp = client
             .get(&url)
             .send()
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .map_err(ApiError::from_send)?;
         if resp.status().is_success() {
             let status: ServerStatus = resp.json().map_err(|e| ApiError::Parse(e.to_string()))?;
             Ok(status)
@@ -183,14 +332,27 @@ impl AudiobookshelfClient {
 
     /// GET /api/libraries/:id/items — fetches all items with pagination
     pub fn get_library_items(&self, library_id: &str) -> Result<Vec<LibraryItem>, ApiError> {
+        self.get_library_items_filtered(library_id, None)
+    }
+
+    /// Same as `get_library_items`, narrowed server-side by a `filter=` value
+    /// of the form `<group>.<base64 value>` (e.g. `genres.RmFudGFzeQ==`).
+    pub fn get_library_items_filtered(
+        &self,
+        library_id: &str,
+        filter: Option<&str>,
+    ) -> Result<Vec<LibraryItem>, ApiError> {
         let mut all_items: Vec<LibraryItem> = Vec::new();
         let page_size = 100;
         let mut offset = 0;
+        let filter_query = filter
+            .map(|f| format!("&filter={}", glib::Uri::escape_string(f, None, false)))
+            .unwrap_or_default();
 
         loop {
             let resp: serde_json::Value = self.get(&format!(
-                "/api/libraries/{}/items?limit={}&offset={}&minified=0&include=progress",
-                library_id, page_size, offset
+                "/api/libraries/{}/items?limit={}&offset={}&minified=0&include=progress{}",
+                library_id, page_size, offset, filter_query
             ))?;
 
             let total = resp.get("total").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
@@ -227,6 +389,11 @@ impl AudiobookshelfClient {
         Ok(all_items)
     }
 
+    /// GET /api/libraries/:id/filterdata — genres, tags, narrators, ... in a library
+    pub fn get_library_filter_data(&self, library_id: &str) -> Result<LibraryFilterData, ApiError> {
+        self.get(&format!("/api/libraries/{}/filterdata", library_id))
+    }
+
     /// GET /api/items/:id?expanded=1
     pub fn get_library_item(&self, item_id: &str) -> Result<LibraryItemExpanded, ApiError> {
         let resp: LibraryItemExpanded = self.get(&format!(
@@ -315,14 +482,14 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}/api/me/progress/{}", base_url, item_id);
             let mut req = client.get(&url);
             if let Some(ref t) = access_token {
                 req = req.header("Authorization", format!("Bearer {}", t));
             }
 
-            let resp = req.send().map_err(|e| ApiError::Network(e.to_string()))?;
+            let resp = req.send().map_err(ApiError::from_send)?;
             let status = resp.status();
             if status == 404 {
                 return Ok(None);
@@ -334,7 +501,7 @@ impl AudiobookshelfClient {
             }
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -354,14 +521,79 @@ impl AudiobookshelfClient {
         self.execute_empty_patch(&format!("/api/me/progress/{}", item_id), &body)
     }
 
+    /// PATCH /api/me/progress/:id — move an item's listening position
+    pub fn set_progress_position(
+        &self,
+        item_id: &str,
+        current_time: f64,
+        duration: f64,
+    ) -> Result<(), ApiError> {
+        let progress = if duration > 0.0 {
+            (current_time / duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let body = serde_json::json!({
+            "currentTime": current_time,
+            "duration": duration,
+            "progress": progress,
+            "isFinished": false,
+        });
+        self.execute_empty_patch(&format!("/api/me/progress/{}", item_id), &body)
+    }
+
+    /// PATCH /api/me/progress/:id — write back progress saved before a reset,
+    /// a position change or hiding from Continue Listening
+    pub fn restore_media_progress(
+        &self,
+        item_id: &str,
+        saved: &MediaProgress,
+    ) -> Result<(), ApiError> {
+        let body = serde_json::json!({
+            "currentTime": saved.current_time.unwrap_or(0.0),
+            "duration": saved.duration.unwrap_or(0.0),
+            "progress": saved.progress.unwrap_or(0.0),
+            "isFinished": saved.is_finished.unwrap_or(false),
+            "hideFromContinueListening": saved.hide_from_continue_listening.unwrap_or(false),
+        });
+        self.execute_empty_patch(&format!("/api/me/progress/{}", item_id), &body)
+    }
+
+    /// DELETE /api/me/progress/:id — forget all progress of an item
+    pub fn delete_media_progress(&self, progress_id: &str) -> Result<(), ApiError> {
+        self.execute_empty_delete(&format!("/api/me/progress/{}", progress_id))
+    }
+
+    /// GET /api/me/progress/:id/remove-from-continue-listening — hide an item
+    /// from the Continue Listening shelf without touching its progress
+    pub fn remove_from_continue_listening(&self, progress_id: &str) -> Result<(), ApiError> {
+        let _: serde_json::Value = self.get(&format!(
+            "/api/me/progress/{}/remove-from-continue-listening",
+            progress_id
+        ))?;
+        Ok(())
+    }
+
     /// GET /api/me — fetches the current user, used to read bookmarks
     pub fn get_me(&self) -> Result<User, ApiError> {
         self.get("/api/me")
     }
 
-    /// GET /api/authors/:id?include=items — fetches an author with their library items
+    /// GET /api/authors/:id?include=items,series — fetches an author with their library items
     pub fn get_author_with_items(&self, author_id: &str) -> Result<AuthorExpanded, ApiError> {
-        self.get(&format!("/api/authors/{}?include=items", author_id))
+        self.get(&format!("/api/authors/{}?include=items,series", author_id))
+    }
+
+    /// GET /api/libraries/:id/authors — every author in a library, with book counts
+    pub fn get_library_authors(&self, library_id: &str) -> Result<Vec<AuthorExpanded>, ApiError> {
+        let resp: LibraryAuthorsResponse =
+            self.get(&format!("/api/libraries/{}/authors", library_id))?;
+        Ok(resp.authors)
+    }
+
+    /// Download an author's image bytes
+    pub fn download_author_image(&self, author_id: &str) -> Result<Vec<u8>, ApiError> {
+        self.execute_json_bytes(&format!("/api/authors/{}/image?width=400", author_id))
     }
 
     /// Returns the current user's bookmarks filtered by libraryItemId, sorted by time.
@@ -416,6 +648,393 @@ impl AudiobookshelfClient {
         self.execute_empty_delete(&path)
     }
 
+    // ─── Metadata editing (requires update permission) ─────────────────────
+
+    /// PATCH /api/items/:id/media — update book metadata and tags
+    pub fn update_item_media(&self, item_id: &str, update: &MediaUpdate) -> Result<(), ApiError> {
+        let body = serde_json::to_value(update).map_err(|e| ApiError::Parse(e.to_string()))?;
+        self.execute_empty_patch(&format!("/api/items/{}/media", item_id), &body)
+    }
+
+    /// POST /api/items/:id/chapters — replace the chapter list
+    pub fn update_item_chapters(
+        &self,
+        item_id: &str,
+        chapters: &[Chapter],
+    ) -> Result<(), ApiError> {
+        let body = serde_json::json!({ "chapters": chapters });
+        self.execute_empty_post(&format!("/api/items/{}/chapters", item_id), &body)
+    }
+
+    /// POST /api/items/:id/cover — upload a local image as the item cover
+    pub fn upload_cover(
+        &self,
+        item_id: &str,
+        filename: &str,
+        mime_type: &str,
+        bytes: &[u8],
+    ) -> Result<(), ApiError> {
+        let mut form = MultipartForm::new();
+        form.file("cover", filename, mime_type, bytes);
+        self.execute_multipart_post(&format!("/api/items/{}/cover", item_id), &form.finish())
+    }
+
+    /// POST /api/items/:id/cover — let the server download a cover from a URL
+    pub fn set_cover_from_url(&self, item_id: &str, url: &str) -> Result<(), ApiError> {
+        let body = serde_json::json!({ "url": url });
+        self.execute_empty_post(&format!("/api/items/{}/cover", item_id), &body)
+    }
+
+    /// GET /api/search/providers — book metadata providers configured on the server.
+    /// Older servers lack the endpoint; fall back to the providers they ship with.
+    pub fn get_book_providers(&self) -> Result<Vec<MetadataProvider>, ApiError> {
+        match self.get::<serde_json::Value>("/api/search/providers") {
+            Ok(resp) => {
+                let books_val = resp
+                    .get("providers")
+                    .and_then(|p| p.get("books"))
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Array(vec![]));
+                serde_json::from_value(books_val).map_err(|e| ApiError::Parse(e.to_string()))
+            }
+            Err(ApiError::Server(_)) => Ok(DEFAULT_BOOK_PROVIDERS
+                .iter()
+                .map(|(value, text)| MetadataProvider {
+                    value: value.to_string(),
+                    text: text.to_string(),
+                })
+                .collect()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// GET /api/search/books — look up a book with one metadata provider
+    pub fn search_books(
+        &self,
+        provider: &str,
+        title: &str,
+        author: &str,
+    ) -> Result<Vec<BookMatch>, ApiError> {
+        let path = format!(
+            "/api/search/books?provider={}&title={}&author={}",
+            glib::Uri::escape_string(provider, None, false),
+            glib::Uri::escape_string(title, None, true),
+            glib::Uri::escape_string(author, None, true),
+        );
+        self.get(&path)
+    }
+
+    // ─── Uploads (requires upload permission) ──────────────────────────────
+
+    /// POST /api/upload — stream one file into `<folder>/<author>/<series>/<title>`.
+    /// Transient failures are retried; `cancel` aborts between and during attempts.
+    pub fn upload_file(
+        &self,
+        request: &UploadRequest,
+        path: &Path,
+        mime_type: &str,
+        progress: Arc<dyn Fn(u64, u64) + Send + Sync>,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(), ApiError> {
+        let mut attempt = 0;
+        let mut attempted_refresh = false;
+
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(ApiError::Cancelled);
+            }
+            attempt += 1;
+
+            let (_, _, access_token, _) = self.request_connection_info();
+            match self.send_upload(
+                request,
+                path,
+                mime_type,
+                access_token.as_deref(),
+                progress.clone(),
+                cancel.clone(),
+            ) {
+                Ok(()) => return Ok(()),
+                Err(ApiError::Auth(err)) if !attempted_refresh => {
+                    attempted_refresh = true;
+                    attempt -= 1;
+                    if !self.refresh_access_token(access_token.as_deref())? {
+                        return Err(ApiError::Auth(err));
+                    }
+                }
+                Err(err @ (ApiError::Network(_) | ApiError::Server(_)))
+                    if attempt < UPLOAD_ATTEMPTS =>
+                {
+                    log::warn!(
+                        "Upload of {} failed (attempt {}/{}): {}",
+                        path.display(),
+                        attempt,
+                        UPLOAD_ATTEMPTS,
+                        err
+                    );
+                    // Back off 2s, 4s, ... while still reacting to cancellation.
+                    for _ in 0..(2 * attempt * 10) {
+                        if cancel.load(Ordering::Relaxed) {
+                            return Err(ApiError::Cancelled);
+                        }
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn send_upload(
+        &self,
+        request: &UploadRequest,
+        path: &Path,
+        mime_type: &str,
+        access_token: Option<&str>,
+        progress: Arc<dyn Fn(u64, u64) + Send + Sync>,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<(), ApiError> {
+        let file = File::open(path).map_err(|e| ApiError::Io(e.to_string()))?;
+        let file_len = file
+            .metadata()
+            .map_err(|e| ApiError::Io(e.to_string()))?
+            .len();
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "upload".to_string());
+
+        let mut form = MultipartForm::new();
+        form.text("title", &request.title);
+        form.text("author", &request.author);
+        form.text("series", &request.series);
+        form.text("library", &request.library_id);
+        form.text("folder", &request.folder_id);
+        let content_type = form.content_type();
+        let (stream, total) = form.into_file_stream("0", &filename, mime_type, file, file_len);
+        let reader = ProgressReader {
+            inner: stream,
+            sent: 0,
+            total,
+            progress,
+            cancel: cancel.clone(),
+        };
+
+        let (client, base_url, _, _) = self.connection_info();
+        let mut req = client
+            .post(format!("{}/api/upload", base_url))
+            .header("Content-Type", content_type)
+            // The client-wide 30s timeout is far too short for large audio files.
+            .timeout(std::time::Duration::from_secs(6 * 60 * 60))
+            .body(reqwest::blocking::Body::sized(reader, total));
+        if let Some(token) = access_token {
+            req = req.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let resp = req.send().map_err(|e| {
+            if cancel.load(Ordering::Relaxed) {
+                ApiError::Cancelled
+            } else {
+                ApiError::from_send(e)
+            }
+        })?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        if status.as_u16() == 401 || status.as_u16() == 403 {
+            return Err(ApiError::Auth(format!("HTTP {}", status)));
+        }
+        Err(ApiError::Server(format!("HTTP {}", status)))
+    }
+
+    // ─── Collections & playlists ───────────────────────────────────────────
+
+    /// GET /api/libraries/:id/collections
+    pub fn get_collections(&self, library_id: &str) -> Result<Vec<Collection>, ApiError> {
+        let resp: CollectionsResponse =
+            self.get(&format!("/api/libraries/{}/collections", library_id))?;
+        Ok(resp.results)
+    }
+
+    /// POST /api/collections — create a collection holding the given books
+    /// (requires update permission)
+    pub fn create_collection(
+        &self,
+        library_id: &str,
+        name: &str,
+        item_ids: &[String],
+    ) -> Result<Collection, ApiError> {
+        let body = serde_json::json!({
+            "libraryId": library_id,
+            "name": name,
+            "books": item_ids,
+        });
+        self.post("/api/collections", &body)
+    }
+
+    /// GET /api/collections/:id — a collection with its books
+    pub fn get_collection(&self, collection_id: &str) -> Result<Collection, ApiError> {
+        self.get(&format!("/api/collections/{}", collection_id))
+    }
+
+    /// POST /api/collections/:id/batch/add
+    pub fn add_to_collection(
+        &self,
+        collection_id: &str,
+        item_ids: &[String],
+    ) -> Result<(), ApiError> {
+        let body = serde_json::json!({ "books": item_ids });
+        self.execute_empty_post(
+            &format!("/api/collections/{}/batch/add", collection_id),
+            &body,
+        )
+    }
+
+    /// GET /api/libraries/:id/playlists — the current user's playlists
+    pub fn get_playlists(&self, library_id: &str) -> Result<Vec<Playlist>, ApiError> {
+        let resp: PlaylistsResponse =
+            self.get(&format!("/api/libraries/{}/playlists", library_id))?;
+        Ok(resp.results)
+    }
+
+    /// POST /api/playlists — create a playlist holding the given books
+    pub fn create_playlist(
+        &self,
+        library_id: &str,
+        name: &str,
+        item_ids: &[String],
+    ) -> Result<Playlist, ApiError> {
+        let body = serde_json::json!({
+            "libraryId": library_id,
+            "name": name,
+            "items": playlist_items(item_ids),
+        });
+        self.post("/api/playlists", &body)
+    }
+
+    /// POST /api/playlists/:id/batch/add
+    pub fn add_to_playlist(&self, playlist_id: &str, item_ids: &[String]) -> Result<(), ApiError> {
+        let body = serde_json::json!({ "items": playlist_items(item_ids) });
+        self.execute_empty_post(&format!("/api/playlists/{}/batch/add", playlist_id), &body)
+    }
+
+    // ─── Downloads (requires download permission) ──────────────────────────
+
+    /// GET /api/items/:id/file/:ino/download — streams one file of an item to
+    /// `dest`, reporting the bytes written so far. The file only appears at
+    /// `dest` once complete.
+    pub fn download_item_file(
+        &self,
+        item_id: &str,
+        ino: &str,
+        dest: &Path,
+        progress: &dyn Fn(u64),
+        cancel: &AtomicBool,
+    ) -> Result<u64, ApiError> {
+        let mut attempted_refresh = false;
+
+        loop {
+            let (client, base_url, access_token, _) = self.request_connection_info();
+            let mut req = client
+                .get(format!(
+                    "{}/api/items/{}/file/{}/download",
+                    base_url, item_id, ino
+                ))
+                .timeout(std::time::Duration::from_secs(6 * 60 * 60));
+            if let Some(token) = access_token.as_deref() {
+                req = req.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let mut resp = req.send().map_err(ApiError::from_send)?;
+            let status = resp.status();
+
+            if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
+                attempted_refresh = true;
+                if self.refresh_access_token(access_token.as_deref())? {
+                    continue;
+                }
+                return Err(ApiError::Auth(format!("HTTP {}", status)));
+            }
+            if status.as_u16() == 401 || status.as_u16() == 403 {
+                return Err(ApiError::Auth(format!("HTTP {}", status)));
+            }
+            if !status.is_success() {
+                return Err(ApiError::Server(format!("HTTP {}", status)));
+            }
+
+            let mut partial = dest.as_os_str().to_owned();
+            partial.push(".part");
+            let partial = std::path::PathBuf::from(partial);
+            let result = write_download(&mut resp, &partial, dest, progress, cancel);
+            if result.is_err() {
+                let _ = std::fs::remove_file(&partial);
+            }
+            return result;
+        }
+    }
+
+    // ─── Administration (admin/root only) ──────────────────────────────────
+
+    /// POST /api/libraries/:id/scan — queue a library scan on the server
+    pub fn scan_library(&self, library_id: &str, force: bool) -> Result<(), ApiError> {
+        let path = if force {
+            format!("/api/libraries/{}/scan?force=1", library_id)
+        } else {
+            format!("/api/libraries/{}/scan", library_id)
+        };
+        self.execute_empty_post(&path, &serde_json::json!({}))
+    }
+
+    /// GET /api/tasks — running and recently finished background tasks
+    pub fn get_tasks(&self) -> Result<Vec<ServerTask>, ApiError> {
+        let resp: serde_json::Value = self.get("/api/tasks")?;
+        let tasks_val = resp
+            .get("tasks")
+            .cloned()
+            .unwrap_or(serde_json::Value::Array(vec![]));
+        serde_json::from_value(tasks_val).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// GET /api/users
+    pub fn get_users(&self) -> Result<Vec<User>, ApiError> {
+        let resp: serde_json::Value = self.get("/api/users")?;
+        let users_val = resp
+            .get("users")
+            .cloned()
+            .unwrap_or(serde_json::Value::Array(vec![]));
+        serde_json::from_value(users_val).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// POST /api/users — create a user account
+    pub fn create_user(&self, user: &UserUpdate) -> Result<User, ApiError> {
+        let body = serde_json::to_value(user).map_err(|e| ApiError::Parse(e.to_string()))?;
+        let resp: serde_json::Value = self.post("/api/users", &body)?;
+        let user_val = resp.get("user").cloned().unwrap_or(resp);
+        serde_json::from_value(user_val).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
+    /// PATCH /api/users/:id
+    pub fn update_user(&self, user_id: &str, update: &UserUpdate) -> Result<(), ApiError> {
+        let body = serde_json::to_value(update).map_err(|e| ApiError::Parse(e.to_string()))?;
+        self.execute_empty_patch(&format!("/api/users/{}", user_id), &body)
+    }
+
+    /// DELETE /api/users/:id
+    pub fn delete_user(&self, user_id: &str) -> Result<(), ApiError> {
+        self.execute_empty_delete(&format!("/api/users/{}", user_id))
+    }
+
+    /// GET /api/logger-data — today's server log lines
+    pub fn get_server_logs(&self) -> Result<Vec<LogEntry>, ApiError> {
+        let resp: serde_json::Value = self.get("/api/logger-data")?;
+        let logs_val = resp
+            .get("currentDailyLogs")
+            .cloned()
+            .unwrap_or(serde_json::Value::Array(vec![]));
+        serde_json::from_value(logs_val).map_err(|e| ApiError::Parse(e.to_string()))
+    }
+
     /// Helper: extract base_url, token, and client clone from the inner lock
     fn connection_info(&self) -> (Client, String, Option<String>, Option<String>) {
         let inner = self.inner.lock().unwrap();
@@ -438,13 +1057,44 @@ impl AudiobookshelfClient {
             return false;
         }
 
-        let refresh_token = login_resp.user.refresh_token.as_deref().unwrap_or_default();
-        self.set_tokens(access_token, refresh_token);
+        // Without rotation the server doesn't send a new refresh token
+        match login_resp.user.refresh_token.as_deref() {
+            Some(refresh_token) if !refresh_token.is_empty() => {
+                self.set_tokens(access_token, refresh_token)
+            }
+            _ => self.set_access_token(access_token),
+        }
         true
     }
 
-    fn refresh_access_token(&self) -> Result<bool, ApiError> {
-        let (client, base_url, _, refresh_token) = self.connection_info();
+    /// Like `connection_info`, but refreshes first when the access token is
+    /// about to expire, so requests don't have to fail before a refresh.
+    fn request_connection_info(&self) -> (Client, String, Option<String>, Option<String>) {
+        if self.uses_api_key() {
+            return self.connection_info();
+        }
+        if let Some(token) = self.access_token() {
+            if tokens::expires_soon(&token) {
+                if let Err(e) = self.refresh_access_token(Some(&token)) {
+                    log::warn!("Proactive token refresh failed: {}", e);
+                }
+            }
+        }
+        self.connection_info()
+    }
+
+    /// Exchanges the refresh token for a new pair. `failed_token` is the
+    /// access token a request was rejected with; when another thread has
+    /// replaced it in the meantime, its result is used instead.
+    fn refresh_access_token(&self, failed_token: Option<&str>) -> Result<bool, ApiError> {
+        if self.uses_api_key() {
+            return self.check_api_key();
+        }
+        let _guard = self.tokens.lock_refresh();
+        let (client, base_url, access_token, refresh_token) = self.connection_info();
+        if failed_token.is_some() && access_token.as_deref() != failed_token {
+            return Ok(access_token.is_some());
+        }
         let Some(refresh_token) = refresh_token else {
             return Ok(false);
         };
@@ -459,19 +1109,57 @@ impl AudiobookshelfClient {
             .header("x-refresh-token", &refresh_token)
             .header("x-return-tokens", "true")
             .send()
-            .map_err(|e| ApiError::Network(e.to_string()))?;
+            .map_err(ApiError::from_send)?;
 
         if resp.status().is_success() {
             let login_resp: LoginResponse =
                 resp.json().map_err(|e| ApiError::Parse(e.to_string()))?;
-            Ok(self.apply_refreshed_tokens(&login_resp))
+            let refreshed = self.apply_refreshed_tokens(&login_resp);
+            if refreshed {
+                log::info!("Access token refreshed");
+                self.tokens.emit(TokenEvent::Refreshed {
+                    server_url: base_url,
+                    access_token: self.access_token().unwrap_or_default(),
+                    refresh_token: self.refresh_token().unwrap_or_default(),
+                });
+            }
+            Ok(refreshed)
         } else if resp.status().as_u16() == 401 || resp.status().as_u16() == 403 {
+            log::warn!(
+                "Refresh token rejected ({}), session expired",
+                resp.status()
+            );
+            self.tokens.emit(TokenEvent::Expired {
+                server_url: base_url,
+            });
             Ok(false)
         } else {
             Err(ApiError::Server(format!("HTTP {}", resp.status())))
         }
     }
 
+    /// API keys can't be refreshed. When a request is rejected, asks
+    /// /api/me whether the key itself was revoked or has expired, rather
+    /// than only lacking a permission, and reports the session as expired.
+    fn check_api_key(&self) -> Result<bool, ApiError> {
+        let (client, base_url, api_key, _) = self.connection_info();
+        let resp = client
+            .get(format!("{}/api/me", base_url))
+            .header(
+                "Authorization",
+                format!("Bearer {}", api_key.unwrap_or_default()),
+            )
+            .send()
+            .map_err(ApiError::from_send)?;
+        if resp.status().as_u16() == 401 {
+            log::warn!("API key rejected, session expired");
+            self.tokens.emit(TokenEvent::Expired {
+                server_url: base_url,
+            });
+        }
+        Ok(false)
+    }
+
     fn execute_json<T, F>(&self, path: &str, send: F) -> Result<T, ApiError>
     where
         T: serde::de::DeserializeOwned,
@@ -480,10 +1168,10 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}{}", base_url, path);
             let resp = send(&client, &url, access_token.as_deref())
-                .map_err(|e| ApiError::Network(e.to_string()))?;
+                .map_err(ApiError::from_send)?;
             let status = resp.status();
 
             if status.is_success() {
@@ -493,7 +1181,7 @@ impl AudiobookshelfClient {
 
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -512,34 +1200,23 @@ impl AudiobookshelfClient {
         self.execute_json_bytes(&format!("/api/items/{}/cover?width=400", item_id))
     }
 
-    /// Build audio stream URL for a track
+    /// Build audio stream URL for a track. The URL carries no credentials;
+    /// the player sends `authorization_header` with the request instead.
     pub fn audio_stream_url(&self, content_url: &str) -> String {
         let inner = self.inner.lock().unwrap();
-        let token = inner.access_token.as_deref().unwrap_or("");
-        let base = if content_url.starts_with("http://") || content_url.starts_with("https://") {
+        if content_url.starts_with("http://") || content_url.starts_with("https://") {
             content_url.to_string()
         } else {
             format!("{}{}", inner.base_url, content_url)
-        };
-
-        if token.is_empty() {
-            return base;
         }
-
-        let separator = if base.contains('?') { '&' } else { '?' };
-        format!("{base}{separator}token={token}")
     }
 
-    /// Public cover URL (token-authenticated) for use as MPRIS art etc.
-    pub fn cover_url(&self, item_id: &str) -> String {
-        let inner = self.inner.lock().unwrap();
-        let token = inner.access_token.as_deref().unwrap_or("");
-        let base = format!("{}/api/items/{}/cover?width=400", inner.base_url, item_id);
-        if token.is_empty() {
-            base
-        } else {
-            format!("{base}&token={token}")
-        }
+    /// `Authorization` header value for requests made outside this client,
+    /// such as GStreamer's HTTP source.
+    pub fn authorization_header(&self) -> Option<String> {
+        self.access_token()
+            .filter(|token| !token.is_empty())
+            .map(|token| format!("Bearer {}", token))
     }
 
     /// Generic GET request
@@ -572,14 +1249,14 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}{}", base_url, path);
             let mut req = client.get(&url);
             if let Some(token) = access_token.as_deref() {
                 req = req.header("Authorization", format!("Bearer {}", token));
             }
 
-            let resp = req.send().map_err(|e| ApiError::Network(e.to_string()))?;
+            let resp = req.send().map_err(ApiError::from_send)?;
             let status = resp.status();
 
             if status.is_success() {
@@ -589,7 +1266,7 @@ impl AudiobookshelfClient {
 
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -607,7 +1284,7 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}{}", base_url, path);
             let mut req = client.patch(&url);
             if let Some(token) = access_token.as_deref() {
@@ -617,7 +1294,7 @@ impl AudiobookshelfClient {
             let resp = req
                 .json(body)
                 .send()
-                .map_err(|e| ApiError::Network(e.to_string()))?;
+                .map_err(ApiError::from_send)?;
             let status = resp.status();
 
             if status.is_success() {
@@ -626,7 +1303,7 @@ impl AudiobookshelfClient {
 
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -644,14 +1321,14 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}{}", base_url, path);
             let mut req = client.delete(&url);
             if let Some(token) = access_token.as_deref() {
                 req = req.header("Authorization", format!("Bearer {}", token));
             }
 
-            let resp = req.send().map_err(|e| ApiError::Network(e.to_string()))?;
+            let resp = req.send().map_err(ApiError::from_send)?;
             let status = resp.status();
 
             if status.is_success() {
@@ -660,7 +1337,7 @@ impl AudiobookshelfClient {
 
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -678,7 +1355,7 @@ impl AudiobookshelfClient {
         let mut attempted_refresh = false;
 
         loop {
-            let (client, base_url, access_token, _) = self.connection_info();
+            let (client, base_url, access_token, _) = self.request_connection_info();
             let url = format!("{}{}", base_url, path);
             let mut req = client.post(&url);
             if let Some(token) = access_token.as_deref() {
@@ -688,7 +1365,43 @@ impl AudiobookshelfClient {
             let resp = req
                 .json(body)
                 .send()
-                .map_err(|e| ApiError::Network(e.to_string()))?;
+                .map_err(ApiError::from_send)?;
+            let status = resp.status();
+
+            if status.is_success() {
+                return Ok(());
+            }
+
+            if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
+                attempted_refresh = true;
+                if self.refresh_access_token(access_token.as_deref())? {
+                    continue;
+                }
+                return Err(ApiError::Auth(format!("HTTP {}", status)));
+            }
+
+            if status.as_u16() == 401 || status.as_u16() == 403 {
+                return Err(ApiError::Auth(format!("HTTP {}", status)));
+            }
+
+            return Err(ApiError::Server(format!("HTTP {}", status)));
+        }
+    }
+    fn execute_multipart_post(&self, path: &str, form: &MultipartForm) -> Result<(), ApiError> {
+        let mut attempted_refresh = false;
+
+        loop {
+            let (client, base_url, access_token, _) = self.request_connection_info();
+            let url = format!("{}{}", base_url, path);
+            let mut req = client
+                .post(&url)
+                .header("Content-Type", form.content_type())
+                .body(form.body.clone());
+            if let Some(token) = access_token.as_deref() {
+                req = req.header("Authorization", format!("Bearer {}", token));
+            }
+
+            let resp = req.send().map_err(ApiError::from_send)?;
             let status = resp.status();
 
             if status.is_success() {
@@ -697,7 +1410,7 @@ impl AudiobookshelfClient {
 
             if (status.as_u16() == 401 || status.as_u16() == 403) && !attempted_refresh {
                 attempted_refresh = true;
-                if self.refresh_access_token()? {
+                if self.refresh_access_token(access_token.as_deref())? {
                     continue;
                 }
                 return Err(ApiError::Auth(format!("HTTP {}", status)));
@@ -712,6 +1425,232 @@ impl AudiobookshelfClient {
     }
 }
 
+const UPLOAD_ATTEMPTS: u32 = 3;
+
+/// Providers bundled with Audiobookshelf, used when the server cannot list them
+const DEFAULT_BOOK_PROVIDERS: [(&str, &str); 6] = [
+    ("google", "Google Books"),
+    ("openlibrary", "Open Library"),
+    ("itunes", "iTunes"),
+    ("audible", "Audible.com"),
+    ("audible.uk", "Audible.co.uk"),
+    ("fantlab", "FantLab.ru"),
+];
+
+/// Minimal multipart/form-data encoder (reqwest's multipart support is not enabled)
+struct MultipartForm {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartForm {
+    fn new() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Self {
+            boundary: format!("----ShelfilyFormBoundary{:x}", nanos),
+            body: Vec::new(),
+        }
+    }
+
+    fn text(&mut self, name: &str, value: &str) {
+        self.body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                self.boundary,
+                escape_form_name(name),
+                value
+            )
+            .as_bytes(),
+        );
+    }
+
+    fn file(&mut self, name: &str, filename: &str, mime_type: &str, bytes: &[u8]) {
+        self.file_header(name, filename, mime_type);
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    fn file_header(&mut self, name: &str, filename: &str, mime_type: &str) {
+        self.body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                self.boundary,
+                escape_form_name(name),
+                escape_form_name(filename),
+                mime_type
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Finishes the form with a file part read from `file` instead of memory.
+    /// Returns the body reader and its total length.
+    fn into_file_stream(
+        mut self,
+        name: &str,
+        filename: &str,
+        mime_type: &str,
+        file: File,
+        file_len: u64,
+    ) -> (impl Read + Send + 'static, u64) {
+        self.file_header(name, filename, mime_type);
+        let tail = format!("\r\n--{}--\r\n", self.boundary).into_bytes();
+        let total = self.body.len() as u64 + file_len + tail.len() as u64;
+        let stream = Cursor::new(self.body)
+            .chain(file.take(file_len))
+            .chain(Cursor::new(tail));
+        (stream, total)
+    }
+
+    fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    fn finish(mut self) -> Self {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self
+    }
+}
+
+/// Streams a download into `partial` and moves it to `dest` once complete.
+/// The caller removes `partial` when this fails.
+fn write_download(
+    resp: &mut reqwest::blocking::Response,
+    partial: &Path,
+    dest: &Path,
+    progress: &dyn Fn(u64),
+    cancel: &AtomicBool,
+) -> Result<u64, ApiError> {
+    let mut file = File::create(partial).map_err(|e| ApiError::Io(e.to_string()))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut written = 0u64;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(ApiError::Cancelled);
+        }
+        let n = match resp.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) => return Err(ApiError::Network(e.to_string())),
+        };
+        file.write_all(&buf[..n])
+            .map_err(|e| ApiError::Io(e.to_string()))?;
+        written += n as u64;
+        progress(written);
+    }
+    file.sync_all().map_err(|e| ApiError::Io(e.to_string()))?;
+    drop(file);
+    std::fs::rename(partial, dest).map_err(|e| ApiError::Io(e.to_string()))?;
+    Ok(written)
+}
+
+/// Request body wrapper that reports upload progress and aborts on cancellation
+struct ProgressReader<R> {
+    inner: R,
+    sent: u64,
+    total: u64,
+    progress: Arc<dyn Fn(u64, u64) + Send + Sync>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(std::io::Error::other("upload cancelled"));
+        }
+        let n = self.inner.read(buf)?;
+        self.sent += n as u64;
+        (self.progress)(self.sent, self.total);
+        Ok(n)
+    }
+}
+
+/// HTTP client that also trusts what the user accepted for the server.
+/// A pinned server is checked by the fingerprint of the certificate it
+/// presents, whatever host name it was issued for; an imported CA bundle
+/// is added to the system roots.
+fn build_http_client(trust: Option<&ServerTrust>, connection: &ConnectionSettings) -> Client {
+    match try_build_http_client(trust, connection) {
+        Ok(client) => return client,
+        Err(e) => log::warn!(
+            "HTTP client failed, retrying without the client certificate: {}",
+            e
+        ),
+    }
+    let mut connection = connection.clone();
+    connection.client_certificate = None;
+    connection.client_key = None;
+    match try_build_http_client(trust, &connection) {
+        Ok(client) => return client,
+        Err(e) => log::warn!(
+            "HTTP client failed, retrying without trusted certificates: {}",
+            e
+        ),
+    }
+    try_build_http_client(None, &connection).unwrap_or_else(|e| {
+        log::warn!(
+            "HTTP client failed, ignoring the connection settings: {}",
+            e
+        );
+        Client::new()
+    })
+}
+
+fn try_build_http_client(
+    trust: Option<&ServerTrust>,
+    connection: &ConnectionSettings,
+) -> reqwest::Result<Client> {
+    http_client_builder(trust, connection).build()
+}
+
+fn http_client_builder(
+    trust: Option<&ServerTrust>,
+    connection: &ConnectionSettings,
+) -> reqwest::blocking::ClientBuilder {
+    let mut builder =
+        connection.configure(Client::builder().timeout(std::time::Duration::from_secs(30)));
+    if let Some(trust) = trust {
+        match trust::pinned_tls_config(trust, connection) {
+            Ok(Some(config)) => builder = builder.use_preconfigured_tls(config),
+            Ok(None) => {
+                let certs = trust
+                    .ca_bundle
+                    .as_deref()
+                    .map(|pem| reqwest::Certificate::from_pem_bundle(pem.as_bytes()));
+                match certs {
+                    Some(Ok(certs)) => {
+                        for cert in certs {
+                            builder = builder.add_root_certificate(cert);
+                        }
+                    }
+                    Some(Err(e)) => log::warn!("Ignoring unreadable CA bundle: {}", e),
+                    None => {}
+                }
+            }
+            Err(e) => log::warn!("Ignoring unusable pinned certificate: {}", e),
+        }
+    }
+    builder
+}
+
+fn playlist_items(item_ids: &[String]) -> Vec<serde_json::Value> {
+    item_ids
+        .iter()
+        .map(|id| serde_json::json!({ "libraryItemId": id }))
+        .collect()
+}
+
+fn escape_form_name(name: &str) -> String {
+    name.replace('\\', "\\\\")
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
 /// API Error types
 #[derive(Debug)]
 pub enum ApiError {
@@ -719,6 +1658,36 @@ pub enum ApiError {
     Auth(String),
     Parse(String),
     Server(String),
+    Io(String),
+    /// The server's TLS certificate could not be verified
+    Certificate(String),
+    Cancelled,
+}
+
+impl ApiError {
+    /// Classifies a failed request, telling certificate problems apart
+    /// from other network errors so the user can be asked to trust them
+    pub fn from_send(e: reqwest::Error) -> Self {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&e);
+        while let Some(err) = source {
+            let rejected = matches!(
+                err.downcast_ref::<rustls::Error>(),
+                Some(rustls::Error::InvalidCertificate(_))
+            );
+            if rejected || err.is::<native_tls::Error>() {
+                return ApiError::Certificate(err.to_string());
+            }
+            // io::Error skips the error it wraps in source()
+            source = match err
+                .downcast_ref::<std::io::Error>()
+                .and_then(|io| io.get_ref())
+            {
+                Some(inner) => Some(inner),
+                None => err.source(),
+            };
+        }
+        ApiError::Network(e.to_string())
+    }
 }
 
 impl std::fmt::Display for ApiError {
@@ -728,6 +1697,144 @@ impl std::fmt::Display for ApiError {
             ApiError::Auth(e) => write!(f, "Authentication error: {}", e),
             ApiError::Parse(e) => write!(f, "Parse error: {}", e),
             ApiError::Server(e) => write!(f, "Server error: {}", e),
+            ApiError::Io(e) => write!(f, "File error: {}", e),
+            ApiError::Certificate(e) => write!(f, "Certificate error: {}", e),
+            ApiError::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    /// Accepts one request, answers it with `status` and hands back the
+    /// request body
+    fn serve_once(status: &'static str) -> (String, std::thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+            body
+        });
+        (url, handle)
+    }
+
+    fn upload_fixture(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("shelfily-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn upload_request() -> UploadRequest {
+        UploadRequest {
+            library_id: "lib1".to_string(),
+            folder_id: "fold1".to_string(),
+            title: "The Hobbit".to_string(),
+            author: "J. R. R. Tolkien".to_string(),
+            series: String::new(),
+        }
+    }
+
+    #[test]
+    fn upload_streams_multipart_body_and_reports_progress() {
+        let (url, server) = serve_once("200 OK");
+        let client = AudiobookshelfClient::new();
+        client.set_server(&url);
+        client.set_access_token("token");
+        let path = upload_fixture("chapter01.mp3", b"ID3 fake audio");
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let progress: Arc<dyn Fn(u64, u64) + Send + Sync> = {
+            let reports = reports.clone();
+            Arc::new(move |sent, total| reports.lock().unwrap().push((sent, total)))
+        };
+        let result = client.upload_file(
+            &upload_request(),
+            &path,
+            "audio/mpeg",
+            progress,
+            Arc::new(AtomicBool::new(false)),
+        );
+        let body = String::from_utf8(server.join().unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(result.is_ok(), "{:?}", result);
+        assert!(body.contains("name=\"title\"\r\n\r\nThe Hobbit\r\n"));
+        assert!(body.contains("name=\"author\"\r\n\r\nJ. R. R. Tolkien\r\n"));
+        assert!(body.contains("name=\"library\"\r\n\r\nlib1\r\n"));
+        assert!(body.contains("name=\"folder\"\r\n\r\nfold1\r\n"));
+        assert!(body.contains(
+            "name=\"0\"; filename=\"chapter01.mp3\"\r\nContent-Type: audio/mpeg\r\n\r\nID3 fake audio\r\n"
+        ));
+        assert!(body.trim_end().ends_with("--"));
+
+        let reports = reports.lock().unwrap();
+        let total = body.len() as u64;
+        assert!(!reports.is_empty());
+        assert!(reports.iter().all(|&(_, t)| t == total));
+        assert!(reports.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert_eq!(reports.last().unwrap().0, total);
+    }
+
+    #[test]
+    fn upload_reports_server_errors() {
+        let (url, server) = serve_once("500 Internal Server Error");
+        let client = AudiobookshelfClient::new();
+        client.set_server(&url);
+        let path = upload_fixture("broken.mp3", b"data");
+
+        let result = client.send_upload(
+            &upload_request(),
+            &path,
+            "audio/mpeg",
+            None,
+            Arc::new(|_: u64, _: u64| {}),
+            Arc::new(AtomicBool::new(false)),
+        );
+        server.join().unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(ApiError::Server(_))), "{:?}", result);
+    }
+
+    #[test]
+    fn upload_honours_cancellation() {
+        let client = AudiobookshelfClient::new();
+        let path = upload_fixture("cancelled.mp3", b"data");
+        let result = client.upload_file(
+            &upload_request(),
+            &path,
+            "audio/mpeg",
+            Arc::new(|_: u64, _: u64| {}),
+            Arc::new(AtomicBool::new(true)),
+        );
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(result, Err(ApiError::Cancelled)), "{:?}", result);
+    }
+}
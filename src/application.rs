@@ -13,15 +13,23 @@
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::{gio, glib};
+use std::cell::RefCell;
 
 use crate::config::VERSION;
+use crate::links;
+use crate::notifications::{NotificationKind, NotificationSettings};
+use crate::power::PowerSettings;
+use crate::search_provider::{self, SearchRequest};
+use crate::window::RemoteCommand;
 use crate::ShelfilyDesktopWindow;
 
 mod imp {
     use super::*;
 
     #[derive(Debug, Default)]
-    pub struct ShelfilyDesktopApplication {}
+    pub struct ShelfilyDesktopApplication {
+        pub search_provider: RefCell<Option<gio::RegistrationId>>,
+    }
 
     #[glib::object_subclass]
     impl ObjectSubclass for ShelfilyDesktopApplication {
@@ -35,6 +43,9 @@ mod imp {
             self.parent_constructed();
             let obj = self.obj();
             obj.setup_gactions();
+            obj.setup_command_line();
+            // Keep a D-Bus activated service around briefly between searches
+            obj.set_inactivity_timeout(10_000);
             obj.set_accels_for_action("app.quit", &["<primary>q"]);
             obj.set_accels_for_action("app.preferences", &["<primary>comma"]);
         }
@@ -55,6 +66,89 @@ mod imp {
             });
             window.present();
         }
+
+        // Runs in the primary instance, also for invocations forwarded from
+        // a second process; errors are printed on the caller's terminal.
+        fn command_line(&self, command_line: &gio::ApplicationCommandLine) -> glib::ExitCode {
+            let application = self.obj();
+            let commands =
+                parse_remote_commands(&command_line.options_dict()).and_then(|mut commands| {
+                    // Positional arguments are deep links
+                    for arg in command_line.arguments().iter().skip(1) {
+                        let arg = arg.to_string_lossy();
+                        if !links::is_link(&arg) {
+                            return Err(format!("Unexpected argument: {}", arg));
+                        }
+                        commands.extend(links::parse(&arg)?);
+                    }
+                    Ok(commands)
+                });
+            let commands = match commands {
+                Ok(commands) => commands,
+                Err(err) => {
+                    command_line.printerr_literal(&format!("{}\n", err));
+                    return glib::ExitCode::FAILURE;
+                }
+            };
+            if commands.is_empty() {
+                application.activate();
+                return glib::ExitCode::SUCCESS;
+            }
+
+            // Remote control leaves an existing window where it is
+            let Some(window) = application.main_window() else {
+                return glib::ExitCode::FAILURE;
+            };
+            match window.handle_remote_commands(commands) {
+                Ok(()) => glib::ExitCode::SUCCESS,
+                Err(err) => {
+                    command_line.printerr_literal(&format!("{}\n", err));
+                    glib::ExitCode::FAILURE
+                }
+            }
+        }
+
+        fn dbus_register(
+            &self,
+            connection: &gio::DBusConnection,
+            object_path: &str,
+        ) -> Result<(), glib::Error> {
+            self.parent_dbus_register(connection, object_path)?;
+            let application = self.obj().downgrade();
+            let registration = search_provider::register(connection, object_path, move |request| {
+                if let Some(application) = application.upgrade() {
+                    application.handle_search_request(request);
+                }
+            });
+            match registration {
+                Ok(id) => *self.search_provider.borrow_mut() = Some(id),
+                Err(e) => log::warn!("Failed to export the search provider: {}", e),
+            }
+            Ok(())
+        }
+
+        fn dbus_unregister(&self, connection: &gio::DBusConnection, object_path: &str) {
+            if let Some(id) = self.search_provider.take() {
+                let _ = connection.unregister_object(id);
+            }
+            self.parent_dbus_unregister(connection, object_path);
+        }
+
+        // Deep links opened from the desktop, e.g. a clicked shelfily:// link
+        fn open(&self, files: &[gio::File], _hint: &str) {
+            let Some(window) = self.obj().main_window() else {
+                return;
+            };
+            window.present();
+            for file in files {
+                let result = links::parse(&file.uri())
+                    .and_then(|commands| window.handle_remote_commands(commands));
+                if let Err(err) = result {
+                    log::warn!("Cannot open {}: {}", file.uri(), err);
+                    window.show_toast(&err);
+                }
+            }
+        }
     }
 
     impl GtkApplicationImpl for ShelfilyDesktopApplication {}
@@ -91,14 +185,147 @@ impl ShelfilyDesktopApplication {
         let shortcuts_action = gio::ActionEntry::builder("shortcuts")
             .activate(move |app: &Self, _, _| app.show_shortcuts())
             .build();
+        // Sleep timer controls, also used by notification buttons
+        let extend_sleep_action = gio::ActionEntry::builder("extend-sleep-timer")
+            .parameter_type(Some(glib::VariantTy::INT32))
+            .activate(move |app: &Self, _, param| {
+                let minutes = param.and_then(|p| p.get::<i32>()).unwrap_or(15);
+                if let Some(window) = app.main_window() {
+                    window.extend_sleep_timer(minutes.into());
+                }
+            })
+            .build();
+        let cancel_sleep_action = gio::ActionEntry::builder("cancel-sleep-timer")
+            .activate(move |app: &Self, _, _| {
+                if let Some(window) = app.main_window() {
+                    window.cancel_sleep_timer();
+                }
+            })
+            .build();
         self.add_action_entries([
             quit_action,
             about_action,
             logout_action,
             preferences_action,
             shortcuts_action,
+            extend_sleep_action,
+            cancel_sleep_action,
         ]);
         self.set_accels_for_action("app.shortcuts", &["<primary>question"]);
+
+        // Book actions take the library item id, so menus, shortcuts and
+        // D-Bus callers can all point them at any book.
+        let item_actions = ShelfilyDesktopWindow::ITEM_ACTIONS.map(|name| {
+            gio::ActionEntry::builder(name)
+                .parameter_type(Some(glib::VariantTy::STRING))
+                .activate(move |app: &Self, _, param| {
+                    let Some(item_id) = param.and_then(|p| p.get::<String>()) else {
+                        return;
+                    };
+                    if let Some(window) = app.main_window() {
+                        window.activate_item_action(name, &item_id);
+                    }
+                })
+                .build()
+        });
+        self.add_action_entries(item_actions);
+    }
+
+    fn setup_command_line(&self) {
+        self.set_option_context_summary(Some(
+            "Without options the library window opens. Options are sent to \
+             the running instance, so they work from scripts and keybindings.",
+        ));
+        let options: [(&str, glib::OptionArg, &str, Option<&str>); 8] = [
+            (
+                "play",
+                glib::OptionArg::String,
+                "Play a book",
+                Some("ITEM-ID"),
+            ),
+            (
+                "resume",
+                glib::OptionArg::None,
+                "Resume the last played book",
+                None,
+            ),
+            (
+                "toggle",
+                glib::OptionArg::None,
+                "Toggle play and pause",
+                None,
+            ),
+            (
+                "seek",
+                glib::OptionArg::String,
+                "Seek to a time, or by +/- a time (30, 5m, 1:02:03)",
+                Some("[+|-]TIME"),
+            ),
+            (
+                "speed",
+                glib::OptionArg::Double,
+                "Set the playback speed",
+                Some("RATE"),
+            ),
+            (
+                "sleep",
+                glib::OptionArg::String,
+                "Set the sleep timer (30m, 1h, chapter or off)",
+                Some("DURATION"),
+            ),
+            (
+                "server",
+                glib::OptionArg::String,
+                "Use an Audiobookshelf server",
+                Some("URL"),
+            ),
+            (
+                "library",
+                glib::OptionArg::String,
+                "Open a library",
+                Some("LIBRARY-ID"),
+            ),
+        ];
+        for (name, arg, description, arg_description) in options {
+            self.add_main_option(
+                name,
+                glib::Char::from(0),
+                glib::OptionFlags::NONE,
+                arg,
+                description,
+                arg_description,
+            );
+        }
+    }
+
+    fn handle_search_request(&self, request: SearchRequest) {
+        let Some(window) = self.main_window() else {
+            return;
+        };
+        window.present();
+        match request {
+            SearchRequest::Activate(item_id) => {
+                let commands = vec![
+                    RemoteCommand::OpenItem {
+                        item_id: item_id.clone(),
+                        position: None,
+                    },
+                    RemoteCommand::Play(item_id),
+                ];
+                if let Err(err) = window.handle_remote_commands(commands) {
+                    window.show_toast(&err);
+                }
+            }
+            SearchRequest::Launch(query) => window.search_library(&query),
+        }
+    }
+
+    /// The library window, created first when activated from outside.
+    fn main_window(&self) -> Option<ShelfilyDesktopWindow> {
+        if self.active_window().is_none() {
+            self.activate();
+        }
+        self.active_window().and_downcast::<ShelfilyDesktopWindow>()
     }
 
     fn show_shortcuts(&self) {
@@ -117,6 +344,7 @@ impl ShelfilyDesktopApplication {
             ("Forward 30 seconds", "→"),
             ("Add bookmark", "Ctrl+B"),
             ("Search", "Ctrl+F"),
+            ("Book menu", "Menu / Shift+F10"),
             ("Preferences", "Ctrl+,"),
             ("Keyboard shortcuts", "Ctrl+?"),
             ("Quit", "Ctrl+Q"),
@@ -169,8 +397,43 @@ impl ShelfilyDesktopApplication {
             apply_color_scheme(theme);
         });
         group.add(&combo);
-
         page.add(&group);
+
+        let playback_group = adw::PreferencesGroup::new();
+        playback_group.set_title("Playback");
+        let inhibit_row = adw::SwitchRow::new();
+        inhibit_row.set_title("Prevent Suspend While Playing");
+        inhibit_row.set_subtitle("Keep the computer awake until the book is paused");
+        inhibit_row.set_active(PowerSettings::load().inhibit_suspend);
+        let app = self.clone();
+        inhibit_row.connect_active_notify(move |row| {
+            let mut settings = PowerSettings::load();
+            settings.inhibit_suspend = row.is_active();
+            settings.save();
+            if let Some(window) = app.active_window().and_downcast::<ShelfilyDesktopWindow>() {
+                window.refresh_suspend_inhibit();
+            }
+        });
+        playback_group.add(&inhibit_row);
+        page.add(&playback_group);
+
+        let notifications_group = adw::PreferencesGroup::new();
+        notifications_group.set_title("Notifications");
+        notifications_group.set_description(Some("Shown while the window is in the background"));
+        let settings = NotificationSettings::load();
+        for kind in NotificationKind::ALL {
+            let row = adw::SwitchRow::new();
+            row.set_title(kind.label());
+            row.set_active(kind.is_enabled(&settings));
+            row.connect_active_notify(move |row| {
+                let mut settings = NotificationSettings::load();
+                kind.set_enabled(&mut settings, row.is_active());
+                settings.save();
+            });
+            notifications_group.add(&row);
+        }
+        page.add(&notifications_group);
+
         dialog.add(&page);
         dialog.present(window.as_ref());
     }
@@ -202,6 +465,111 @@ impl ShelfilyDesktopApplication {
     }
 }
 
+/// Turns the parsed options into window commands, in the order they should
+/// run.
+fn parse_remote_commands(options: &glib::VariantDict) -> Result<Vec<RemoteCommand>, String> {
+    let string = |name: &str| {
+        options
+            .lookup::<String>(name)
+            .ok()
+            .flatten()
+            .map(|value| value.trim().to_string())
+    };
+    let flag = |name: &str| options.contains(name);
+    let mut commands = Vec::new();
+
+    if let Some(url) = string("server") {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "Server URL must start with http:// or https://: {}",
+                url
+            ));
+        }
+        commands.push(RemoteCommand::Server(url));
+    }
+    if let Some(library_id) = string("library").filter(|id| !id.is_empty()) {
+        commands.push(RemoteCommand::Library(library_id));
+    }
+    if let Some(item_id) = string("play").filter(|id| !id.is_empty()) {
+        commands.push(RemoteCommand::Play(item_id));
+    } else if flag("resume") {
+        commands.push(RemoteCommand::Resume);
+    }
+    if flag("toggle") {
+        commands.push(RemoteCommand::TogglePlayback);
+    }
+    if let Some(seek) = string("seek") {
+        let (sign, time) = match seek.strip_prefix('+') {
+            Some(rest) => (Some(1.0), rest),
+            None => match seek.strip_prefix('-') {
+                Some(rest) => (Some(-1.0), rest),
+                None => (None, seek.as_str()),
+            },
+        };
+        let seconds =
+            parse_duration(time, 1.0).ok_or_else(|| format!("Invalid seek time: {}", seek))?;
+        commands.push(RemoteCommand::Seek {
+            seconds: seconds * sign.unwrap_or(1.0),
+            relative: sign.is_some(),
+        });
+    }
+    if let Some(rate) = options.lookup::<f64>("speed").ok().flatten() {
+        if !(0.25..=4.0).contains(&rate) {
+            return Err(format!("Speed must be between 0.25 and 4: {}", rate));
+        }
+        commands.push(RemoteCommand::Speed(rate));
+    }
+    if let Some(sleep) = string("sleep") {
+        commands.push(match sleep.as_str() {
+            "chapter" | "end-of-chapter" => RemoteCommand::SleepEndOfChapter,
+            "off" | "cancel" => RemoteCommand::CancelSleep,
+            _ => {
+                let seconds = parse_duration(&sleep, 60.0)
+                    .filter(|s| *s > 0.0)
+                    .ok_or_else(|| format!("Invalid sleep duration: {}", sleep))?;
+                RemoteCommand::SleepMinutes((seconds / 60.0).ceil() as i64)
+            }
+        });
+    }
+    Ok(commands)
+}
+
+/// Parses "90", "1:30", "1:02:03", "45s", "30m" or "1h30m" into seconds; a
+/// bare number counts in `unit` seconds.
+fn parse_duration(text: &str, unit: f64) -> Option<f64> {
+    let text = text.trim();
+    let valid = |v: f64| v.is_finite() && v >= 0.0;
+    if text.contains(':') {
+        let mut total = 0.0;
+        for part in text.split(':') {
+            let value: f64 = part.parse().ok().filter(|v| valid(*v))?;
+            total = total * 60.0 + value;
+        }
+        return Some(total);
+    }
+    if let Ok(value) = text.parse::<f64>() {
+        return valid(value).then_some(value * unit);
+    }
+
+    let mut total = 0.0;
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let scale = match c {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        total += number.parse::<f64>().ok()? * scale;
+        number.clear();
+    }
+    (number.is_empty() && !text.is_empty()).then_some(total)
+}
+
 fn theme_file_path() -> std::path::PathBuf {
     let mut path = glib::user_config_dir();
     path.push("shelfily-desktop");
@@ -235,3 +603,153 @@ fn apply_color_scheme(theme: &str) {
     };
     adw::StyleManager::default().set_color_scheme(scheme);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(entries: &[(&str, glib::Variant)]) -> glib::VariantDict {
+        let dict = glib::VariantDict::new(None);
+        for (name, value) in entries {
+            dict.insert_value(name, value);
+        }
+        dict
+    }
+
+    #[test]
+    fn parse_duration_reads_clock_times() {
+        assert_eq!(parse_duration("1:30", 1.0), Some(90.0));
+        assert_eq!(parse_duration("1:02:03", 1.0), Some(3723.0));
+        assert_eq!(parse_duration(" 0:05 ", 60.0), Some(5.0));
+    }
+
+    #[test]
+    fn parse_duration_reads_units() {
+        assert_eq!(parse_duration("45s", 1.0), Some(45.0));
+        assert_eq!(parse_duration("30m", 1.0), Some(1800.0));
+        assert_eq!(parse_duration("1h30m", 1.0), Some(5400.0));
+        assert_eq!(parse_duration("1.5h", 1.0), Some(5400.0));
+    }
+
+    #[test]
+    fn parse_duration_scales_bare_numbers() {
+        assert_eq!(parse_duration("90", 1.0), Some(90.0));
+        assert_eq!(parse_duration("30", 60.0), Some(1800.0));
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed_values() {
+        for text in [
+            "", "  ", "abc", "-5", "1x", "5m3", ":30", "1::2", "h", "inf",
+        ] {
+            assert_eq!(parse_duration(text, 1.0), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn no_options_give_no_commands() {
+        assert_eq!(parse_remote_commands(&options(&[])), Ok(vec![]));
+    }
+
+    #[test]
+    fn commands_keep_their_order() {
+        let commands = parse_remote_commands(&options(&[
+            ("toggle", true.to_variant()),
+            ("play", "li_1".to_variant()),
+            ("library", "lib_1".to_variant()),
+            ("server", "https://abs.example".to_variant()),
+        ]));
+        assert_eq!(
+            commands,
+            Ok(vec![
+                RemoteCommand::Server("https://abs.example".to_string()),
+                RemoteCommand::Library("lib_1".to_string()),
+                RemoteCommand::Play("li_1".to_string()),
+                RemoteCommand::TogglePlayback,
+            ])
+        );
+    }
+
+    #[test]
+    fn play_wins_over_resume() {
+        let commands = parse_remote_commands(&options(&[
+            ("play", "li_1".to_variant()),
+            ("resume", true.to_variant()),
+        ]));
+        assert_eq!(commands, Ok(vec![RemoteCommand::Play("li_1".to_string())]));
+        let commands = parse_remote_commands(&options(&[("resume", true.to_variant())]));
+        assert_eq!(commands, Ok(vec![RemoteCommand::Resume]));
+    }
+
+    #[test]
+    fn empty_ids_are_ignored() {
+        let commands = parse_remote_commands(&options(&[
+            ("library", "  ".to_variant()),
+            ("play", "".to_variant()),
+        ]));
+        assert_eq!(commands, Ok(vec![]));
+    }
+
+    #[test]
+    fn seek_is_relative_with_a_sign() {
+        let seek = |value: &str| parse_remote_commands(&options(&[("seek", value.to_variant())]));
+        assert_eq!(
+            seek("+30"),
+            Ok(vec![RemoteCommand::Seek {
+                seconds: 30.0,
+                relative: true
+            }])
+        );
+        assert_eq!(
+            seek("-1:00"),
+            Ok(vec![RemoteCommand::Seek {
+                seconds: -60.0,
+                relative: true
+            }])
+        );
+        assert_eq!(
+            seek("1h"),
+            Ok(vec![RemoteCommand::Seek {
+                seconds: 3600.0,
+                relative: false
+            }])
+        );
+        assert!(seek("+").is_err());
+        assert!(seek("soon").is_err());
+    }
+
+    #[test]
+    fn sleep_counts_bare_numbers_in_minutes() {
+        let sleep = |value: &str| parse_remote_commands(&options(&[("sleep", value.to_variant())]));
+        assert_eq!(sleep("45"), Ok(vec![RemoteCommand::SleepMinutes(45)]));
+        assert_eq!(sleep("1h"), Ok(vec![RemoteCommand::SleepMinutes(60)]));
+        assert_eq!(sleep("90s"), Ok(vec![RemoteCommand::SleepMinutes(2)]));
+        assert_eq!(sleep("chapter"), Ok(vec![RemoteCommand::SleepEndOfChapter]));
+        assert_eq!(sleep("off"), Ok(vec![RemoteCommand::CancelSleep]));
+        assert!(sleep("0").is_err());
+        assert!(sleep("").is_err());
+        assert!(sleep("later").is_err());
+    }
+
+    #[test]
+    fn speed_must_be_in_range() {
+        let speed = |value: f64| parse_remote_commands(&options(&[("speed", value.to_variant())]));
+        assert_eq!(speed(1.5), Ok(vec![RemoteCommand::Speed(1.5)]));
+        assert!(speed(0.1).is_err());
+        assert!(speed(5.0).is_err());
+    }
+
+    #[test]
+    fn server_must_be_http() {
+        let server =
+            |value: &str| parse_remote_commands(&options(&[("server", value.to_variant())]));
+        assert!(server("ftp://abs.example").is_err());
+        assert!(server("abs.example").is_err());
+        assert_eq!(
+            server("http://abs.local:13378"),
+            Ok(vec![RemoteCommand::Server(
+                "http://abs.local:13378".to_string()
+            )])
+        );
+    }
+}
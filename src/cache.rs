@@ -0,0 +1,194 @@
+/* cache.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::models::{Bookmark, Library, LibraryItem};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Server origin and user id the library state and downloads belong to
+static ACCOUNT: Mutex<(String, String)> = Mutex::new((String::new(), String::new()));
+
+/// Files kept between runs so the app can show the library without the
+/// server, in user_cache_dir/shelfily-desktop.
+pub fn cache_dir() -> PathBuf {
+    let mut path = gtk::glib::user_cache_dir();
+    path.push("shelfily-desktop");
+    path
+}
+
+pub fn cover_path(item_id: &str) -> PathBuf {
+    let mut path = cache_dir();
+    path.push("covers");
+    path.push(format!("{}.jpg", item_id));
+    path
+}
+
+/// Stores a downloaded cover and tells whether it's on disk now; failures
+/// only cost a later re-download.
+pub fn save_cover(item_id: &str, bytes: &[u8]) -> bool {
+    let path = cover_path(item_id);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&path, bytes) {
+        log::warn!("Failed to cache cover of {}: {}", item_id, e);
+    }
+    path.exists()
+}
+
+/// A cover saved by an earlier run, for use without the network.
+pub fn load_cover(item_id: &str) -> Option<Vec<u8>> {
+    std::fs::read(cover_path(item_id)).ok()
+}
+
+/// Switches the library state and downloads to another server or user,
+/// so one account never shows another one's books. Tokens that don't
+/// name a user key the state by server only.
+pub fn set_account(server_url: &str, user_id: &str) {
+    let origin = crate::trust::origin(server_url).unwrap_or_default();
+    *ACCOUNT.lock().unwrap() = (origin, user_id.to_string());
+}
+
+fn hashed(value: &str) -> String {
+    gtk::glib::compute_checksum_for_string(gtk::glib::ChecksumType::Sha256, value)
+        .map(|sum| sum.to_string())
+        .unwrap_or_default()
+}
+
+/// Folder name of the current server; downloaded files are the same for
+/// every user of a server.
+pub fn server_key() -> String {
+    hashed(&ACCOUNT.lock().unwrap().0)
+}
+
+fn account_key() -> String {
+    let account = ACCOUNT.lock().unwrap();
+    hashed(&format!("{}\n{}", account.0, account.1))
+}
+
+/// Last known library state of the current account, for starting without
+/// the server.
+fn state_dir() -> PathBuf {
+    let mut path = cache_dir();
+    path.push("state");
+    path.push(account_key());
+    path
+}
+
+fn state_path(name: &str) -> PathBuf {
+    state_dir().join(format!("{}.json", name))
+}
+
+pub fn save_state<T: Serialize + ?Sized>(name: &str, value: &T) {
+    let path = state_path(name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(value) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                log::warn!("Failed to cache {}: {}", name, e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize {}: {}", name, e),
+    }
+}
+
+pub fn load_state<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let json = std::fs::read_to_string(state_path(name)).ok()?;
+    serde_json::from_str(&json)
+        .map_err(|e| log::warn!("Ignoring cached {}: {}", name, e))
+        .ok()
+}
+
+/// Names of the current account's state files that start with `prefix`,
+/// e.g. one per library
+pub fn state_names(prefix: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(state_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let name = name.strip_suffix(".json")?;
+            name.starts_with(prefix).then(|| name.to_string())
+        })
+        .collect()
+}
+
+pub fn save_libraries(libraries: &[Library]) {
+    save_state("libraries", libraries);
+}
+
+pub fn load_libraries() -> Option<Vec<Library>> {
+    load_state("libraries")
+}
+
+pub fn save_library_items(library_id: &str, items: &[LibraryItem]) {
+    save_state(&format!("items-{}", library_id), items);
+}
+
+pub fn load_library_items(library_id: &str) -> Option<Vec<LibraryItem>> {
+    load_state(&format!("items-{}", library_id))
+}
+
+pub fn save_continue_listening(items: &[LibraryItem]) {
+    save_state("continue-listening", items);
+}
+
+pub fn load_continue_listening() -> Option<Vec<LibraryItem>> {
+    load_state("continue-listening")
+}
+
+pub fn save_bookmarks(bookmarks: &[Bookmark]) {
+    save_state("bookmarks", bookmarks);
+}
+
+pub fn load_bookmarks() -> Option<Vec<Bookmark>> {
+    load_state("bookmarks")
+}
+
+/// Last position of a book played while the server couldn't be reached,
+/// sent once it is back. Books played from disk have no session id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PendingSync {
+    pub session_id: String,
+    pub current_time: f64,
+    pub duration: f64,
+}
+
+/// Pending positions by item id
+pub fn save_pending_sync(pending: &HashMap<String, PendingSync>) {
+    save_state("pending-sync", pending);
+}
+
+pub fn load_pending_sync() -> HashMap<String, PendingSync> {
+    load_state("pending-sync").unwrap_or_default()
+}
+
+/// Forgets the account's library state; covers are kept.
+pub fn clear_state() {
+    let _ = std::fs::remove_dir_all(state_dir());
+}
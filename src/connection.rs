@@ -0,0 +1,422 @@
+/* connection.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::settings;
+use glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+const SECRET_SCHEMA: &str = "io.github.yusyel.ShelfilyDesktop.Connection";
+
+/// Secrets already read from the keyring, by origin, so rebuilding the
+/// HTTP client doesn't wait for the keyring again
+static SECRETS: Mutex<BTreeMap<String, Secrets>> = Mutex::new(BTreeMap::new());
+
+/// How to reach a server that sits behind a reverse proxy, kept per origin
+/// in connections.json. Header values, the basic auth password and the
+/// client key are stored in the keyring.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ConnectionSettings {
+    /// Extra headers sent with every request, e.g. CF-Access-Client-Id
+    pub headers: Vec<Header>,
+    /// http://, https:// or socks5:// proxy to connect through
+    pub proxy: Option<String>,
+    /// PEM client certificate for mutual TLS
+    pub client_certificate: Option<String>,
+    /// PEM (PKCS#8) key of the client certificate
+    #[serde(skip)]
+    pub client_key: Option<String>,
+    /// Basic auth user for a proxy in front of the server
+    pub basic_auth_user: Option<String>,
+    #[serde(skip)]
+    pub basic_auth_password: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Header {
+    pub name: String,
+    #[serde(skip)]
+    pub value: String,
+}
+
+// Keep secrets out of debug output
+impl std::fmt::Debug for ConnectionSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionSettings")
+            .field("headers", &self.headers)
+            .field("proxy", &self.proxy)
+            .field("client_certificate", &self.client_certificate.is_some())
+            .field("basic_auth_user", &self.basic_auth_user)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Debug for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// The parts of the settings that go to the keyring
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Secrets {
+    headers: BTreeMap<String, String>,
+    basic_auth_password: String,
+    client_key: Option<String>,
+}
+
+impl ConnectionSettings {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+            && self.proxy.is_none()
+            && self.client_certificate.is_none()
+            && self.basic_auth_user.is_none()
+    }
+
+    /// Value for an Authorization header, when basic auth is configured
+    pub fn basic_authorization(&self) -> Option<String> {
+        let user = self.basic_auth_user.as_deref()?;
+        let credentials = format!("{}:{}", user, self.basic_auth_password);
+        Some(format!(
+            "Basic {}",
+            glib::base64_encode(credentials.as_bytes())
+        ))
+    }
+
+    /// Client certificate and key for consumers that take a GTlsCertificate
+    pub fn tls_certificate(&self) -> Option<gio::TlsCertificate> {
+        let pem = format!(
+            "{}\n{}",
+            self.client_certificate.as_deref()?.trim_end(),
+            self.client_key.as_deref()?
+        );
+        match gio::TlsCertificate::from_pem(&pem) {
+            Ok(cert) => Some(cert),
+            Err(e) => {
+                log::warn!("Could not load client certificate: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Interaction that presents the client certificate when the server
+    /// asks for one, for consumers that take a GTlsInteraction
+    pub fn tls_interaction(&self) -> Option<gio::TlsInteraction> {
+        let certificate = self.tls_certificate()?;
+        Some(ClientCertificateInteraction::new(certificate).upcast())
+    }
+
+    /// Applies the proxy, client certificate and extra headers to an HTTP
+    /// client. Basic auth is sent as Authorization on requests that carry
+    /// no session token, and as Proxy-Authorization on all of them, which
+    /// forward-auth proxies such as Authelia accept alongside the token.
+    /// Redirects to another host are not followed while any of these are
+    /// set, so they only ever reach the configured server.
+    pub fn configure(
+        &self,
+        builder: reqwest::blocking::ClientBuilder,
+    ) -> reqwest::blocking::ClientBuilder {
+        let mut headers = reqwest::header::HeaderMap::new();
+        for header in &self.headers {
+            match (
+                reqwest::header::HeaderName::from_bytes(header.name.as_bytes()),
+                reqwest::header::HeaderValue::from_str(&header.value),
+            ) {
+                (Ok(name), Ok(mut value)) => {
+                    value.set_sensitive(true);
+                    headers.insert(name, value);
+                }
+                _ => log::warn!("Ignoring invalid header {}", header.name),
+            }
+        }
+        if let Some(Ok(mut value)) = self
+            .basic_authorization()
+            .map(|auth| reqwest::header::HeaderValue::from_str(&auth))
+        {
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::PROXY_AUTHORIZATION, value.clone());
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        let has_secrets = !headers.is_empty();
+        self.configure_transport(builder)
+            .default_headers(headers)
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                let leaves_server = attempt
+                    .previous()
+                    .first()
+                    .is_some_and(|first| first.host_str() != attempt.url().host_str());
+                if attempt.previous().len() >= 10 {
+                    attempt.error("too many redirects")
+                } else if has_secrets && leaves_server {
+                    log::warn!(
+                        "Not following a redirect to {:?}, connection headers only go to the server",
+                        attempt.url().host_str()
+                    );
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }))
+    }
+
+    /// Only the proxy and client certificate, without any secrets that
+    /// could leak to a server whose certificate isn't verified
+    pub fn configure_transport(
+        &self,
+        mut builder: reqwest::blocking::ClientBuilder,
+    ) -> reqwest::blocking::ClientBuilder {
+        if let Some(proxy) = self.proxy.as_deref() {
+            match reqwest::Proxy::all(proxy) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => log::warn!("Ignoring invalid proxy {}: {}", proxy, e),
+            }
+        }
+        if let (Some(cert), Some(key)) = (
+            self.client_certificate.as_deref(),
+            self.client_key.as_deref(),
+        ) {
+            match reqwest::Identity::from_pkcs8_pem(cert.as_bytes(), key.as_bytes()) {
+                Ok(identity) => builder = builder.identity(identity),
+                Err(e) => log::warn!("Ignoring unreadable client certificate: {}", e),
+            }
+        }
+        builder
+    }
+
+    /// Checks the settings before they are saved
+    pub fn validate(&self) -> Result<(), String> {
+        for header in &self.headers {
+            if reqwest::header::HeaderName::from_bytes(header.name.as_bytes()).is_err() {
+                return Err(format!("\"{}\" is not a valid header name", header.name));
+            }
+            if reqwest::header::HeaderValue::from_str(&header.value).is_err() {
+                return Err(format!("The value of {} is not valid", header.name));
+            }
+        }
+        if let Some(proxy) = self.proxy.as_deref() {
+            let scheme = proxy.split("://").next().unwrap_or_default();
+            if !matches!(scheme, "http" | "https" | "socks5" | "socks5h") || !proxy.contains("://")
+            {
+                return Err("Proxy must be an http://, https:// or socks5:// URL".to_string());
+            }
+            reqwest::Proxy::all(proxy).map_err(|e| format!("Invalid proxy: {}", e))?;
+        }
+        match (
+            self.client_certificate.as_deref(),
+            self.client_key.as_deref(),
+        ) {
+            (Some(cert), Some(key)) => {
+                reqwest::Identity::from_pkcs8_pem(cert.as_bytes(), key.as_bytes())
+                    .map_err(|e| format!("Invalid client certificate or key: {}", e))?;
+            }
+            (Some(_), None) => return Err("Choose the key of the client certificate".to_string()),
+            (None, Some(_)) => return Err("Choose the client certificate".to_string()),
+            (None, None) => {}
+        }
+        // Some problems, like a key that doesn't match the certificate,
+        // only show up once a client is built with them
+        self.configure(reqwest::blocking::Client::builder())
+            .build()
+            .map_err(|e| format!("These settings can't be used: {}", e))?;
+        Ok(())
+    }
+}
+
+mod imp {
+    use super::*;
+
+    /// Only read after construction, from the thread doing the handshake
+    #[derive(Debug, Default)]
+    pub struct ClientCertificateInteraction {
+        pub certificate: OnceLock<gio::TlsCertificate>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ClientCertificateInteraction {
+        const NAME: &'static str = "ShelfilyClientCertificateInteraction";
+        type Type = super::ClientCertificateInteraction;
+        type ParentType = gio::TlsInteraction;
+    }
+
+    impl ObjectImpl for ClientCertificateInteraction {}
+}
+
+glib::wrapper! {
+    pub struct ClientCertificateInteraction(ObjectSubclass<imp::ClientCertificateInteraction>)
+        @extends gio::TlsInteraction;
+}
+
+impl ClientCertificateInteraction {
+    fn new(certificate: gio::TlsCertificate) -> Self {
+        let interaction: Self = glib::Object::new();
+        let _ = interaction.imp().certificate.set(certificate);
+        interaction
+    }
+}
+
+// gio has no subclassing support for GTlsInteraction, so the class method
+// is set directly
+unsafe impl IsSubclassable<imp::ClientCertificateInteraction> for gio::TlsInteraction {
+    fn class_init(class: &mut glib::Class<Self>) {
+        Self::parent_class_init::<imp::ClientCertificateInteraction>(class);
+        class.as_mut().request_certificate = Some(request_client_certificate);
+    }
+}
+
+unsafe extern "C" fn request_client_certificate(
+    interaction: *mut gio::ffi::GTlsInteraction,
+    connection: *mut gio::ffi::GTlsConnection,
+    _flags: gio::ffi::GTlsCertificateRequestFlags,
+    _cancellable: *mut gio::ffi::GCancellable,
+    _error: *mut *mut glib::ffi::GError,
+) -> gio::ffi::GTlsInteractionResult {
+    let instance =
+        &*(interaction as *mut <imp::ClientCertificateInteraction as ObjectSubclass>::Instance);
+    let Some(certificate) = instance.imp().certificate.get() else {
+        return gio::ffi::G_TLS_INTERACTION_UNHANDLED;
+    };
+    let connection: glib::translate::Borrowed<gio::TlsConnection> =
+        glib::translate::from_glib_borrow(connection);
+    connection.set_certificate(certificate);
+    gio::ffi::G_TLS_INTERACTION_HANDLED
+}
+
+fn load_store() -> HashMap<String, ConnectionSettings> {
+    settings::load_config("connections.json")
+}
+
+fn save_store(store: &HashMap<String, ConnectionSettings>) {
+    settings::save_config("connections.json", store);
+}
+
+fn secret_schema() -> libsecret::Schema {
+    let mut attrs = HashMap::new();
+    attrs.insert("server", libsecret::SchemaAttributeType::String);
+    libsecret::Schema::new(SECRET_SCHEMA, libsecret::SchemaFlags::NONE, attrs)
+}
+
+fn secret_attrs(origin: &str) -> HashMap<&'static str, &str> {
+    let mut attrs = HashMap::new();
+    attrs.insert("server", origin);
+    attrs
+}
+
+fn load_secrets(origin: &str) -> Secrets {
+    if let Some(secrets) = SECRETS.lock().unwrap().get(origin) {
+        return secrets.clone();
+    }
+    let secrets = match libsecret::password_lookup_sync(
+        Some(&secret_schema()),
+        secret_attrs(origin),
+        gio::Cancellable::NONE,
+    ) {
+        Ok(Some(raw)) => serde_json::from_str(raw.as_str()).unwrap_or_default(),
+        Ok(None) => Secrets::default(),
+        Err(e) => {
+            // Not remembered, a locked keyring may be unlocked next time
+            log::warn!("Could not read connection secrets: {}", e);
+            return Secrets::default();
+        }
+    };
+    SECRETS
+        .lock()
+        .unwrap()
+        .insert(origin.to_string(), secrets.clone());
+    secrets
+}
+
+fn save_secrets(origin: &str, secrets: &Secrets) -> Result<(), String> {
+    let schema = secret_schema();
+    if secrets.headers.is_empty()
+        && secrets.basic_auth_password.is_empty()
+        && secrets.client_key.is_none()
+    {
+        if let Err(e) = libsecret::password_clear_sync(
+            Some(&schema),
+            secret_attrs(origin),
+            gio::Cancellable::NONE,
+        ) {
+            log::warn!("Could not clear connection secrets: {}", e);
+        }
+    } else {
+        let payload = serde_json::to_string(secrets).map_err(|e| e.to_string())?;
+        libsecret::password_store_sync(
+            Some(&schema),
+            secret_attrs(origin),
+            Some(libsecret::COLLECTION_DEFAULT.as_str()),
+            &format!("Shelfily Desktop Connection ({})", origin),
+            &payload,
+            gio::Cancellable::NONE,
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    SECRETS
+        .lock()
+        .unwrap()
+        .insert(origin.to_string(), secrets.clone());
+    Ok(())
+}
+
+/// Settings for a server, with secrets filled in from the keyring. The
+/// keyring is only asked the first time for each server; signing in does
+/// that on its worker thread.
+pub fn load(server_url: &str) -> ConnectionSettings {
+    let Some(origin) = crate::trust::origin(server_url) else {
+        return ConnectionSettings::default();
+    };
+    let Some(mut settings) = load_store().remove(&origin) else {
+        return ConnectionSettings::default();
+    };
+    let mut secrets = load_secrets(&origin);
+    for header in &mut settings.headers {
+        header.value = secrets.headers.remove(&header.name).unwrap_or_default();
+    }
+    settings.basic_auth_password = secrets.basic_auth_password;
+    settings.client_key = secrets.client_key;
+    settings
+}
+
+pub fn save(server_url: &str, settings: &ConnectionSettings) -> Result<(), String> {
+    let origin = crate::trust::origin(server_url).ok_or("Invalid server URL")?;
+    let secrets = Secrets {
+        headers: settings
+            .headers
+            .iter()
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect(),
+        basic_auth_password: settings.basic_auth_password.clone(),
+        client_key: settings.client_key.clone(),
+    };
+    save_secrets(&origin, &secrets)?;
+    let mut store = load_store();
+    if settings.is_empty() {
+        store.remove(&origin);
+    } else {
+        store.insert(origin, settings.clone());
+    }
+    save_store(&store);
+    Ok(())
+}
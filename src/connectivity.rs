@@ -0,0 +1,178 @@
+/* connectivity.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::api::{ApiError, AudiobookshelfClient};
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/// How often an unreachable server is probed again
+const RETRY_INTERVAL_SECS: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    Online,
+    /// Online, but network-heavy work waits for an unmetered connection
+    Metered,
+    /// No network at all
+    Offline,
+    /// The network is up but the server doesn't answer
+    Unreachable,
+}
+
+impl Connectivity {
+    pub fn is_online(self) -> bool {
+        matches!(self, Self::Online | Self::Metered)
+    }
+}
+
+type ChangeCallback = Box<dyn Fn(Connectivity, Connectivity)>;
+
+/// Follows GNetworkMonitor and checks that the server answers `/status`
+/// whenever the network changes or a request fails.
+pub struct ConnectivityMonitor {
+    monitor: gio::NetworkMonitor,
+    client: AudiobookshelfClient,
+    state: Cell<Connectivity>,
+    probing: Cell<bool>,
+    retry_source: RefCell<Option<glib::SourceId>>,
+    // Gets (previous, new) on every change
+    on_change: ChangeCallback,
+}
+
+impl std::fmt::Debug for ConnectivityMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectivityMonitor")
+            .field("state", &self.state.get())
+            .field("probing", &self.probing.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConnectivityMonitor {
+    pub fn new(
+        client: AudiobookshelfClient,
+        on_change: impl Fn(Connectivity, Connectivity) + 'static,
+    ) -> Rc<Self> {
+        let monitor = gio::NetworkMonitor::default();
+        let this = Rc::new(Self {
+            monitor: monitor.clone(),
+            client,
+            state: Cell::new(Connectivity::Online),
+            probing: Cell::new(false),
+            retry_source: RefCell::new(None),
+            on_change: Box::new(on_change),
+        });
+        let weak = Rc::downgrade(&this);
+        monitor.connect_network_changed(move |_, _| {
+            if let Some(this) = weak.upgrade() {
+                this.check();
+            }
+        });
+        let weak = Rc::downgrade(&this);
+        monitor.connect_network_metered_notify(move |_| {
+            if let Some(this) = weak.upgrade() {
+                this.check();
+            }
+        });
+        this
+    }
+
+    pub fn state(&self) -> Connectivity {
+        self.state.get()
+    }
+
+    /// Probes the server now, unless a probe is already running.
+    pub fn check(self: &Rc<Self>) {
+        if self.probing.replace(true) {
+            return;
+        }
+        if let Some(id) = self.retry_source.borrow_mut().take() {
+            id.remove();
+        }
+
+        let client = self.client.clone();
+        let this = self.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                // Without a server there is nothing to reach yet
+                let result = if client.server_url().is_empty() {
+                    Ok(())
+                } else {
+                    client.get_status().map(|_| ())
+                };
+                let _ = tx.send_blocking(result);
+            });
+            let reachable = match rx.recv().await {
+                Ok(Ok(())) => true,
+                Ok(Err(e)) => {
+                    log::info!("Server probe failed: {}", e);
+                    false
+                }
+                Err(_) => false,
+            };
+            this.probing.set(false);
+
+            let state = if reachable {
+                if this.monitor.is_network_metered() {
+                    Connectivity::Metered
+                } else {
+                    Connectivity::Online
+                }
+            } else if this.monitor.is_network_available() {
+                Connectivity::Unreachable
+            } else {
+                // A LAN server may still answer without a default route,
+                // so "no network" is only trusted once the probe failed
+                Connectivity::Offline
+            };
+            this.set_state(state);
+
+            if !state.is_online() {
+                let weak = Rc::downgrade(&this);
+                let id = glib::timeout_add_seconds_local_once(RETRY_INTERVAL_SECS, move || {
+                    if let Some(this) = weak.upgrade() {
+                        this.retry_source.take();
+                        this.check();
+                    }
+                });
+                *this.retry_source.borrow_mut() = Some(id);
+            }
+        });
+    }
+
+    /// Called with the error of any failed request; network errors
+    /// trigger a probe so the app notices it went offline.
+    pub fn report_error(self: &Rc<Self>, error: &ApiError) {
+        if matches!(error, ApiError::Network(_)) && self.state.get().is_online() {
+            self.check();
+        }
+    }
+
+    fn set_state(&self, state: Connectivity) {
+        let previous = self.state.replace(state);
+        if previous != state {
+            log::info!("Connectivity changed: {:?} -> {:?}", previous, state);
+            (self.on_change)(previous, state);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+/* downloads.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::api::{ApiError, AudiobookshelfClient};
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+
+/// Written next to the audio files once every file of an item is on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadManifest {
+    pub item_id: String,
+    pub title: String,
+    pub author: String,
+    pub duration: f64,
+    /// Audio files in playback order, relative to the item's folder
+    pub files: Vec<DownloadedFile>,
+    /// Unix time in milliseconds
+    pub downloaded_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedFile {
+    pub ino: String,
+    pub filename: String,
+    pub duration: f64,
+}
+
+pub fn downloads_dir() -> PathBuf {
+    let mut path = glib::user_data_dir();
+    path.push("shelfily-desktop");
+    path.push("downloads");
+    path.push(crate::cache::server_key());
+    path
+}
+
+pub fn item_dir(item_id: &str) -> PathBuf {
+    downloads_dir().join(item_id)
+}
+
+fn manifest_path(item_id: &str) -> PathBuf {
+    item_dir(item_id).join("manifest.json")
+}
+
+pub fn manifest(item_id: &str) -> Option<DownloadManifest> {
+    let content = fs::read_to_string(manifest_path(item_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn is_downloaded(item_id: &str) -> bool {
+    manifest(item_id).is_some()
+}
+
+/// Downloads every audio file and the cover of an item, reporting
+/// (bytes done, bytes total). Files finished by an earlier, interrupted
+/// attempt are kept.
+pub fn download_item(
+    client: &AudiobookshelfClient,
+    item_id: &str,
+    progress: &dyn Fn(u64, u64),
+    cancel: &AtomicBool,
+) -> Result<DownloadManifest, ApiError> {
+    let item = client.get_library_item(item_id)?;
+    let media = item.media.as_ref();
+    let mut audio_files = media
+        .and_then(|m| m.audio_files.clone())
+        .unwrap_or_default();
+    if audio_files.is_empty() {
+        return Err(ApiError::Server("No audio files to download".to_string()));
+    }
+    audio_files.sort_by_key(|f| f.index.unwrap_or(0));
+
+    let size_of =
+        |f: &crate::models::AudioFile| f.metadata.as_ref().and_then(|m| m.size).unwrap_or(0);
+    let total: u64 = audio_files.iter().map(size_of).sum();
+
+    let dir = item_dir(item_id);
+    fs::create_dir_all(&dir).map_err(|e| ApiError::Io(e.to_string()))?;
+
+    let mut done = 0u64;
+    let mut files = Vec::new();
+    for (position, audio) in audio_files.iter().enumerate() {
+        let ino = audio
+            .ino
+            .clone()
+            .ok_or_else(|| ApiError::Parse("Audio file without an inode".to_string()))?;
+        // Server file names may contain anything; number the files instead.
+        let ext = audio
+            .metadata
+            .as_ref()
+            .and_then(|m| m.ext.as_deref())
+            .filter(|ext| ext.starts_with('.') && ext[1..].chars().all(char::is_alphanumeric))
+            .unwrap_or("");
+        let filename = format!("{:03}{}", position + 1, ext);
+        let dest = dir.join(&filename);
+        let size = size_of(audio);
+
+        let complete = size > 0 && fs::metadata(&dest).is_ok_and(|m| m.len() == size);
+        if !complete {
+            let written = client.download_item_file(
+                item_id,
+                &ino,
+                &dest,
+                &|written| progress(done + written, total),
+                cancel,
+            )?;
+            if size > 0 && written != size {
+                let _ = fs::remove_file(&dest);
+                return Err(ApiError::Network(format!(
+                    "{} ended after {} of {} bytes",
+                    filename, written, size
+                )));
+            }
+        }
+        done += size;
+        progress(done, total);
+        files.push(DownloadedFile {
+            ino,
+            filename,
+            duration: audio.duration.unwrap_or(0.0),
+        });
+    }
+
+    // A missing cover doesn't make the book unplayable.
+    if let Ok(bytes) = client.download_cover(item_id) {
+        let _ = fs::write(dir.join("cover.jpg"), bytes);
+    }
+
+    let metadata = media.and_then(|m| m.metadata.as_ref());
+    let manifest = DownloadManifest {
+        item_id: item_id.to_string(),
+        title: metadata.and_then(|m| m.title.clone()).unwrap_or_default(),
+        author: metadata
+            .and_then(|m| m.authors.as_ref())
+            .map(|authors| {
+                authors
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default(),
+        duration: media.and_then(|m| m.duration).unwrap_or(0.0),
+        files,
+        downloaded_at: glib::real_time() as u64 / 1000,
+    };
+    let json =
+        serde_json::to_string_pretty(&manifest).map_err(|e| ApiError::Parse(e.to_string()))?;
+    fs::write(manifest_path(item_id), json).map_err(|e| ApiError::Io(e.to_string()))?;
+    Ok(manifest)
+}
@@ -0,0 +1,117 @@
+/* links.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::window::RemoteCommand;
+use gtk::glib;
+use std::collections::HashMap;
+
+pub const SCHEME: &str = "shelfily";
+
+pub fn is_link(text: &str) -> bool {
+    text.get(..SCHEME.len() + 1)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+}
+
+/// Parses a deep link into the commands that open it:
+///
+/// - `shelfily://item/<item-id>` opens a book
+/// - `shelfily://item/<item-id>?t=<seconds>` plays a book from a position
+/// - `shelfily://author/<author-id>?name=<name>`
+/// - `shelfily://series/<series-name>`
+/// - `shelfily://collection/<collection-id>`
+/// - `shelfily://oauth?code=<code>&state=<state>` finishes a browser sign-in
+///
+/// Any link may name the server it belongs to with `server=<url>`.
+pub fn parse(link: &str) -> Result<Vec<RemoteCommand>, String> {
+    let invalid = || format!("Not a valid {} link: {}", SCHEME, link);
+    // Keep components escaped so separators inside values survive
+    let uri = glib::Uri::parse(link, glib::UriFlags::ENCODED).map_err(|_| invalid())?;
+    if !uri.scheme().eq_ignore_ascii_case(SCHEME) {
+        return Err(invalid());
+    }
+    let kind = uri.host().map(|h| h.to_lowercase()).unwrap_or_default();
+    if kind == "oauth" {
+        return Ok(vec![RemoteCommand::OAuthCallback(link.to_string())]);
+    }
+    let target = unescape(uri.path().trim_matches('/')).ok_or_else(invalid)?;
+    if target.is_empty() {
+        return Err(invalid());
+    }
+    let params = uri.query().map(|q| parse_query(&q)).unwrap_or_default();
+
+    let mut commands = Vec::new();
+    if let Some(server) = params.get("server") {
+        commands.push(RemoteCommand::Server(server.clone()));
+    }
+    commands.push(match kind.as_str() {
+        "item" => RemoteCommand::OpenItem {
+            item_id: target,
+            position: match params.get("t") {
+                Some(t) => Some(
+                    t.parse::<f64>()
+                        .ok()
+                        .filter(|t| t.is_finite() && *t >= 0.0)
+                        .ok_or_else(invalid)?,
+                ),
+                None => None,
+            },
+        },
+        "author" => RemoteCommand::OpenAuthor {
+            author_id: target,
+            name: params.get("name").cloned(),
+        },
+        "series" => RemoteCommand::OpenSeries(target),
+        "collection" => RemoteCommand::OpenCollection(target),
+        _ => return Err(invalid()),
+    });
+    Ok(commands)
+}
+
+pub fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Some((unescape(key)?, unescape(value)?))
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> Option<String> {
+    glib::Uri::unescape_string(text, None).map(|s| s.to_string())
+}
+
+fn escape(text: &str) -> String {
+    glib::Uri::escape_string(text, None, true).to_string()
+}
+
+/// Link to a book on `server_url`, optionally at a position in seconds.
+pub fn item_link(server_url: &str, item_id: &str, position: Option<f64>) -> String {
+    let mut link = format!(
+        "{}://item/{}?server={}",
+        SCHEME,
+        escape(item_id),
+        escape(server_url)
+    );
+    if let Some(position) = position {
+        link.push_str(&format!("&t={}", position.max(0.0).floor() as u64));
+    }
+    link
+}
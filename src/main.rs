@@ -12,8 +12,21 @@
 
 mod api;
 mod application;
+mod cache;
 mod config;
+mod connection;
+mod connectivity;
+mod downloads;
+mod links;
 mod models;
+mod notifications;
+mod oauth;
+mod power;
+mod search_provider;
+mod settings;
+mod tokens;
+mod trust;
+mod upload;
 mod window;
 
 use self::application::ShelfilyDesktopApplication;
@@ -49,7 +62,7 @@ fn main() -> glib::ExitCode {
 
     let app = ShelfilyDesktopApplication::new(
         "io.github.yusyel.ShelfilyDesktop",
-        &gio::ApplicationFlags::empty(),
+        &(gio::ApplicationFlags::HANDLES_COMMAND_LINE | gio::ApplicationFlags::HANDLES_OPEN),
     );
 
     app.run()
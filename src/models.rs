@@ -67,9 +67,83 @@ pub struct User {
     #[serde(rename = "mediaProgress")]
     pub media_progress: Option<Vec<MediaProgress>>,
     pub bookmarks: Option<Vec<Bookmark>>,
+    pub permissions: Option<UserPermissions>,
+    #[serde(rename = "librariesAccessible")]
+    pub libraries_accessible: Option<Vec<String>>,
+    #[serde(rename = "isActive")]
+    pub is_active: Option<bool>,
+    #[serde(rename = "isLocked")]
+    pub is_locked: Option<bool>,
+    #[serde(rename = "lastSeen")]
+    pub last_seen: Option<u64>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl User {
+    pub fn is_root(&self) -> bool {
+        self.user_type.as_deref() == Some("root")
+    }
+
+    /// Root users are admins as well; the server treats both as "admin or up".
+    pub fn is_admin(&self) -> bool {
+        matches!(self.user_type.as_deref(), Some("root") | Some("admin"))
+    }
+
+    fn has_permission(&self, pick: fn(&UserPermissions) -> Option<bool>) -> bool {
+        self.is_active.unwrap_or(true) && self.permissions.as_ref().and_then(pick).unwrap_or(false)
+    }
+
+    pub fn can_download(&self) -> bool {
+        self.has_permission(|p| p.download)
+    }
+
+    pub fn can_update(&self) -> bool {
+        self.has_permission(|p| p.update)
+    }
+
+    pub fn can_upload(&self) -> bool {
+        self.has_permission(|p| p.upload)
+    }
+
+    pub fn can_access_all_libraries(&self) -> bool {
+        self.has_permission(|p| p.access_all_libraries)
+    }
+
+    /// Whether the user may open the given library. Users without
+    /// `accessAllLibraries` are limited to `librariesAccessible`.
+    pub fn can_access_library(&self, library_id: &str) -> bool {
+        self.can_access_all_libraries()
+            || self
+                .libraries_accessible
+                .as_ref()
+                .map(|ids| ids.iter().any(|id| id == library_id))
+                .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserPermissions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload: Option<bool>,
+    #[serde(rename = "accessAllLibraries", skip_serializing_if = "Option::is_none")]
+    pub access_all_libraries: Option<bool>,
+    #[serde(rename = "accessAllTags", skip_serializing_if = "Option::is_none")]
+    pub access_all_tags: Option<bool>,
+    #[serde(
+        rename = "accessExplicitContent",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub access_explicit_content: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Bookmark {
     #[serde(rename = "libraryItemId")]
     pub library_item_id: Option<String>,
@@ -81,20 +155,43 @@ pub struct Bookmark {
 
 // ─── Libraries ──────────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Library {
     pub id: String,
     pub name: String,
     #[serde(rename = "mediaType")]
     pub media_type: Option<String>,
     pub icon: Option<String>,
+    pub folders: Option<Vec<LibraryFolder>>,
     #[serde(flatten)]
     pub extra: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LibraryFolder {
+    pub id: String,
+    #[serde(rename = "fullPath")]
+    pub full_path: Option<String>,
+}
+
+/// Distinct values found in a library, from GET /api/libraries/:id/filterdata
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct LibraryFilterData {
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub narrators: Vec<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default)]
+    pub publishers: Vec<String>,
+}
+
 // ─── Library Items ──────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LibraryItem {
     pub id: String,
     pub ino: Option<String>,
@@ -128,7 +225,7 @@ pub struct LibraryItemExpanded {
 
 // ─── Media ──────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Media {
     pub metadata: Option<Metadata>,
     #[serde(rename = "coverPath")]
@@ -166,7 +263,7 @@ pub struct MediaExpanded {
 
 // ─── Metadata ───────────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Metadata {
     pub title: Option<String>,
     #[serde(rename = "titleIgnorePrefix")]
@@ -211,8 +308,9 @@ pub struct MetadataExpanded {
     pub _extra: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Author {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub name: String,
 }
@@ -221,17 +319,171 @@ pub struct Author {
 pub struct AuthorExpanded {
     pub id: Option<String>,
     pub name: Option<String>,
+    #[serde(rename = "lastFirst")]
+    pub last_first: Option<String>,
+    pub asin: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "imagePath")]
+    pub image_path: Option<String>,
+    #[serde(rename = "libraryId")]
+    pub library_id: Option<String>,
+    #[serde(rename = "numBooks")]
+    pub num_books: Option<u32>,
+    #[serde(rename = "addedAt")]
+    pub added_at: Option<u64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<u64>,
     #[serde(rename = "libraryItems")]
     pub library_items: Option<Vec<LibraryItem>>,
+    pub series: Option<Vec<AuthorSeries>>,
+}
+
+impl AuthorExpanded {
+    pub fn has_image(&self) -> bool {
+        self.image_path.as_deref().is_some_and(|p| !p.is_empty())
+    }
+}
+
+/// A series entry of GET /api/authors/:id?include=series
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthorSeries {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub items: Option<Vec<LibraryItem>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
+pub struct LibraryAuthorsResponse {
+    pub authors: Vec<AuthorExpanded>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SeriesItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub name: Option<String>,
     pub sequence: Option<String>,
 }
 
+// ─── Metadata Editing ───────────────────────────────────────────────────────
+
+/// Body of PATCH /api/items/:id/media; only the fields that are set are changed
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MediaUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MetadataUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct MetadataUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<Author>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub narrators: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<Vec<SeriesItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genres: Option<Vec<String>>,
+    #[serde(rename = "publishedYear", skip_serializing_if = "Option::is_none")]
+    pub published_year: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isbn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl MetadataUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.subtitle.is_none()
+            && self.authors.is_none()
+            && self.narrators.is_none()
+            && self.series.is_none()
+            && self.genres.is_none()
+            && self.published_year.is_none()
+            && self.description.is_none()
+            && self.publisher.is_none()
+            && self.isbn.is_none()
+            && self.asin.is_none()
+            && self.language.is_none()
+    }
+}
+
+/// Metadata provider entry from GET /api/search/providers
+#[derive(Debug, Deserialize, Clone)]
+pub struct MetadataProvider {
+    pub value: String,
+    pub text: String,
+}
+
+/// Result of GET /api/search/books. Providers disagree on whether years and
+/// sequences are strings or numbers, so those stay as raw JSON values.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BookMatch {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub narrator: Option<String>,
+    pub publisher: Option<String>,
+    #[serde(rename = "publishedYear")]
+    pub published_year: Option<serde_json::Value>,
+    pub description: Option<String>,
+    pub cover: Option<String>,
+    pub isbn: Option<String>,
+    pub asin: Option<String>,
+    pub genres: Option<Vec<String>>,
+    pub series: Option<Vec<BookMatchSeries>>,
+    pub language: Option<String>,
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BookMatchSeries {
+    pub series: Option<String>,
+    pub sequence: Option<serde_json::Value>,
+}
+
+impl BookMatch {
+    pub fn published_year(&self) -> Option<String> {
+        json_scalar_to_string(self.published_year.as_ref())
+    }
+
+    pub fn series_items(&self) -> Vec<SeriesItem> {
+        self.series
+            .iter()
+            .flatten()
+            .filter_map(|s| {
+                let name = s.series.clone().filter(|n| !n.is_empty())?;
+                Some(SeriesItem {
+                    id: None,
+                    name: Some(name),
+                    sequence: json_scalar_to_string(s.sequence.as_ref()),
+                })
+            })
+            .collect()
+    }
+}
+
+fn json_scalar_to_string(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
 // ─── Audio ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Deserialize, Clone)]
@@ -271,7 +523,7 @@ pub struct AudioTrack {
     pub mime_type: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Chapter {
     pub id: Option<u32>,
     pub start: Option<f64>,
@@ -344,7 +596,7 @@ fn hostname() -> String {
 
 // ─── Media Progress ─────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MediaProgress {
     pub id: Option<String>,
     #[serde(rename = "libraryItemId")]
@@ -363,6 +615,39 @@ pub struct MediaProgress {
     pub started_at: Option<u64>,
     #[serde(rename = "finishedAt")]
     pub finished_at: Option<u64>,
+    #[serde(rename = "hideFromContinueListening")]
+    pub hide_from_continue_listening: Option<bool>,
+}
+
+// ─── Collections & Playlists ────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "libraryId")]
+    pub library_id: Option<String>,
+    pub description: Option<String>,
+    pub books: Option<Vec<LibraryItem>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "libraryId")]
+    pub library_id: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CollectionsResponse {
+    pub results: Vec<Collection>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlaylistsResponse {
+    pub results: Vec<Playlist>,
 }
 
 // ─── Personalized Shelves ───────────────────────────────────────────────────
@@ -378,3 +663,74 @@ pub struct PersonalizedShelf {
     pub entities: Option<Vec<serde_json::Value>>,
     pub total: Option<u32>,
 }
+
+// ─── Uploads ────────────────────────────────────────────────────────────────
+
+/// Destination and book fields sent with every file of an upload
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub library_id: String,
+    pub folder_id: String,
+    pub title: String,
+    pub author: String,
+    pub series: String,
+}
+
+// ─── Administration ─────────────────────────────────────────────────────────
+
+/// Background task reported by GET /api/tasks (library scans, encodes, ...)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ServerTask {
+    pub id: String,
+    pub action: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub error: Option<String>,
+    #[serde(rename = "isFailed")]
+    pub is_failed: Option<bool>,
+    #[serde(rename = "isFinished")]
+    pub is_finished: Option<bool>,
+    #[serde(rename = "startedAt")]
+    pub started_at: Option<u64>,
+    #[serde(rename = "finishedAt")]
+    pub finished_at: Option<u64>,
+}
+
+impl ServerTask {
+    pub fn library_id(&self) -> Option<&str> {
+        self.data
+            .as_ref()
+            .and_then(|d| d.get("libraryId"))
+            .and_then(|v| v.as_str())
+    }
+
+    pub fn is_library_scan(&self) -> bool {
+        self.action.as_deref() == Some("library-scan")
+    }
+}
+
+/// Fields accepted by POST /api/users and PATCH /api/users/:id
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct UserUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub user_type: Option<String>,
+    #[serde(rename = "isActive", skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<UserPermissions>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LogEntry {
+    pub timestamp: Option<String>,
+    pub source: Option<String>,
+    pub message: Option<String>,
+    #[serde(rename = "levelName")]
+    pub level_name: Option<String>,
+    pub level: Option<u32>,
+}
@@ -0,0 +1,145 @@
+/* notifications.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::settings;
+use gtk::gio;
+use gtk::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Which desktop notifications the user wants, kept in notifications.json
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NotificationSettings {
+    pub chapter_change: bool,
+    pub book_finished: bool,
+    pub sleep_timer: bool,
+    pub download_finished: bool,
+    pub sync_error: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            // Every chapter would be a lot of notifications
+            chapter_change: false,
+            book_finished: true,
+            sleep_timer: true,
+            download_finished: true,
+            sync_error: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn load() -> Self {
+        settings::load_config("notifications.json")
+    }
+
+    pub fn save(&self) {
+        settings::save_config("notifications.json", self);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    ChapterChange,
+    BookFinished,
+    SleepTimer,
+    DownloadFinished,
+    SyncError,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 5] = [
+        Self::ChapterChange,
+        Self::BookFinished,
+        Self::SleepTimer,
+        Self::DownloadFinished,
+        Self::SyncError,
+    ];
+
+    /// Notification id; a new notification replaces the last of its kind.
+    fn id(self) -> &'static str {
+        match self {
+            Self::ChapterChange => "chapter-change",
+            Self::BookFinished => "book-finished",
+            Self::SleepTimer => "sleep-timer",
+            Self::DownloadFinished => "download-finished",
+            Self::SyncError => "sync-error",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ChapterChange => "Chapter Changes",
+            Self::BookFinished => "Finished Books",
+            Self::SleepTimer => "Sleep Timer",
+            Self::DownloadFinished => "Finished Downloads",
+            Self::SyncError => "Sync Problems",
+        }
+    }
+
+    pub fn is_enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            Self::ChapterChange => settings.chapter_change,
+            Self::BookFinished => settings.book_finished,
+            Self::SleepTimer => settings.sleep_timer,
+            Self::DownloadFinished => settings.download_finished,
+            Self::SyncError => settings.sync_error,
+        }
+    }
+
+    pub fn set_enabled(self, settings: &mut NotificationSettings, enabled: bool) {
+        let setting = match self {
+            Self::ChapterChange => &mut settings.chapter_change,
+            Self::BookFinished => &mut settings.book_finished,
+            Self::SleepTimer => &mut settings.sleep_timer,
+            Self::DownloadFinished => &mut settings.download_finished,
+            Self::SyncError => &mut settings.sync_error,
+        };
+        *setting = enabled;
+    }
+}
+
+/// Sends a notification unless the user turned its kind off. `buttons` are
+/// labels with detailed app action names.
+pub fn send(
+    application: &gio::Application,
+    kind: NotificationKind,
+    title: &str,
+    body: &str,
+    buttons: &[(&str, &str)],
+) {
+    if !kind.is_enabled(&NotificationSettings::load()) {
+        return;
+    }
+    let notification = gio::Notification::new(title);
+    if !body.is_empty() {
+        notification.set_body(Some(body));
+    }
+    for (label, action) in buttons {
+        notification.add_button(label, action);
+    }
+    application.send_notification(Some(kind.id()), &notification);
+}
+
+pub fn withdraw(application: &gio::Application, kind: NotificationKind) {
+    application.withdraw_notification(kind.id());
+}
@@ -0,0 +1,222 @@
+/* oauth.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::links;
+use gtk::{gio, glib};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A browser sign-in waiting for its redirect
+#[derive(Debug)]
+pub struct PendingSignIn {
+    pub server_url: String,
+    pub pkce: Pkce,
+    /// Session cookie from starting the sign-in, the server needs it back
+    /// to match the code to the PKCE challenge
+    pub cookie: String,
+    /// Stops the loopback listener, if there is one
+    pub cancel: Arc<AtomicBool>,
+}
+
+/// Where the server sends the browser back to when the desktop file
+/// registers the app for shelfily:// links. The server only accepts it
+/// once it is listed under the allowed mobile redirect URIs.
+pub const REDIRECT_URI: &str = "shelfily://oauth";
+
+/// Port of the loopback redirect, http://127.0.0.1:47813/callback. It is
+/// fixed because the server only accepts redirect URIs it lists exactly,
+/// so that one has to be allowed as well for the fallback to work.
+const LOOPBACK_PORT: u16 = 47813;
+
+/// How long to wait for the browser before giving up on a loopback sign-in
+const LOOPBACK_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// PKCE verifier and challenge (RFC 7636) and the state that ties the
+/// redirect to this sign-in
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+    pub state: String,
+}
+
+impl Pkce {
+    pub fn new() -> std::io::Result<Self> {
+        let verifier = base64url(&random_bytes(32)?);
+        let mut checksum =
+            glib::Checksum::new(glib::ChecksumType::Sha256).expect("SHA-256 is always available");
+        checksum.update(verifier.as_bytes());
+        let challenge = base64url(&checksum.digest());
+        Ok(Self {
+            verifier,
+            challenge,
+            state: base64url(&random_bytes(16)?),
+        })
+    }
+}
+
+fn random_bytes(len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    glib::base64_encode(bytes)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// Whether shelfily:// links reach this app, so the browser can hand the
+/// sign-in back through `REDIRECT_URI`
+pub fn scheme_handler_registered() -> bool {
+    gio::AppInfo::default_for_uri_scheme(links::SCHEME).is_some()
+}
+
+/// Query parameters of a redirect, e.g. `code`, `state` or `error`
+pub fn callback_params(uri: &str) -> HashMap<String, String> {
+    glib::Uri::parse(uri, glib::UriFlags::ENCODED)
+        .ok()
+        .and_then(|uri| uri.query())
+        .map(|query| links::parse_query(&query))
+        .unwrap_or_default()
+}
+
+/// Whether a redirect answers the sign-in started with `state`: it has to
+/// carry that state and either a code or an error. Anything else, like a
+/// stale tab or another page hitting the callback, is ignored.
+pub fn answers_sign_in(params: &HashMap<String, String>, state: &str) -> bool {
+    params.get("state").is_some_and(|s| s == state)
+        && (params.contains_key("code") || params.contains_key("error"))
+}
+
+/// One-shot HTTP listener on 127.0.0.1 that receives the redirect when
+/// the app can't be reached through its URI scheme
+pub struct LoopbackListener {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl LoopbackListener {
+    pub fn bind() -> std::io::Result<Self> {
+        // A cancelled sign-in may still hold the port for a moment
+        let mut attempts = 0;
+        let listener = loop {
+            match TcpListener::bind(("127.0.0.1", LOOPBACK_PORT)) {
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse && attempts < 3 => {
+                    attempts += 1;
+                    std::thread::sleep(Duration::from_millis(250));
+                }
+                result => break result?,
+            }
+        };
+        let port = listener.local_addr()?.port();
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, port })
+    }
+
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}/callback", self.port)
+    }
+
+    /// Blocks until the browser is redirected to the callback of the
+    /// sign-in started with `state`, the sign-in is cancelled (`None`) or
+    /// the wait times out. Run it on a worker thread.
+    pub fn wait(
+        self,
+        state: &str,
+        cancel: Arc<AtomicBool>,
+    ) -> Result<Option<HashMap<String, String>>, String> {
+        let started = Instant::now();
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            if started.elapsed() > LOOPBACK_TIMEOUT {
+                return Err("Timed out waiting for the browser".to_string());
+            }
+            let mut stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(200));
+                    continue;
+                }
+                Err(e) => return Err(e.to_string()),
+            };
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            let mut request_line = String::new();
+            let _ = BufReader::new(&stream).read_line(&mut request_line);
+            // "GET /callback?code=…&state=… HTTP/1.1"
+            let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let Some(query) = target.strip_prefix("/callback") else {
+                let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+                continue;
+            };
+            let params = links::parse_query(query.trim_start_matches('?'));
+            if !answers_sign_in(&params, state) {
+                write_page(
+                    &mut stream,
+                    "400 Bad Request",
+                    "Unknown Sign-In",
+                    "This page doesn't belong to the sign-in in progress.",
+                );
+                continue;
+            }
+            if params.contains_key("error") {
+                write_page(
+                    &mut stream,
+                    "200 OK",
+                    "Sign-In Failed",
+                    "Return to Shelfily Desktop to try again.",
+                );
+            } else {
+                write_page(
+                    &mut stream,
+                    "200 OK",
+                    "Signed In",
+                    "You can close this tab and return to Shelfily Desktop.",
+                );
+            }
+            return Ok(Some(params));
+        }
+    }
+}
+
+fn write_page(stream: &mut TcpStream, status: &str, title: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Shelfily Desktop</title></head>\
+<body style=\"font-family: sans-serif; text-align: center; margin-top: 4em\">\
+<h1>{}</h1><p>{}</p></body></html>",
+        title, message
+    );
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
@@ -0,0 +1,148 @@
+/* power.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::settings;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const LOGIND_NAME: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+/// Power preferences, kept in power.json
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PowerSettings {
+    /// Keep the system awake while a book plays
+    pub inhibit_suspend: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            inhibit_suspend: true,
+        }
+    }
+}
+
+impl PowerSettings {
+    pub fn load() -> Self {
+        settings::load_config("power.json")
+    }
+
+    pub fn save(&self) {
+        settings::save_config("power.json", self);
+    }
+}
+
+/// Follows logind's PrepareForSleep. While awake it holds a delay
+/// inhibitor, so logind waits (up to its InhibitDelayMaxSec) until
+/// `release_lock` before the system actually sleeps.
+#[derive(Debug)]
+pub struct SleepMonitor {
+    connection: gio::DBusConnection,
+    // logind releases the delay lock when its file descriptor closes
+    lock: RefCell<Option<gio::UnixFDList>>,
+}
+
+impl SleepMonitor {
+    /// Connects to the system bus; `on_sleep` gets `true` before sleeping and
+    /// `false` after resuming. Without logind nothing happens.
+    pub async fn start(on_sleep: impl Fn(&Rc<SleepMonitor>, bool) + 'static) -> Option<Rc<Self>> {
+        let connection = match gio::bus_get_future(gio::BusType::System).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::info!("No system bus, not following suspend: {}", e);
+                return None;
+            }
+        };
+        let monitor = Rc::new(Self {
+            connection: connection.clone(),
+            lock: RefCell::new(None),
+        });
+        monitor.take_lock().await;
+
+        let weak = Rc::downgrade(&monitor);
+        connection.signal_subscribe(
+            Some(LOGIND_NAME),
+            Some(LOGIND_MANAGER),
+            Some("PrepareForSleep"),
+            Some(LOGIND_PATH),
+            None,
+            gio::DBusSignalFlags::NONE,
+            move |_, _, _, _, _, params| {
+                let (Some(monitor), Some((sleeping,))) = (weak.upgrade(), params.get::<(bool,)>())
+                else {
+                    return;
+                };
+                if sleeping {
+                    log::info!("System is about to sleep");
+                } else {
+                    log::info!("System resumed");
+                    let monitor = monitor.clone();
+                    glib::spawn_future_local(async move { monitor.take_lock().await });
+                }
+                on_sleep(&monitor, sleeping);
+            },
+        );
+        Some(monitor)
+    }
+
+    async fn take_lock(&self) {
+        if self.lock.borrow().is_some() {
+            return;
+        }
+        let result = self
+            .connection
+            .call_with_unix_fd_list_future(
+                Some(LOGIND_NAME),
+                LOGIND_PATH,
+                LOGIND_MANAGER,
+                "Inhibit",
+                Some(
+                    &(
+                        "sleep",
+                        "Shelfily Desktop",
+                        "Saving listening progress",
+                        "delay",
+                    )
+                        .to_variant(),
+                ),
+                Some(glib::VariantTy::new("(h)").unwrap()),
+                gio::DBusCallFlags::NONE,
+                -1,
+                None::<&gio::UnixFDList>,
+            )
+            .await;
+        match result {
+            Ok((_, Some(fds))) => *self.lock.borrow_mut() = Some(fds),
+            Ok((_, None)) => log::warn!("logind returned no inhibitor lock"),
+            Err(e) => log::warn!("Failed to take a sleep delay lock: {}", e),
+        }
+    }
+
+    /// Lets the pending sleep go ahead.
+    pub fn release_lock(&self) {
+        self.lock.borrow_mut().take();
+    }
+}
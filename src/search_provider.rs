@@ -0,0 +1,256 @@
+/* search_provider.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::cache;
+use crate::models::LibraryItem;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="org.gnome.Shell.SearchProvider2">
+    <method name="GetInitialResultSet">
+      <arg type="as" name="terms" direction="in"/>
+      <arg type="as" name="results" direction="out"/>
+    </method>
+    <method name="GetSubsearchResultSet">
+      <arg type="as" name="previous_results" direction="in"/>
+      <arg type="as" name="terms" direction="in"/>
+      <arg type="as" name="results" direction="out"/>
+    </method>
+    <method name="GetResultMetas">
+      <arg type="as" name="identifiers" direction="in"/>
+      <arg type="aa{sv}" name="metas" direction="out"/>
+    </method>
+    <method name="ActivateResult">
+      <arg type="s" name="identifier" direction="in"/>
+      <arg type="as" name="terms" direction="in"/>
+      <arg type="u" name="timestamp" direction="in"/>
+    </method>
+    <method name="LaunchSearch">
+      <arg type="as" name="terms" direction="in"/>
+      <arg type="u" name="timestamp" direction="in"/>
+    </method>
+  </interface>
+</node>
+"#;
+
+/// Appended to the application's object path; must match the .ini file
+pub const OBJECT_PATH_SUFFIX: &str = "/SearchProvider";
+
+/// What the shell asks the application to do with a result
+pub enum SearchRequest {
+    Activate(String),
+    Launch(String),
+}
+
+/// One searchable book. The index lives on disk so the provider answers
+/// while no window (and no server connection) exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SearchEntry {
+    id: String,
+    title: String,
+    author: String,
+    narrator: String,
+    series: String,
+}
+
+impl SearchEntry {
+    fn matches(&self, terms: &[String]) -> bool {
+        let haystack = format!(
+            "{}\n{}\n{}\n{}",
+            self.title, self.author, self.narrator, self.series
+        )
+        .to_lowercase();
+        terms.iter().all(|term| haystack.contains(term.as_str()))
+    }
+}
+
+/// Books of the current account across all of its libraries, rebuilt
+/// from the per-library entries in the account's cache
+fn index_path() -> PathBuf {
+    let mut path = cache::cache_dir();
+    path.push("search-index.json");
+    path
+}
+
+/// Replaces a library's books in the index.
+pub fn write_index(library_id: &str, items: &[LibraryItem]) {
+    let entries: Vec<SearchEntry> = items
+        .iter()
+        .map(|item| {
+            let metadata = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+            let field = |f: fn(&crate::models::Metadata) -> Option<&String>| {
+                metadata.and_then(f).cloned().unwrap_or_default()
+            };
+            SearchEntry {
+                id: item.id.clone(),
+                title: field(|m| m.title.as_ref()),
+                author: field(|m| m.author_name.as_ref()),
+                narrator: field(|m| m.narrator_name.as_ref()),
+                series: field(|m| m.series_name.as_ref()),
+            }
+        })
+        .collect();
+    cache::save_state(&format!("search-{}", library_id), &entries);
+    rebuild_index();
+}
+
+/// Makes the index hold the books of the current account, after writing a
+/// library or switching accounts.
+pub fn rebuild_index() {
+    let mut seen = std::collections::HashSet::new();
+    let entries: Vec<SearchEntry> = cache::state_names("search-")
+        .iter()
+        .filter_map(|name| cache::load_state::<Vec<SearchEntry>>(name))
+        .flatten()
+        .filter(|entry| seen.insert(entry.id.clone()))
+        .collect();
+    if entries.is_empty() {
+        clear_index();
+        return;
+    }
+    let path = index_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_vec(&entries) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                log::warn!("Failed to write search index: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize search index: {}", e),
+    }
+}
+
+/// Forgets the signed-out account's books.
+pub fn clear_index() {
+    let _ = std::fs::remove_file(index_path());
+}
+
+fn read_index() -> Vec<SearchEntry> {
+    std::fs::read(index_path())
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Matching ids, books whose title matches first.
+fn search(entries: &[SearchEntry], terms: &[String]) -> Vec<String> {
+    let terms: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+    let mut matches: Vec<&SearchEntry> = entries.iter().filter(|e| e.matches(&terms)).collect();
+    matches.sort_by_key(|e| {
+        let title = e.title.to_lowercase();
+        !terms.iter().all(|term| title.contains(term.as_str()))
+    });
+    matches.into_iter().map(|e| e.id.clone()).collect()
+}
+
+fn result_meta(entry: &SearchEntry) -> glib::Variant {
+    let dict = glib::VariantDict::new(None);
+    dict.insert("id", &entry.id);
+    dict.insert("name", &entry.title);
+    let description = [&entry.author, &entry.narrator, &entry.series]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" · ");
+    dict.insert("description", &description);
+    let cover = cache::cover_path(&entry.id);
+    let icon: gio::Icon = if cover.exists() {
+        gio::FileIcon::new(&gio::File::for_path(&cover)).upcast()
+    } else {
+        gio::ThemedIcon::new("audio-x-generic").upcast()
+    };
+    if let Some(icon) = icon.serialize() {
+        dict.insert_value("icon", &icon);
+    }
+    dict.end()
+}
+
+/// Exports the provider next to the application object; `on_request`
+/// handles activations on the main thread.
+pub fn register(
+    connection: &gio::DBusConnection,
+    application_path: &str,
+    on_request: impl Fn(SearchRequest) + 'static,
+) -> Result<gio::RegistrationId, glib::Error> {
+    let node = gio::DBusNodeInfo::for_xml(INTERFACE_XML)?;
+    let interface = node
+        .lookup_interface("org.gnome.Shell.SearchProvider2")
+        .ok_or_else(|| glib::Error::new(gio::IOErrorEnum::NotFound, "Missing interface"))?;
+    connection
+        .register_object(
+            &format!("{}{}", application_path, OBJECT_PATH_SUFFIX),
+            &interface,
+        )
+        .method_call(move |_, _, _, _, method, params, invocation| {
+            let result = match method {
+                "GetInitialResultSet" => params
+                    .get::<(Vec<String>,)>()
+                    .map(|(terms,)| (search(&read_index(), &terms),).to_variant()),
+                "GetSubsearchResultSet" => {
+                    params
+                        .get::<(Vec<String>, Vec<String>)>()
+                        .map(|(previous, terms)| {
+                            let entries: Vec<SearchEntry> = read_index()
+                                .into_iter()
+                                .filter(|e| previous.contains(&e.id))
+                                .collect();
+                            (search(&entries, &terms),).to_variant()
+                        })
+                }
+                "GetResultMetas" => params.get::<(Vec<String>,)>().map(|(ids,)| {
+                    let entries = read_index();
+                    let metas: Vec<glib::Variant> = ids
+                        .iter()
+                        .filter_map(|id| entries.iter().find(|e| &e.id == id))
+                        .map(result_meta)
+                        .collect();
+                    let metas =
+                        glib::Variant::array_from_iter_with_type(glib::VariantTy::VARDICT, metas);
+                    glib::Variant::tuple_from_iter([metas])
+                }),
+                "ActivateResult" => params
+                    .get::<(String, Vec<String>, u32)>()
+                    .map(|(id, _, _)| {
+                        on_request(SearchRequest::Activate(id));
+                        ().to_variant()
+                    }),
+                "LaunchSearch" => params.get::<(Vec<String>, u32)>().map(|(terms, _)| {
+                    on_request(SearchRequest::Launch(terms.join(" ")));
+                    ().to_variant()
+                }),
+                _ => None,
+            };
+            match result {
+                Some(value) => invocation.return_value(Some(&value)),
+                None => invocation.return_dbus_error(
+                    "org.freedesktop.DBus.Error.InvalidArgs",
+                    &format!("Invalid call of {}", method),
+                ),
+            }
+        })
+        .build()
+}
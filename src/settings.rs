@@ -0,0 +1,55 @@
+/* settings.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Settings files, in user_config_dir/shelfily-desktop so they outlive
+/// a cleared cache.
+pub fn config_path(name: &str) -> PathBuf {
+    let mut path = gtk::glib::user_config_dir();
+    path.push("shelfily-desktop");
+    path.push(name);
+    path
+}
+
+/// Reads a JSON settings file, or the defaults when it is missing or unreadable.
+pub fn load_config<T: DeserializeOwned + Default>(name: &str) -> T {
+    std::fs::read_to_string(config_path(name))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config<T: Serialize + ?Sized>(name: &str, value: &T) {
+    let path = config_path(name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                log::warn!("Failed to save {}: {}", name, e);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize {}: {}", name, e),
+    }
+}
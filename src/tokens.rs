@@ -0,0 +1,200 @@
+/* tokens.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::glib;
+use std::sync::{Mutex, MutexGuard};
+
+/// Access tokens are refreshed this long before they expire
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenEvent {
+    /// A refresh rotated the tokens; the new pair has to be stored
+    Refreshed {
+        server_url: String,
+        access_token: String,
+        refresh_token: String,
+    },
+    /// The server rejected the refresh token, so the user must sign in again
+    Expired { server_url: String },
+}
+
+/// Serializes token refreshes between worker threads and reports their
+/// outcome to the UI.
+#[derive(Debug, Default)]
+pub struct TokenManager {
+    refresh_lock: Mutex<()>,
+    events: Mutex<Vec<async_channel::Sender<TokenEvent>>>,
+}
+
+impl TokenManager {
+    /// Held for the whole refresh, so a second thread waits for the first
+    /// one's result instead of spending the same refresh token again.
+    pub fn lock_refresh(&self) -> MutexGuard<'_, ()> {
+        self.refresh_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Every subscriber gets every event until it drops its receiver.
+    pub fn subscribe(&self) -> async_channel::Receiver<TokenEvent> {
+        let (tx, rx) = async_channel::unbounded();
+        self.events.lock().unwrap().push(tx);
+        rx
+    }
+
+    pub fn emit(&self, event: TokenEvent) {
+        self.events
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.try_send(event.clone()).is_ok());
+    }
+}
+
+/// The payload of a JWT. The signature isn't checked, so the claims are
+/// only good for decisions the server re-checks anyway.
+fn claims(token: &str) -> Option<serde_json::Value> {
+    let payload = token.split('.').nth(1)?;
+    let mut base64: String = payload
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    while !base64.len().is_multiple_of(4) {
+        base64.push('=');
+    }
+    serde_json::from_slice(&glib::base64_decode(&base64)).ok()
+}
+
+/// Unix time of a JWT's `exp` claim, for deciding when to refresh. Tokens
+/// without the claim don't expire.
+pub fn expiry(token: &str) -> Option<i64> {
+    claims(token)?.get("exp")?.as_i64()
+}
+
+/// The user a session token or API key was issued to
+pub fn user_id(token: &str) -> Option<String> {
+    Some(claims(token)?.get("userId")?.as_str()?.to_string())
+}
+
+pub fn expires_soon(token: &str) -> bool {
+    let now = glib::real_time() / 1_000_000;
+    expiry(token).is_some_and(|exp| exp - now < REFRESH_MARGIN_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An unsigned JWT with the given claims, base64url encoded like the
+    /// server does
+    fn jwt(claims: &str) -> String {
+        let payload: String = glib::base64_encode(claims.as_bytes())
+            .chars()
+            .filter(|&c| c != '=')
+            .map(|c| match c {
+                '+' => '-',
+                '/' => '_',
+                c => c,
+            })
+            .collect();
+        format!("eyJhbGciOiJIUzI1NiJ9.{}.signature", payload)
+    }
+
+    fn now() -> i64 {
+        glib::real_time() / 1_000_000
+    }
+
+    #[test]
+    fn expiry_reads_exp_claim() {
+        let token = jwt(r#"{"userId":"u1","exp":1700000000}"#);
+        assert_eq!(expiry(&token), Some(1_700_000_000));
+    }
+
+    #[test]
+    fn token_without_exp_never_expires() {
+        let token = jwt(r#"{"userId":"u1"}"#);
+        assert_eq!(expiry(&token), None);
+        assert!(!expires_soon(&token));
+    }
+
+    #[test]
+    fn malformed_token_has_no_expiry() {
+        assert_eq!(expiry("not a jwt"), None);
+        assert_eq!(expiry("header.!!!not*base64!!!.signature"), None);
+        assert_eq!(expiry(&format!("h.{}.s", "e30")), None);
+        assert!(!expires_soon("header.%%%.signature"));
+    }
+
+    #[test]
+    fn expired_token_expires_soon() {
+        let token = jwt(&format!(r#"{{"exp":{}}}"#, now() - 3600));
+        assert!(expires_soon(&token));
+    }
+
+    #[test]
+    fn token_within_margin_expires_soon() {
+        let token = jwt(&format!(r#"{{"exp":{}}}"#, now() + REFRESH_MARGIN_SECS / 2));
+        assert!(expires_soon(&token));
+    }
+
+    #[test]
+    fn fresh_token_does_not_expire_soon() {
+        let token = jwt(&format!(r#"{{"exp":{}}}"#, now() + 3600));
+        assert!(!expires_soon(&token));
+    }
+
+    #[test]
+    fn user_id_reads_claim() {
+        assert_eq!(
+            user_id(&jwt(r#"{"userId":"root","type":"api"}"#)).as_deref(),
+            Some("root")
+        );
+        assert_eq!(user_id(&jwt(r#"{"exp":1}"#)), None);
+    }
+
+    #[test]
+    fn every_subscriber_gets_events() {
+        let manager = TokenManager::default();
+        let first = manager.subscribe();
+        let second = manager.subscribe();
+        let event = TokenEvent::Expired {
+            server_url: "https://abs.example".to_string(),
+        };
+        manager.emit(event.clone());
+        assert_eq!(first.try_recv().ok(), Some(event.clone()));
+        assert_eq!(second.try_recv().ok(), Some(event));
+    }
+
+    #[test]
+    fn dropped_subscribers_are_forgotten() {
+        let manager = TokenManager::default();
+        drop(manager.subscribe());
+        let kept = manager.subscribe();
+        manager.emit(TokenEvent::Expired {
+            server_url: String::new(),
+        });
+        assert_eq!(manager.events.lock().unwrap().len(), 1);
+        assert!(kept.try_recv().is_ok());
+    }
+}
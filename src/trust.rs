@@ -0,0 +1,482 @@
+/* trust.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::connection::ConnectionSettings;
+use crate::settings;
+use glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+/// What the user decided to trust for one server, kept per origin in
+/// trust.json. Both certificates are stored as PEM.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ServerTrust {
+    /// The exact certificate the server presented when the user pinned it
+    pub pinned_certificate: Option<String>,
+    /// A private CA bundle that signs the server's certificate
+    pub ca_bundle: Option<String>,
+}
+
+impl ServerTrust {
+    /// SHA-256 fingerprint of the pinned certificate
+    pub fn pin(&self) -> Option<String> {
+        self.pinned_certificate.as_deref().and_then(pem_fingerprint)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pinned_certificate.is_none() && self.ca_bundle.is_none()
+    }
+}
+
+fn load_store() -> HashMap<String, ServerTrust> {
+    settings::load_config("trust.json")
+}
+
+fn save_store(store: &HashMap<String, ServerTrust>) {
+    settings::save_config("trust.json", store);
+}
+
+/// scheme://host:port of a server URL, the key trust decisions are kept under
+pub fn origin(url: &str) -> Option<String> {
+    let uri = glib::Uri::parse(url.trim(), glib::UriFlags::NONE).ok()?;
+    let scheme = uri.scheme().to_lowercase();
+    let host = uri.host()?.to_lowercase();
+    let port = match uri.port() {
+        -1 if scheme == "https" => 443,
+        -1 => 80,
+        port => port,
+    };
+    Some(format!("{}://{}:{}", scheme, host, port))
+}
+
+/// Host name of a server URL, as WebKit expects it
+pub fn host(url: &str) -> Option<String> {
+    let uri = glib::Uri::parse(url.trim(), glib::UriFlags::NONE).ok()?;
+    uri.host().map(|h| h.to_string())
+}
+
+pub fn load(server_url: &str) -> Option<ServerTrust> {
+    let origin = origin(server_url)?;
+    load_store().remove(&origin).filter(|t| !t.is_empty())
+}
+
+pub fn save(server_url: &str, trust: &ServerTrust) {
+    let Some(origin) = origin(server_url) else {
+        return;
+    };
+    let mut store = load_store();
+    if trust.is_empty() {
+        store.remove(&origin);
+    } else {
+        store.insert(origin, trust.clone());
+    }
+    save_store(&store);
+    write_anchors(server_url, trust);
+}
+
+/// PEM file with the imported CA bundle of a server, for consumers that
+/// take a GTlsDatabase
+pub fn anchors_path(server_url: &str) -> Option<PathBuf> {
+    let trust = load(server_url)?;
+    trust.ca_bundle.as_ref()?;
+    let path = anchors_file(server_url)?;
+    write_anchors(server_url, &trust);
+    Some(path)
+}
+
+fn anchors_file(server_url: &str) -> Option<PathBuf> {
+    let origin = origin(server_url)?;
+    let name = glib::compute_checksum_for_string(glib::ChecksumType::Sha256, &origin)?;
+    let mut path = settings::config_path("certificates");
+    path.push(format!("{}.pem", name));
+    Some(path)
+}
+
+fn write_anchors(server_url: &str, trust: &ServerTrust) {
+    let Some(path) = anchors_file(server_url) else {
+        return;
+    };
+    let Some(ca_bundle) = trust.ca_bundle.as_deref() else {
+        let _ = std::fs::remove_file(&path);
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&path, ca_bundle) {
+        log::warn!("Failed to write certificate anchors: {}", e);
+    }
+}
+
+/// SHA-256 fingerprint of a DER certificate, as colon separated hex
+pub fn fingerprint(der: &[u8]) -> String {
+    let hex = glib::compute_checksum_for_data(glib::ChecksumType::Sha256, der)
+        .map(|s| s.to_uppercase())
+        .unwrap_or_default();
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub fn der_to_pem(der: &[u8]) -> String {
+    let base64 = glib::base64_encode(der);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in base64.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+/// Fingerprint of the first certificate in a PEM string
+pub fn pem_fingerprint(pem: &str) -> Option<String> {
+    let body: String = pem
+        .lines()
+        .skip_while(|l| !l.starts_with("-----BEGIN CERTIFICATE-----"))
+        .skip(1)
+        .take_while(|l| !l.starts_with("-----END CERTIFICATE-----"))
+        .collect();
+    let der = glib::base64_decode(&body);
+    (!der.is_empty()).then(|| fingerprint(&der))
+}
+
+/// Checks that a file holds at least one PEM certificate
+pub fn validate_ca_bundle(pem: &str) -> Result<(), String> {
+    match reqwest::Certificate::from_pem_bundle(pem.as_bytes()) {
+        Ok(certs) if !certs.is_empty() => Ok(()),
+        Ok(_) => Err("No certificates found in file".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Connects without verifying the certificate, only to show it to the
+/// user. Nothing is sent besides an unauthenticated status request.
+pub fn fetch_certificate(server_url: &str) -> Result<Vec<u8>, String> {
+    let builder = reqwest::blocking::Client::builder().timeout(std::time::Duration::from_secs(15));
+    let client = crate::connection::load(server_url)
+        .configure_transport(builder)
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("{}/status", server_url.trim_end_matches('/'));
+    let resp = client.get(&url).send().map_err(|e| e.to_string())?;
+    resp.extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .map(|der| der.to_vec())
+        .ok_or_else(|| "The server did not present a certificate".to_string())
+}
+
+/// Accepts the pinned certificate by its fingerprint, whatever host name
+/// or issuer it carries, and checks anything else against the CA bundle
+#[derive(Debug)]
+struct PinVerifier {
+    pin: String,
+    roots: Option<Arc<WebPkiServerVerifier>>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if fingerprint(end_entity) == self.pin {
+            return Ok(ServerCertVerified::assertion());
+        }
+        match &self.roots {
+            Some(verifier) => verifier.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            ),
+            None => Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            )),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// PEM files the system keeps its trusted CAs in, by distribution
+const SYSTEM_CA_FILES: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/pki/tls/cacert.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// The system's trusted CAs, which rustls doesn't load by itself.
+/// `SSL_CERT_FILE` wins like it does for OpenSSL.
+fn system_roots() -> rustls::RootCertStore {
+    let mut roots = rustls::RootCertStore::empty();
+    let files = std::env::var_os("SSL_CERT_FILE")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(SYSTEM_CA_FILES.iter().map(PathBuf::from));
+    for file in files {
+        let Ok(pem) = std::fs::read(&file) else {
+            continue;
+        };
+        let certs = CertificateDer::pem_slice_iter(&pem).filter_map(Result::ok);
+        let (added, _) = roots.add_parsable_certificates(certs);
+        if added > 0 {
+            break;
+        }
+    }
+    if roots.is_empty() {
+        log::warn!("No system CA certificates found");
+    }
+    roots
+}
+
+/// TLS configuration for the API client of a pinned server, or None when
+/// nothing is pinned and the platform's verification applies. Besides the
+/// pinned certificate, the system roots and the imported CA bundle are
+/// trusted as usual. The client certificate has to be set here, reqwest
+/// ignores its own identity once the TLS configuration is given.
+pub fn pinned_tls_config(
+    trust: &ServerTrust,
+    connection: &ConnectionSettings,
+) -> Result<Option<rustls::ClientConfig>, String> {
+    let Some(pin) = trust.pin() else {
+        return Ok(None);
+    };
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut roots = system_roots();
+    if let Some(pem) = trust.ca_bundle.as_deref() {
+        for cert in CertificateDer::pem_slice_iter(pem.as_bytes()) {
+            roots
+                .add(cert.map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    let roots = if roots.is_empty() {
+        None
+    } else {
+        let verifier =
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .map_err(|e| e.to_string())?;
+        Some(verifier)
+    };
+    let verifier = PinVerifier {
+        pin,
+        roots,
+        algorithms: provider.signature_verification_algorithms,
+    };
+    let builder = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier));
+    let config = match (
+        connection.client_certificate.as_deref(),
+        connection.client_key.as_deref(),
+    ) {
+        (Some(cert), Some(key)) => {
+            let chain = CertificateDer::pem_slice_iter(cert.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            let key = PrivateKeyDer::from_pem_slice(key.as_bytes()).map_err(|e| e.to_string())?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| e.to_string())?
+        }
+        _ => builder.with_no_client_auth(),
+    };
+    Ok(Some(config))
+}
+
+/// GTlsDatabase applying the same rule as the API client, for consumers
+/// that take one (GStreamer's souphttpsrc)
+pub fn tls_database(server_url: &str) -> Option<gio::TlsDatabase> {
+    let trust = load(server_url)?;
+    let ca_bundle = anchors_path(server_url).and_then(|path| {
+        gio::TlsFileDatabase::new(path)
+            .map_err(|e| log::warn!("Could not load trusted certificates: {}", e))
+            .ok()
+    });
+    match trust.pin() {
+        Some(pin) => {
+            // Without a bundle the system roots still apply, as for the API
+            let roots = match ca_bundle {
+                Some(db) => db.upcast(),
+                None => gio::TlsBackend::default().default_database(),
+            };
+            Some(PinnedTlsDatabase::new(pin, Some(roots)).upcast())
+        }
+        None => ca_bundle.map(|db| db.upcast()),
+    }
+}
+
+mod imp {
+    use super::*;
+
+    /// Only read after construction, from the thread doing the handshake
+    #[derive(Debug, Default)]
+    pub struct PinnedTlsDatabase {
+        pub pin: OnceLock<String>,
+        pub ca_bundle: OnceLock<Option<gio::TlsDatabase>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PinnedTlsDatabase {
+        const NAME: &'static str = "ShelfilyPinnedTlsDatabase";
+        type Type = super::PinnedTlsDatabase;
+        type ParentType = gio::TlsDatabase;
+    }
+
+    impl ObjectImpl for PinnedTlsDatabase {}
+
+    impl PinnedTlsDatabase {
+        pub fn ca_bundle(&self) -> Option<&gio::TlsDatabase> {
+            self.ca_bundle.get().and_then(Option::as_ref)
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct PinnedTlsDatabase(ObjectSubclass<imp::PinnedTlsDatabase>)
+        @extends gio::TlsDatabase;
+}
+
+impl PinnedTlsDatabase {
+    fn new(pin: String, ca_bundle: Option<gio::TlsDatabase>) -> Self {
+        let database: Self = glib::Object::new();
+        let _ = database.imp().pin.set(pin);
+        let _ = database.imp().ca_bundle.set(ca_bundle);
+        database
+    }
+}
+
+// gio has no subclassing support for GTlsDatabase, so the class methods
+// are set directly
+unsafe impl IsSubclassable<imp::PinnedTlsDatabase> for gio::TlsDatabase {
+    fn class_init(class: &mut glib::Class<Self>) {
+        Self::parent_class_init::<imp::PinnedTlsDatabase>(class);
+        let klass = class.as_mut();
+        klass.verify_chain = Some(pinned_verify_chain);
+        klass.lookup_certificate_issuer = Some(pinned_lookup_certificate_issuer);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn pinned_verify_chain(
+    database: *mut gio::ffi::GTlsDatabase,
+    chain: *mut gio::ffi::GTlsCertificate,
+    purpose: *const std::ffi::c_char,
+    identity: *mut gio::ffi::GSocketConnectable,
+    interaction: *mut gio::ffi::GTlsInteraction,
+    flags: gio::ffi::GTlsDatabaseVerifyFlags,
+    cancellable: *mut gio::ffi::GCancellable,
+    error: *mut *mut glib::ffi::GError,
+) -> gio::ffi::GTlsCertificateFlags {
+    let instance = &*(database as *mut <imp::PinnedTlsDatabase as ObjectSubclass>::Instance);
+    let imp = instance.imp();
+    let certificate: glib::translate::Borrowed<gio::TlsCertificate> =
+        glib::translate::from_glib_borrow(chain);
+    let pinned = imp.pin.get().is_some_and(|pin| {
+        certificate
+            .certificate()
+            .is_some_and(|der| fingerprint(der.as_ref()) == *pin)
+    });
+    if pinned {
+        return gio::ffi::G_TLS_CERTIFICATE_NO_FLAGS;
+    }
+    match imp.ca_bundle() {
+        Some(ca_bundle) => gio::ffi::g_tls_database_verify_chain(
+            ca_bundle.as_ptr(),
+            chain,
+            purpose,
+            identity,
+            interaction,
+            flags,
+            cancellable,
+            error,
+        ),
+        None => gio::ffi::G_TLS_CERTIFICATE_UNKNOWN_CA,
+    }
+}
+
+unsafe extern "C" fn pinned_lookup_certificate_issuer(
+    database: *mut gio::ffi::GTlsDatabase,
+    certificate: *mut gio::ffi::GTlsCertificate,
+    interaction: *mut gio::ffi::GTlsInteraction,
+    flags: gio::ffi::GTlsDatabaseLookupFlags,
+    cancellable: *mut gio::ffi::GCancellable,
+    error: *mut *mut glib::ffi::GError,
+) -> *mut gio::ffi::GTlsCertificate {
+    let instance = &*(database as *mut <imp::PinnedTlsDatabase as ObjectSubclass>::Instance);
+    match instance.imp().ca_bundle() {
+        Some(ca_bundle) => gio::ffi::g_tls_database_lookup_certificate_issuer(
+            ca_bundle.as_ptr(),
+            certificate,
+            interaction,
+            flags,
+            cancellable,
+            error,
+        ),
+        None => std::ptr::null_mut(),
+    }
+}
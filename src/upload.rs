@@ -0,0 +1,273 @@
+/* upload.rs
+ *
+ * Copyright 2026 yusuf
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::api::{ApiError, AudiobookshelfClient};
+use crate::models::UploadRequest;
+use gstreamer::prelude::*;
+use gtk::{gio, glib};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// File extensions Audiobookshelf accepts in a book folder
+const AUDIO_EXTENSIONS: [&str; 16] = [
+    "m4b", "mp3", "m4a", "flac", "opus", "ogg", "oga", "mp4", "aac", "wma", "aif", "aiff", "wav",
+    "webm", "webma", "mka",
+];
+const OTHER_EXTENSIONS: [&str; 11] = [
+    "epub", "pdf", "mobi", "azw3", "cbr", "cbz", "jpg", "jpeg", "png", "webp", "opf",
+];
+
+#[derive(Debug, Clone)]
+pub struct UploadFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+}
+
+impl UploadFile {
+    pub fn is_audio(&self) -> bool {
+        has_extension(&self.path, &AUDIO_EXTENSIONS)
+    }
+}
+
+/// Book fields guessed from the dropped files
+#[derive(Debug, Clone, Default)]
+pub struct FileTags {
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+pub enum UploadEvent {
+    Progress {
+        index: usize,
+        sent: u64,
+        total: u64,
+    },
+    FileFinished {
+        index: usize,
+        result: Result<(), String>,
+    },
+    Done {
+        cancelled: bool,
+    },
+}
+
+/// Expands dropped files and folders (recursively) into uploadable files,
+/// skipping anything the server would ignore.
+pub fn collect_files(paths: &[PathBuf]) -> Vec<UploadFile> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_into(path, &mut files);
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+    files
+}
+
+fn collect_into(path: &Path, files: &mut Vec<UploadFile>) {
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            log::warn!("Cannot read folder {}", path.display());
+            return;
+        };
+        for entry in entries.flatten() {
+            collect_into(&entry.path(), files);
+        }
+        return;
+    }
+
+    if !has_extension(path, &AUDIO_EXTENSIONS) && !has_extension(path, &OTHER_EXTENSIONS) {
+        return;
+    }
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+    let mime_type = gio::content_type_get_mime_type(&content_type)
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    files.push(UploadFile {
+        path: path.to_path_buf(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        size: metadata.len(),
+        mime_type,
+    });
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| extensions.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Guesses book fields: tags of the first audio file, falling back to the
+/// name of a single dropped folder for the title.
+pub fn guess_tags(dropped: &[PathBuf], files: &[UploadFile]) -> FileTags {
+    let mut tags = files
+        .iter()
+        .find(|f| f.is_audio())
+        .map(|f| read_tags(&f.path))
+        .unwrap_or_default();
+    if tags.title.is_none() {
+        if let [single] = dropped {
+            tags.title = if single.is_dir() {
+                single.file_name()
+            } else {
+                single.file_stem()
+            }
+            .map(|n| n.to_string_lossy().to_string());
+        }
+    }
+    tags
+}
+
+/// Reads container tags by prerolling the file with GStreamer. Audiobook
+/// files usually carry the book title as album and the author as artist.
+pub fn read_tags(path: &Path) -> FileTags {
+    let mut album = None;
+    let mut title = None;
+    let mut album_artist = None;
+    let mut artist = None;
+
+    let Ok(uri) = glib::filename_to_uri(path, None) else {
+        return FileTags::default();
+    };
+    let Ok(decodebin) = gstreamer::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()
+    else {
+        return FileTags::default();
+    };
+    let pipeline = gstreamer::Pipeline::new();
+    if pipeline.add(&decodebin).is_err() {
+        return FileTags::default();
+    }
+    // Give every decoded stream (audio, embedded cover, ...) its own sink so
+    // preroll can complete.
+    decodebin.connect_pad_added(|element, pad| {
+        let Some(bin) = element.parent().and_downcast::<gstreamer::Bin>() else {
+            return;
+        };
+        let Ok(sink) = gstreamer::ElementFactory::make("fakesink").build() else {
+            return;
+        };
+        if bin.add(&sink).is_ok() {
+            let _ = sink.sync_state_with_parent();
+            if let Some(sink_pad) = sink.static_pad("sink") {
+                let _ = pad.link(&sink_pad);
+            }
+        }
+    });
+
+    if pipeline.set_state(gstreamer::State::Paused).is_ok() {
+        if let Some(bus) = pipeline.bus() {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                let Some(msg) = bus.timed_pop_filtered(
+                    gstreamer::ClockTime::from_mseconds(remaining.as_millis() as u64),
+                    &[
+                        gstreamer::MessageType::Tag,
+                        gstreamer::MessageType::AsyncDone,
+                        gstreamer::MessageType::Error,
+                    ],
+                ) else {
+                    break;
+                };
+                let gstreamer::MessageView::Tag(tag) = msg.view() else {
+                    break;
+                };
+                let list = tag.tags();
+                let read = |value: Option<gstreamer::tags::TagValue<&str>>| {
+                    value
+                        .map(|v| v.get().trim().to_string())
+                        .filter(|v| !v.is_empty())
+                };
+                album = album.or_else(|| read(list.get::<gstreamer::tags::Album>()));
+                title = title.or_else(|| read(list.get::<gstreamer::tags::Title>()));
+                album_artist =
+                    album_artist.or_else(|| read(list.get::<gstreamer::tags::AlbumArtist>()));
+                artist = artist.or_else(|| read(list.get::<gstreamer::tags::Artist>()));
+            }
+        }
+    }
+    let _ = pipeline.set_state(gstreamer::State::Null);
+
+    FileTags {
+        title: album.or(title),
+        author: album_artist.or(artist),
+    }
+}
+
+/// Uploads `files` one request at a time so each gets its own progress,
+/// retries and failure state. Runs on a worker thread.
+pub fn run_upload(
+    client: AudiobookshelfClient,
+    request: UploadRequest,
+    files: Vec<(usize, UploadFile)>,
+    cancel: Arc<AtomicBool>,
+    events: async_channel::Sender<UploadEvent>,
+) {
+    for (index, file) in files {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // Only forward whole-percent changes; the reader reports every chunk.
+        let last_percent = Arc::new(std::sync::atomic::AtomicU64::new(u64::MAX));
+        let progress_events = events.clone();
+        let progress = Arc::new(move |sent: u64, total: u64| {
+            let percent = (sent * 100).checked_div(total).unwrap_or(100);
+            if last_percent.swap(percent, Ordering::Relaxed) != percent {
+                let _ = progress_events.try_send(UploadEvent::Progress { index, sent, total });
+            }
+        });
+
+        let result = client.upload_file(
+            &request,
+            &file.path,
+            &file.mime_type,
+            progress,
+            cancel.clone(),
+        );
+        let cancelled = matches!(result, Err(ApiError::Cancelled));
+        let _ = events.send_blocking(UploadEvent::FileFinished {
+            index,
+            result: result.map_err(|e| e.to_string()),
+        });
+        if cancelled {
+            break;
+        }
+    }
+
+    let _ = events.send_blocking(UploadEvent::Done {
+        cancelled: cancel.load(Ordering::Relaxed),
+    });
+}
@@ -14,14 +14,28 @@ use adw::prelude::*;
 use adw::subclass::prelude::*;
 use gtk::{gio, glib};
 use std::cell::{Cell, RefCell};
-use std::cmp::Reverse;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use webkit6::prelude::WebViewExt;
 
-use crate::api::AudiobookshelfClient;
+use crate::api::{ApiError, AudiobookshelfClient};
+use crate::cache;
+use crate::connection::{self, ConnectionSettings, Header};
+use crate::connectivity::{Connectivity, ConnectivityMonitor};
+use crate::downloads;
+use crate::links;
 use crate::models::*;
+use crate::notifications::{self, NotificationKind};
+use crate::oauth::{self, PendingSignIn};
+use crate::power::{self, PowerSettings};
+use crate::search_provider;
+use crate::tokens::{self, TokenEvent};
+use crate::trust::{self, ServerTrust};
+use crate::upload::{self, FileTags, UploadEvent, UploadFile};
 
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
 struct StoredSession {
@@ -33,6 +47,8 @@ struct StoredSession {
     access_token: String,
     #[serde(default)]
     refresh_token: String,
+    #[serde(default)]
+    api_key: bool,
 }
 
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
@@ -41,14 +57,580 @@ struct SecretSessionTokens {
     access_token: String,
     #[serde(default)]
     refresh_token: String,
+    // access_token is an API key
+    #[serde(default)]
+    api_key: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum LibrarySortMode {
     NewlyAdded,
-    AuthorAsc,
-    TitleAsc,
+    Title,
+    Author,
     RecentlyPlayed,
+    Duration,
+    PublishedYear,
+    Progress,
+    Series,
+    Narrator,
+    Size,
+    DateFinished,
+}
+
+impl LibrarySortMode {
+    const ALL: [LibrarySortMode; 11] = [
+        Self::NewlyAdded,
+        Self::Title,
+        Self::Author,
+        Self::RecentlyPlayed,
+        Self::Duration,
+        Self::PublishedYear,
+        Self::Progress,
+        Self::Series,
+        Self::Narrator,
+        Self::Size,
+        Self::DateFinished,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::NewlyAdded => "Date Added",
+            Self::Title => "Title",
+            Self::Author => "Author",
+            Self::RecentlyPlayed => "Recently Played",
+            Self::Duration => "Duration",
+            Self::PublishedYear => "Published Year",
+            Self::Progress => "Progress",
+            Self::Series => "Series",
+            Self::Narrator => "Narrator",
+            Self::Size => "File Size",
+            Self::DateFinished => "Date Finished",
+        }
+    }
+
+    /// Dates and progress read most naturally newest/furthest first.
+    fn default_descending(self) -> bool {
+        matches!(
+            self,
+            Self::NewlyAdded | Self::RecentlyPlayed | Self::Progress | Self::DateFinished
+        )
+    }
+}
+
+/// Sort settings of one tab, persisted per library in library-sort.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LibrarySort {
+    mode: LibrarySortMode,
+    descending: bool,
+    /// Sort titles by `titleIgnorePrefix` ("Hobbit, The")
+    ignore_prefix: bool,
+}
+
+impl Default for LibrarySort {
+    fn default() -> Self {
+        Self {
+            mode: LibrarySortMode::NewlyAdded,
+            descending: true,
+            ignore_prefix: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LibraryViewMode {
+    #[default]
+    Grid,
+    List,
+}
+
+/// Columns of the library list view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LibraryColumn {
+    Title,
+    Author,
+    Narrator,
+    Series,
+    Duration,
+    Progress,
+    Added,
+    LastPlayed,
+}
+
+impl LibraryColumn {
+    const ALL: [LibraryColumn; 8] = [
+        Self::Title,
+        Self::Author,
+        Self::Narrator,
+        Self::Series,
+        Self::Duration,
+        Self::Progress,
+        Self::Added,
+        Self::LastPlayed,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Author => "author",
+            Self::Narrator => "narrator",
+            Self::Series => "series",
+            Self::Duration => "duration",
+            Self::Progress => "progress",
+            Self::Added => "added",
+            Self::LastPlayed => "last-played",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Author => "Author",
+            Self::Narrator => "Narrator",
+            Self::Series => "Series",
+            Self::Duration => "Duration",
+            Self::Progress => "Progress",
+            Self::Added => "Added",
+            Self::LastPlayed => "Last Played",
+        }
+    }
+
+    fn sort_mode(self) -> LibrarySortMode {
+        match self {
+            Self::Title => LibrarySortMode::Title,
+            Self::Author => LibrarySortMode::Author,
+            Self::Narrator => LibrarySortMode::Narrator,
+            Self::Series => LibrarySortMode::Series,
+            Self::Duration => LibrarySortMode::Duration,
+            Self::Progress => LibrarySortMode::Progress,
+            Self::Added => LibrarySortMode::NewlyAdded,
+            Self::LastPlayed => LibrarySortMode::RecentlyPlayed,
+        }
+    }
+}
+
+/// Position, visibility and width of one list view column; a width of -1
+/// means automatic.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ColumnLayout {
+    pub column: LibraryColumn,
+    pub visible: bool,
+    #[serde(default = "ColumnLayout::auto_width")]
+    pub width: i32,
+}
+
+impl ColumnLayout {
+    fn auto_width() -> i32 {
+        -1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LibraryViewSettings {
+    pub mode: LibraryViewMode,
+    /// In display order
+    pub columns: Vec<ColumnLayout>,
+}
+
+impl Default for LibraryViewSettings {
+    fn default() -> Self {
+        Self {
+            mode: LibraryViewMode::Grid,
+            columns: LibraryColumn::ALL
+                .iter()
+                .map(|&column| ColumnLayout {
+                    column,
+                    visible: true,
+                    width: -1,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl LibraryViewSettings {
+    /// Drops duplicate columns and appends ones missing from older settings.
+    fn normalized(mut self) -> Self {
+        let mut seen = Vec::new();
+        self.columns.retain(|c| {
+            let new = !seen.contains(&c.column);
+            seen.push(c.column);
+            new
+        });
+        for column in LibraryColumn::ALL {
+            if !seen.contains(&column) {
+                self.columns.push(ColumnLayout {
+                    column,
+                    visible: true,
+                    width: -1,
+                });
+            }
+        }
+        self
+    }
+}
+
+/// One step of a bulk action: client, item, progress of the item (0–1) and
+/// the cancel flag.
+type BulkOp = Arc<
+    dyn Fn(&AudiobookshelfClient, &LibraryItem, &dyn Fn(f64), &AtomicBool) -> Result<(), ApiError>
+        + Send
+        + Sync,
+>;
+
+/// Sent from a bulk action's worker thread
+enum BulkEvent {
+    Progress { index: usize, percent: u32 },
+    ItemDone { error: Option<String> },
+    Finished { cancelled: bool },
+}
+
+/// A request from the command line, possibly forwarded from another
+/// invocation of the app
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    Server(String),
+    Library(String),
+    Play(String),
+    Resume,
+    TogglePlayback,
+    Seek {
+        seconds: f64,
+        relative: bool,
+    },
+    Speed(f64),
+    SleepMinutes(i64),
+    SleepEndOfChapter,
+    CancelSleep,
+    OpenItem {
+        item_id: String,
+        position: Option<f64>,
+    },
+    OpenAuthor {
+        author_id: String,
+        name: Option<String>,
+    },
+    OpenSeries(String),
+    OpenCollection(String),
+    /// Redirect that finishes a browser sign-in
+    OAuthCallback(String),
+}
+
+impl RemoteCommand {
+    /// Whether the command has to wait for a signed-in, loaded library.
+    fn needs_library(&self) -> bool {
+        matches!(
+            self,
+            Self::Library(_)
+                | Self::Play(_)
+                | Self::Resume
+                | Self::TogglePlayback
+                | Self::OpenItem { .. }
+                | Self::OpenAuthor { .. }
+                | Self::OpenSeries(_)
+                | Self::OpenCollection(_)
+        )
+    }
+}
+
+/// Metadata values the library can be browsed by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowseKind {
+    Narrator,
+    Genre,
+    Tag,
+    Series,
+}
+
+impl BrowseKind {
+    fn plural(self) -> &'static str {
+        match self {
+            Self::Narrator => "Narrators",
+            Self::Genre => "Genres",
+            Self::Tag => "Tags",
+            Self::Series => "Series",
+        }
+    }
+
+    fn values(self, item: &LibraryItem) -> Vec<String> {
+        let media = item.media.as_ref();
+        let metadata = media.and_then(|m| m.metadata.as_ref());
+        match self {
+            Self::Narrator => metadata
+                .and_then(|m| m.narrator_name.as_deref())
+                .map(split_list)
+                .unwrap_or_default(),
+            Self::Genre => metadata.and_then(|m| m.genres.clone()).unwrap_or_default(),
+            Self::Tag => media.and_then(|m| m.tags.clone()).unwrap_or_default(),
+            Self::Series => metadata
+                .and_then(|m| m.series_name.as_deref())
+                .map(|s| {
+                    parse_series(&s.replace(", ", ";"))
+                        .into_iter()
+                        .filter_map(|s| s.name)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Every value with the number of items carrying it, by name.
+    fn counts(self, items: &[LibraryItem]) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            let mut values = self.values(item);
+            values.sort();
+            values.dedup();
+            for value in values {
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|(a, _), (b, _)| natural_cmp(a, b));
+        counts
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressFilter {
+    NotStarted,
+    InProgress,
+    Finished,
+}
+
+impl ProgressFilter {
+    const ALL: [ProgressFilter; 3] = [Self::NotStarted, Self::InProgress, Self::Finished];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::NotStarted => "Not Started",
+            Self::InProgress => "In Progress",
+            Self::Finished => "Finished",
+        }
+    }
+
+    fn server_value(self) -> &'static str {
+        match self {
+            Self::NotStarted => "not-started",
+            Self::InProgress => "in-progress",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+/// A series name (`None` for standalone books) with its books and their sequence.
+type SeriesGroup = (Option<String>, Vec<(Option<String>, LibraryItem)>);
+
+/// Accessor for one end of a `LibraryFilter` range
+type FilterBound = fn(&mut LibraryFilter) -> &mut Option<u32>;
+
+/// One removable part of a `LibraryFilter`, shown as a chip above the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterField {
+    Progress,
+    Genre,
+    Tag,
+    Narrator,
+    Language,
+    Publisher,
+    Year,
+    Duration,
+    Ebook,
+}
+
+/// Library grid filters, persisted per library in library-filters.json
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LibraryFilter {
+    progress: Option<ProgressFilter>,
+    genre: Option<String>,
+    tag: Option<String>,
+    narrator: Option<String>,
+    language: Option<String>,
+    publisher: Option<String>,
+    year_min: Option<u32>,
+    year_max: Option<u32>,
+    /// Hours
+    duration_min: Option<u32>,
+    /// Hours
+    duration_max: Option<u32>,
+    has_ebook: bool,
+}
+
+impl LibraryFilter {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The server only understands a single `filter=` group, so the most
+    /// selective active one is sent; everything is still re-checked locally.
+    fn server_filter(&self) -> Option<String> {
+        let (group, value) = if let Some(progress) = self.progress {
+            ("progress", progress.server_value().to_string())
+        } else if let Some(genre) = &self.genre {
+            ("genres", genre.clone())
+        } else if let Some(tag) = &self.tag {
+            ("tags", tag.clone())
+        } else if let Some(narrator) = &self.narrator {
+            ("narrators", narrator.clone())
+        } else if let Some(language) = &self.language {
+            ("languages", language.clone())
+        } else if let Some(publisher) = &self.publisher {
+            ("publishers", publisher.clone())
+        } else if self.has_ebook {
+            ("ebooks", "ebook".to_string())
+        } else {
+            return None;
+        };
+        Some(format!(
+            "{}.{}",
+            group,
+            glib::base64_encode(value.as_bytes())
+        ))
+    }
+
+    fn matches(&self, item: &LibraryItem) -> bool {
+        let media = item.media.as_ref();
+        let metadata = media.and_then(|m| m.metadata.as_ref());
+
+        if let Some(progress) = self.progress {
+            let p = item.user_media_progress.as_ref();
+            let finished = p.and_then(|p| p.is_finished).unwrap_or(false);
+            let started = p
+                .map(|p| p.progress.unwrap_or(0.0) > 0.0 || p.current_time.unwrap_or(0.0) > 0.0)
+                .unwrap_or(false);
+            let state = if finished {
+                ProgressFilter::Finished
+            } else if started {
+                ProgressFilter::InProgress
+            } else {
+                ProgressFilter::NotStarted
+            };
+            if state != progress {
+                return false;
+            }
+        }
+        if let Some(genre) = &self.genre {
+            let genres = metadata.and_then(|m| m.genres.as_ref());
+            if !genres.is_some_and(|g| g.iter().any(|x| x == genre)) {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if !media
+                .and_then(|m| m.tags.as_ref())
+                .is_some_and(|t| t.iter().any(|x| x == tag))
+            {
+                return false;
+            }
+        }
+        if let Some(narrator) = &self.narrator {
+            let narrators = metadata
+                .and_then(|m| m.narrator_name.as_deref())
+                .unwrap_or("");
+            if !narrators.split(',').any(|n| n.trim() == narrator) {
+                return false;
+            }
+        }
+        if let Some(language) = &self.language {
+            if metadata.and_then(|m| m.language.as_deref()) != Some(language.as_str()) {
+                return false;
+            }
+        }
+        if let Some(publisher) = &self.publisher {
+            if metadata.and_then(|m| m.publisher.as_deref()) != Some(publisher.as_str()) {
+                return false;
+            }
+        }
+        if self.year_min.is_some() || self.year_max.is_some() {
+            let Some(year) = metadata
+                .and_then(|m| m.published_year.as_deref())
+                .and_then(|y| y.trim().get(..4))
+                .and_then(|y| y.parse::<u32>().ok())
+            else {
+                return false;
+            };
+            if self.year_min.is_some_and(|min| year < min)
+                || self.year_max.is_some_and(|max| year > max)
+            {
+                return false;
+            }
+        }
+        if self.duration_min.is_some() || self.duration_max.is_some() {
+            let hours = media.and_then(|m| m.duration).unwrap_or(0.0) / 3600.0;
+            if self.duration_min.is_some_and(|min| hours < min as f64)
+                || self.duration_max.is_some_and(|max| hours > max as f64)
+            {
+                return false;
+            }
+        }
+        if self.has_ebook && media.and_then(|m| m.ebook_format.as_deref()).is_none() {
+            return false;
+        }
+        true
+    }
+
+    fn active_fields(&self) -> Vec<(FilterField, String)> {
+        let mut fields = Vec::new();
+        if let Some(progress) = self.progress {
+            fields.push((FilterField::Progress, progress.label().to_string()));
+        }
+        let text_fields = [
+            (FilterField::Genre, &self.genre),
+            (FilterField::Tag, &self.tag),
+            (FilterField::Narrator, &self.narrator),
+            (FilterField::Language, &self.language),
+            (FilterField::Publisher, &self.publisher),
+        ];
+        for (field, value) in text_fields {
+            if let Some(value) = value {
+                fields.push((field, value.clone()));
+            }
+        }
+        match (self.year_min, self.year_max) {
+            (Some(min), Some(max)) => fields.push((FilterField::Year, format!("{}–{}", min, max))),
+            (Some(min), None) => fields.push((FilterField::Year, format!("From {}", min))),
+            (None, Some(max)) => fields.push((FilterField::Year, format!("Until {}", max))),
+            (None, None) => {}
+        }
+        match (self.duration_min, self.duration_max) {
+            (Some(min), Some(max)) => {
+                fields.push((FilterField::Duration, format!("{}–{} h", min, max)))
+            }
+            (Some(min), None) => fields.push((FilterField::Duration, format!("Over {} h", min))),
+            (None, Some(max)) => fields.push((FilterField::Duration, format!("Under {} h", max))),
+            (None, None) => {}
+        }
+        if self.has_ebook {
+            fields.push((FilterField::Ebook, "Has Ebook".to_string()));
+        }
+        fields
+    }
+
+    fn clear(&mut self, field: FilterField) {
+        match field {
+            FilterField::Progress => self.progress = None,
+            FilterField::Genre => self.genre = None,
+            FilterField::Tag => self.tag = None,
+            FilterField::Narrator => self.narrator = None,
+            FilterField::Language => self.language = None,
+            FilterField::Publisher => self.publisher = None,
+            FilterField::Year => {
+                self.year_min = None;
+                self.year_max = None;
+            }
+            FilterField::Duration => {
+                self.duration_min = None;
+                self.duration_max = None;
+            }
+            FilterField::Ebook => self.has_ebook = false,
+        }
+    }
 }
 
 mod imp {
@@ -67,10 +649,37 @@ mod imp {
         pub library_stack: RefCell<Option<gtk::Stack>>,
         pub library_switcher_bar: RefCell<Option<adw::ViewSwitcherBar>>,
         pub library_header_switcher: RefCell<Option<adw::ViewSwitcher>>,
+        pub authors_flowbox: RefCell<Option<gtk::FlowBox>>,
+        pub authors_stack: RefCell<Option<gtk::Stack>>,
+        pub library_authors: RefCell<Vec<AuthorExpanded>>,
         pub library_items: RefCell<Vec<LibraryItem>>,
+        /// What the server returned for the active `filter=`, shown instead
+        /// of the whole library
+        pub library_filtered_items: RefCell<Option<Vec<LibraryItem>>>,
         pub continue_items: RefCell<Vec<LibraryItem>>,
-        pub library_sort_mode: Cell<LibrarySortMode>,
+        pub library_sort: Cell<LibrarySort>,
+        pub continue_sort: Cell<LibrarySort>,
         pub library_search_query: RefCell<String>,
+        pub library_filter: RefCell<LibraryFilter>,
+        pub library_filter_chips: RefCell<Option<gtk::FlowBox>>,
+        pub library_view: RefCell<LibraryViewSettings>,
+        pub library_mode_stack: RefCell<Option<gtk::Stack>>,
+        pub library_list_store: RefCell<Option<gio::ListStore>>,
+        pub library_column_view: RefCell<Option<gtk::ColumnView>>,
+        // Selection mode and what the grids currently show, in order
+        pub selection_mode: Cell<bool>,
+        pub selection_btn: RefCell<Option<gtk::ToggleButton>>,
+        pub selection_bar: RefCell<Option<gtk::ActionBar>>,
+        pub selection_label: RefCell<Option<gtk::Label>>,
+        pub library_rendered: RefCell<Vec<LibraryItem>>,
+        pub continue_rendered: RefCell<Vec<LibraryItem>>,
+        pub play_queue: RefCell<VecDeque<String>>,
+        // Command-line requests waiting for the library to load
+        pub pending_commands: RefCell<Vec<RemoteCommand>>,
+        pub commands_ready: Cell<bool>,
+        pub login_server_row: RefCell<Option<adw::EntryRow>>,
+        // Filter choices of the library they were fetched for
+        pub library_filter_data: RefCell<Option<(String, LibraryFilterData)>>,
         // Detail
         pub detail_content: RefCell<Option<gtk::Box>>,
         pub detail_top_box: RefCell<Option<gtk::Box>>,
@@ -111,10 +720,48 @@ mod imp {
         pub sleep_timer_source: RefCell<Option<glib::SourceId>>,
         pub sleep_until_chapter_end: Rc<Cell<bool>>,
         pub sleep_remaining_secs: Rc<Cell<i64>>,
+        pub sleep_warned: Cell<bool>,
+        // Chapter titles of the playing item and the chapter last seen
+        pub current_chapter_titles: RefCell<Vec<String>>,
+        pub current_chapter_index: Cell<Option<usize>>,
+        // Consecutive failed progress syncs
+        pub sync_failures: Cell<u32>,
+        // Suspend handling: inhibit cookie (0 = none), logind monitor and
+        // the book to reopen after resume as (item id, position, playing)
+        pub suspend_inhibit_cookie: Cell<u32>,
+        pub sleep_monitor: RefCell<Option<Rc<power::SleepMonitor>>>,
+        pub resume_after_sleep: RefCell<Option<(String, f64, bool)>>,
+        pub start_paused: Cell<bool>,
+        // Stream recovery: the open session, pending reconnect and the
+        // number of attempts since playback last advanced
+        pub current_session: RefCell<Option<PlaybackSession>>,
+        pub reconnect_source: RefCell<Option<glib::SourceId>>,
+        pub reconnect_attempts: Cell<u32>,
+        pub reconnecting: Cell<bool>,
+        pub stalled_ticks: Cell<u32>,
+        // Connectivity: the monitor, its banner, a session restore waiting
+        // for the server, progress not yet synced as (item id, session id,
+        // position, duration) and downloads held back on metered networks
+        pub connectivity: RefCell<Option<Rc<ConnectivityMonitor>>>,
+        pub offline_banner: adw::Banner,
+        pub restore_on_reconnect: Cell<bool>,
+        pub pending_sync: RefCell<HashMap<String, cache::PendingSync>>,
+        pub deferred_downloads: RefCell<Vec<LibraryItem>>,
+        // Showing the cached library; only downloaded books play
+        pub offline_mode: Cell<bool>,
+        // A download playing from disk: where each of its files starts in
+        // the book with its URI, and the file in the pipeline
+        pub local_files: RefCell<Vec<(f64, String)>>,
+        pub local_file_index: Cell<usize>,
+        // The "sign in again" alert is showing
+        pub session_expired_shown: Cell<bool>,
+        // Browser sign-in waiting for its redirect
+        pub pending_sign_in: RefCell<Option<PendingSignIn>>,
         // Persistent bottom player bar
         pub player_bar: gtk::ActionBar,
         pub player_title: gtk::Label,
         pub player_author: gtk::Label,
+        pub player_reconnecting: gtk::Box,
         pub player_cover: gtk::Image,
         pub play_pause_btn: gtk::Button,
         pub position_scale: gtk::Scale,
@@ -133,6 +780,8 @@ mod imp {
         pub progress_source: RefCell<Option<glib::SourceId>>,
         // OAuth
         pub oauth_consumed: RefCell<bool>,
+        // Signed-in user (role + permissions)
+        pub current_user: RefCell<Option<User>>,
         pub compact_mode: Cell<bool>,
         pub toast_overlay: adw::ToastOverlay,
         pub nav_view: RefCell<Option<adw::NavigationView>>,
@@ -161,10 +810,32 @@ mod imp {
                 library_stack: RefCell::new(None),
                 library_switcher_bar: RefCell::new(None),
                 library_header_switcher: RefCell::new(None),
+                authors_flowbox: RefCell::new(None),
+                authors_stack: RefCell::new(None),
+                library_authors: RefCell::new(Vec::new()),
                 library_items: RefCell::new(Vec::new()),
+                library_filtered_items: RefCell::new(None),
                 continue_items: RefCell::new(Vec::new()),
-                library_sort_mode: Cell::new(LibrarySortMode::NewlyAdded),
+                library_sort: Cell::new(LibrarySort::default()),
+                continue_sort: Cell::new(LibrarySort::default()),
                 library_search_query: RefCell::new(String::new()),
+                library_filter: RefCell::new(LibraryFilter::default()),
+                library_filter_chips: RefCell::new(None),
+                library_view: RefCell::new(LibraryViewSettings::default()),
+                library_mode_stack: RefCell::new(None),
+                library_list_store: RefCell::new(None),
+                library_column_view: RefCell::new(None),
+                selection_mode: Cell::new(false),
+                selection_btn: RefCell::new(None),
+                selection_bar: RefCell::new(None),
+                selection_label: RefCell::new(None),
+                library_rendered: RefCell::new(Vec::new()),
+                continue_rendered: RefCell::new(Vec::new()),
+                play_queue: RefCell::new(VecDeque::new()),
+                pending_commands: RefCell::new(Vec::new()),
+                commands_ready: Cell::new(false),
+                login_server_row: RefCell::new(None),
+                library_filter_data: RefCell::new(None),
                 detail_content: RefCell::new(None),
                 detail_top_box: RefCell::new(None),
                 detail_cover_image: RefCell::new(None),
@@ -203,9 +874,33 @@ mod imp {
                 sleep_timer_source: RefCell::new(None),
                 sleep_until_chapter_end: Rc::new(Cell::new(false)),
                 sleep_remaining_secs: Rc::new(Cell::new(0)),
+                sleep_warned: Cell::new(false),
+                current_chapter_titles: RefCell::new(Vec::new()),
+                current_chapter_index: Cell::new(None),
+                sync_failures: Cell::new(0),
+                suspend_inhibit_cookie: Cell::new(0),
+                sleep_monitor: RefCell::new(None),
+                resume_after_sleep: RefCell::new(None),
+                start_paused: Cell::new(false),
+                current_session: RefCell::new(None),
+                reconnect_source: RefCell::new(None),
+                reconnect_attempts: Cell::new(0),
+                reconnecting: Cell::new(false),
+                stalled_ticks: Cell::new(0),
+                connectivity: RefCell::new(None),
+                offline_banner: adw::Banner::new(""),
+                restore_on_reconnect: Cell::new(false),
+                pending_sync: RefCell::new(HashMap::new()),
+                deferred_downloads: RefCell::new(Vec::new()),
+                offline_mode: Cell::new(false),
+                local_files: RefCell::new(Vec::new()),
+                local_file_index: Cell::new(0),
+                session_expired_shown: Cell::new(false),
+                pending_sign_in: RefCell::new(None),
                 player_bar,
                 player_title: gtk::Label::new(None),
                 player_author: gtk::Label::new(None),
+                player_reconnecting: gtk::Box::new(gtk::Orientation::Horizontal, 6),
                 player_cover: gtk::Image::from_icon_name("audio-x-generic-symbolic"),
                 play_pause_btn: gtk::Button::from_icon_name("media-playback-start-symbolic"),
                 position_scale,
@@ -222,6 +917,7 @@ mod imp {
                 sync_source: RefCell::new(None),
                 progress_source: RefCell::new(None),
                 oauth_consumed: RefCell::new(false),
+                current_user: RefCell::new(None),
                 compact_mode: Cell::new(false),
                 toast_overlay: adw::ToastOverlay::new(),
                 nav_view: RefCell::new(None),
@@ -318,6 +1014,7 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         serde_json::from_str::<SecretSessionTokens>(raw).unwrap_or_else(|_| SecretSessionTokens {
             access_token: raw.to_string(),
             refresh_token: String::new(),
+            api_key: false,
         })
     }
 
@@ -326,11 +1023,13 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         server_url: &str,
         access_token: &str,
         refresh_token: &str,
+        api_key: bool,
     ) -> bool {
         let account = Self::secret_account_for_server(server_url);
         let payload = match serde_json::to_string(&SecretSessionTokens {
             access_token: access_token.to_string(),
             refresh_token: refresh_token.to_string(),
+            api_key,
         }) {
             Ok(payload) => payload,
             Err(err) => {
@@ -512,10 +1211,19 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         // Build the persistent bottom player bar
         self.build_player_bar();
 
-        // Main layout: stack on top (expanding), player bar pinned at bottom
+        // Main layout: offline banner, stack (expanding), player bar pinned
+        // at bottom
         let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
         main_box.add_css_class("app-root");
         imp.stack.set_vexpand(true);
+        imp.offline_banner.set_button_label(Some("Retry"));
+        let win = self.clone();
+        imp.offline_banner.connect_button_clicked(move |_| {
+            if let Some(connectivity) = win.imp().connectivity.borrow().as_ref() {
+                connectivity.check();
+            }
+        });
+        main_box.append(&imp.offline_banner);
         main_box.append(&imp.stack);
         main_box.append(&imp.player_bar);
 
@@ -523,15 +1231,82 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         self.set_content(Some(&imp.toast_overlay));
         self.install_breakpoint();
         self.install_shortcuts();
+        self.setup_window_actions();
+
+        let win_weak = self.downgrade();
+        let connectivity = ConnectivityMonitor::new(imp.client.clone(), move |previous, state| {
+            if let Some(win) = win_weak.upgrade() {
+                win.connectivity_changed(previous, state);
+            }
+        });
+        *imp.connectivity.borrow_mut() = Some(connectivity.clone());
+
+        // Store rotated tokens right away, so the next launch doesn't
+        // restore a refresh token the server already replaced
+        let token_events = imp.client.token_events();
+        let win_weak = self.downgrade();
+        glib::spawn_future_local(async move {
+            while let Ok(event) = token_events.recv().await {
+                let Some(win) = win_weak.upgrade() else {
+                    break;
+                };
+                // Events from sign-in clients of other servers don't concern
+                // the session
+                let client = &win.imp().client;
+                match event {
+                    TokenEvent::Refreshed {
+                        server_url,
+                        access_token,
+                        refresh_token,
+                    } => {
+                        if server_url != client.server_url() || !client.is_authenticated() {
+                            continue;
+                        }
+                        // A sign-in client may have rotated the session's tokens
+                        if client.access_token().as_deref() != Some(access_token.as_str()) {
+                            client.set_tokens(&access_token, &refresh_token);
+                        }
+                        win.save_credentials();
+                    }
+                    TokenEvent::Expired { server_url } => {
+                        if server_url == client.server_url() {
+                            win.session_expired();
+                        }
+                    }
+                }
+            }
+        });
 
         // Try auto-login from saved credentials
         self.try_restore_session();
+        connectivity.check();
 
         // Set up MPRIS (media keys, GNOME panel/lock-screen controls)
         let win = self.clone();
         glib::spawn_future_local(async move {
             win.setup_mpris().await;
         });
+
+        // Close the session before the system sleeps, reopen it after
+        let win_weak = self.downgrade();
+        let win_weak_monitor = self.downgrade();
+        glib::spawn_future_local(async move {
+            let monitor = power::SleepMonitor::start(move |monitor, sleeping| {
+                let Some(win) = win_weak.upgrade() else {
+                    monitor.release_lock();
+                    return;
+                };
+                if sleeping {
+                    win.prepare_for_sleep(monitor);
+                } else {
+                    win.resume_from_sleep();
+                }
+            })
+            .await;
+            if let Some(win) = win_weak_monitor.upgrade() {
+                *win.imp().sleep_monitor.borrow_mut() = monitor;
+            }
+        });
     }
 
     fn has_saved_session_candidate(&self) -> bool {
@@ -796,18 +1571,26 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         let imp = self.imp();
         let access_token = imp.client.access_token().unwrap_or_default();
         let refresh_token = imp.client.refresh_token().unwrap_or_default();
+        let api_key = imp.client.uses_api_key();
         let mut session = StoredSession {
             server_url: imp.client.server_url(),
             library_id: imp.library_id.borrow().clone(),
             token: String::new(),
             access_token: String::new(),
             refresh_token: String::new(),
+            api_key: false,
         };
 
         if !access_token.is_empty()
-            && !self.store_secret_tokens(&session.server_url, &access_token, &refresh_token)
+            && !self.store_secret_tokens(
+                &session.server_url,
+                &access_token,
+                &refresh_token,
+                api_key,
+            )
         {
             log::warn!("Falling back to storing the session tokens in the local session file");
+            session.api_key = api_key;
             session.access_token = access_token.clone();
             session.refresh_token = refresh_token.clone();
             session.token = access_token;
@@ -863,7 +1646,12 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         log::info!("Found saved session, trying login...");
         let imp = self.imp();
         imp.client.set_server(&server_url);
-        imp.client.set_tokens(&access_token, &refresh_token);
+        if secret_tokens.api_key || saved.api_key {
+            imp.client.set_api_key(&access_token);
+        } else {
+            imp.client.set_tokens(&access_token, &refresh_token);
+        }
+        self.select_cache_account();
         if !library_id.is_empty() {
             *imp.library_id.borrow_mut() = library_id;
         }
@@ -892,14 +1680,34 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                     win.save_credentials();
                     win.imp().stack.set_visible_child_name("library");
                     win.load_library();
+                    win.load_current_user();
                 }
                 Ok(Err(crate::api::ApiError::Auth(e))) => {
                     log::warn!("Saved token is invalid ({}), clearing credentials", e);
-                    win.clear_stored_session();
+                    win.session_expired();
+                }
+                Ok(Err(crate::api::ApiError::Certificate(e))) => {
+                    // The certificate changed or its CA is no longer trusted;
+                    // don't send the saved token until the user decides
+                    log::warn!("Server certificate is not trusted ({})", e);
                     win.imp().stack.set_visible_child_name("login");
+                    let win_c = win.clone();
+                    win.confirm_server_certificate(&server_url, move || {
+                        win_c.try_restore_session();
+                    });
+                }
+                Ok(Err(e @ crate::api::ApiError::Network(_))) => {
+                    // Keep the session and finish restoring it once the
+                    // server answers again
+                    log::warn!("Could not reach server ({}), starting offline", e);
+                    win.imp().restore_on_reconnect.set(true);
+                    win.imp().offline_mode.set(true);
+                    win.imp().stack.set_visible_child_name("library");
+                    win.load_library();
+                    win.report_api_error(&e);
                 }
                 _ => {
-                    log::warn!("Could not reach server, keeping saved credentials");
+                    log::warn!("Could not verify the saved session, keeping saved credentials");
                     win.imp().stack.set_visible_child_name("login");
                 }
             }
@@ -934,9 +1742,20 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         imp.player_author.add_css_class("caption");
         imp.player_author.set_halign(gtk::Align::Start);
 
+        // Replaces the author line while the stream is being reopened
+        let reconnect_spinner = adw::Spinner::new();
+        let reconnect_label = gtk::Label::new(Some("Reconnecting…"));
+        reconnect_label.add_css_class("dim-label");
+        reconnect_label.add_css_class("caption");
+        imp.player_reconnecting.append(&reconnect_spinner);
+        imp.player_reconnecting.append(&reconnect_label);
+        imp.player_reconnecting.set_halign(gtk::Align::Start);
+        imp.player_reconnecting.set_visible(false);
+
         let info_box = gtk::Box::new(gtk::Orientation::Vertical, 2);
         info_box.append(&imp.player_title);
         info_box.append(&imp.player_author);
+        info_box.append(&imp.player_reconnecting);
 
         let start_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
         start_box.append(&imp.player_cover);
@@ -1082,6 +1901,7 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         if let Some(btn) = self.imp().now_playing_play_btn.borrow().as_ref() {
             btn.set_icon_name(icon);
         }
+        self.update_suspend_inhibit(playing);
     }
 
     fn style_chapter_rows(
@@ -1138,9 +1958,8 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         toolbar_view.add_top_bar(&header);
         toolbar_view.set_content(Some(&scrolled));
 
-        let title = format!("Books by {}", author_name);
         let nav_page = adw::NavigationPage::builder()
-            .title(&title)
+            .title(author_name)
             .child(&toolbar_view)
             .build();
         nav_view.push(&nav_page);
@@ -1167,20 +1986,32 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                 });
                 match rx.recv().await {
                     Ok(Ok(author)) => {
-                        let items = author.library_items.unwrap_or_default();
-                        win.populate_author_books(&container_clone, &loading_clone, &items, &name_owned);
+                        let items = author.library_items.clone().unwrap_or_default();
+                        win.populate_author_books(
+                            &container_clone,
+                            &loading_clone,
+                            Some(&author),
+                            &items,
+                            &name_owned,
+                        );
                     }
                     Ok(Err(e)) => {
                         log::warn!("Fetch author items failed: {}, falling back to local filter", e);
                         let items = win.filter_library_items_by_author_name(&name_owned);
-                        win.populate_author_books(&container_clone, &loading_clone, &items, &name_owned);
+                        win.populate_author_books(
+                            &container_clone,
+                            &loading_clone,
+                            None,
+                            &items,
+                            &name_owned,
+                        );
                     }
                     Err(_) => {}
                 }
             });
         } else {
             let items = self.filter_library_items_by_author_name(author_name);
-            self.populate_author_books(&container, &loading, &items, author_name);
+            self.populate_author_books(&container, &loading, None, &items, author_name);
         }
     }
 
@@ -1210,11 +2041,95 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         &self,
         container: &gtk::Box,
         loading: &adw::StatusPage,
+        author: Option<&AuthorExpanded>,
         items: &[LibraryItem],
         author_name: &str,
     ) {
         container.remove(loading);
 
+        // The author endpoint returns items without the user's progress; prefer
+        // the cached library copy, which has it.
+        let items: Vec<LibraryItem> = {
+            let cached = self.imp().library_items.borrow();
+            items
+                .iter()
+                .map(|item| {
+                    cached
+                        .iter()
+                        .find(|c| c.id == item.id)
+                        .cloned()
+                        .unwrap_or_else(|| item.clone())
+                })
+                .collect()
+        };
+        let groups = Self::group_author_books(author, &items);
+
+        let header_box = gtk::Box::new(gtk::Orientation::Horizontal, 24);
+        let avatar = adw::Avatar::new(128, Some(author_name), true);
+        avatar.set_valign(gtk::Align::Start);
+        if let Some(author) = author.filter(|a| a.has_image()) {
+            if let Some(id) = author.id.as_deref() {
+                self.load_author_image(&avatar, id);
+            }
+        }
+        header_box.append(&avatar);
+
+        let info_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        info_box.set_hexpand(true);
+        info_box.set_valign(gtk::Align::Center);
+        let name_label = gtk::Label::new(Some(author_name));
+        name_label.add_css_class("title-1");
+        name_label.set_wrap(true);
+        name_label.set_xalign(0.0);
+        info_box.append(&name_label);
+
+        let count_label = gtk::Label::new(Some(&format!("{} book(s)", items.len())));
+        count_label.add_css_class("dim-label");
+        count_label.set_xalign(0.0);
+        info_box.append(&count_label);
+
+        let next = groups
+            .iter()
+            .flat_map(|(_, books)| books.iter().map(|(_, item)| item))
+            .find(|item| Self::item_progress_fraction(item) > 0.0 && !Self::item_is_finished(item))
+            .or_else(|| {
+                groups
+                    .iter()
+                    .flat_map(|(_, books)| books.iter().map(|(_, item)| item))
+                    .find(|item| !Self::item_is_finished(item))
+            });
+        if let Some(next) = next {
+            let play_btn = gtk::Button::new();
+            let play_content = adw::ButtonContent::new();
+            play_content.set_icon_name("media-playback-start-symbolic");
+            play_content.set_label("Play Next Unfinished");
+            play_btn.set_child(Some(&play_content));
+            play_btn.add_css_class("pill");
+            play_btn.add_css_class("suggested-action");
+            play_btn.set_halign(gtk::Align::Start);
+            play_btn.set_margin_top(4);
+            play_btn.set_tooltip_text(Some(Self::item_title_for_sort(next)));
+            let win = self.clone();
+            let next_id = next.id.clone();
+            play_btn.connect_clicked(move |_| win.start_playback(&next_id));
+            info_box.append(&play_btn);
+        }
+        header_box.append(&info_box);
+        container.append(&header_box);
+
+        if let Some(description) = author
+            .and_then(|a| a.description.as_deref())
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            let desc_label = gtk::Label::new(Some(description));
+            desc_label.set_wrap(true);
+            desc_label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            desc_label.set_xalign(0.0);
+            desc_label.set_selectable(true);
+            container.append(&desc_label);
+        }
+
         if items.is_empty() {
             let empty = adw::StatusPage::new();
             empty.set_icon_name(Some("user-info-symbolic"));
@@ -1227,55 +2142,299 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
             return;
         }
 
-        let count_label =
-            gtk::Label::new(Some(&format!("{} book(s) by {}", items.len(), author_name)));
-        count_label.add_css_class("dim-label");
-        count_label.add_css_class("caption");
-        count_label.set_halign(gtk::Align::Start);
-        container.append(&count_label);
-
-        let flowbox = gtk::FlowBox::new();
-        flowbox.set_selection_mode(gtk::SelectionMode::None);
-        flowbox.set_homogeneous(true);
-        flowbox.set_max_children_per_line(6);
-        flowbox.set_min_children_per_line(1);
-        flowbox.set_column_spacing(12);
-        flowbox.set_row_spacing(12);
-        for item in items {
-            let card = self.create_book_card(item);
-            flowbox.append(&card);
+        let has_series = groups.iter().any(|(name, _)| name.is_some());
+        for (series, books) in &groups {
+            let heading = match series {
+                Some(name) => name.as_str(),
+                None if has_series => "Standalone Books",
+                None => "Books",
+            };
+            let heading_box = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+            heading_box.set_margin_top(8);
+            let heading_label = gtk::Label::new(Some(heading));
+            heading_label.add_css_class("title-4");
+            heading_label.set_xalign(0.0);
+            heading_box.append(&heading_label);
+            let finished = books
+                .iter()
+                .filter(|(_, item)| Self::item_is_finished(item))
+                .count();
+            let count = gtk::Label::new(Some(&format!("{}/{} finished", finished, books.len())));
+            count.add_css_class("dim-label");
+            count.add_css_class("caption");
+            count.set_valign(gtk::Align::Baseline);
+            heading_box.append(&count);
+            container.append(&heading_box);
+
+            let flowbox = gtk::FlowBox::new();
+            flowbox.set_selection_mode(gtk::SelectionMode::None);
+            flowbox.set_homogeneous(true);
+            flowbox.set_max_children_per_line(6);
+            flowbox.set_min_children_per_line(1);
+            flowbox.set_column_spacing(12);
+            flowbox.set_row_spacing(12);
+            for (sequence, item) in books {
+                let card = self.create_book_card(item);
+                if let (Some(seq), Some(card_box)) = (sequence, card.downcast_ref::<gtk::Box>()) {
+                    let seq_label = gtk::Label::new(Some(&format!("Book {}", seq)));
+                    seq_label.add_css_class("dim-label");
+                    seq_label.add_css_class("caption");
+                    seq_label.set_halign(gtk::Align::Start);
+                    card_box.append(&seq_label);
+                }
+                flowbox.append(&card);
+            }
+            container.append(&flowbox);
         }
-        container.append(&flowbox);
     }
 
-    fn refresh_chapter_indicators(&self, current_time: f64) {
-        let imp = self.imp();
-        let force_finished = imp.detail_is_finished.get();
-
-        // Detail page rows: only reflect playback when the detail shows the playing item.
-        let detail_is_current = imp.detail_play_item_id.borrow().as_deref()
-            == imp.current_item_id.borrow().as_deref();
-        if detail_is_current {
-            Self::style_chapter_rows(
-                &imp.chapter_indicators.borrow(),
-                current_time,
-                force_finished,
-            );
+    /// Groups an author's books by their first series, ordered by sequence.
+    /// Series follow the server's order when known; books outside a series
+    /// come last under `None`.
+    fn group_author_books(
+        author: Option<&AuthorExpanded>,
+        items: &[LibraryItem],
+    ) -> Vec<SeriesGroup> {
+        let mut groups: Vec<SeriesGroup> = Vec::new();
+        for item in items {
+            let first_series = item
+                .media
+                .as_ref()
+                .and_then(|m| m.metadata.as_ref())
+                .and_then(|m| m.series_name.as_deref())
+                .and_then(|s| parse_series(&s.replace(", ", ";")).into_iter().next());
+            let (name, sequence) = match first_series {
+                Some(s) => (s.name, s.sequence),
+                None => (None, None),
+            };
+            match groups.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, books)) => books.push((sequence, item.clone())),
+                None => groups.push((name, vec![(sequence, item.clone())])),
+            }
         }
 
-        // Now Playing rows always track the active playback while the page is open.
-        if imp.now_playing_open.get() {
-            Self::style_chapter_rows(
-                &imp.np_chapter_indicators.borrow(),
-                current_time,
-                force_finished,
-            );
+        let server_order: Vec<&str> = author
+            .and_then(|a| a.series.as_ref())
+            .map(|series| series.iter().filter_map(|s| s.name.as_deref()).collect())
+            .unwrap_or_default();
+        groups.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => {
+                let pos = |name: &str| server_order.iter().position(|s| *s == name);
+                match (pos(a), pos(b)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => natural_cmp(a, b),
+                }
+            }
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        for (_, books) in &mut groups {
+            books.sort_by(|(seq_a, a), (seq_b, b)| {
+                let by_sequence = match (seq_a, seq_b) {
+                    (Some(x), Some(y)) => natural_cmp(x, y),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                };
+                by_sequence.then_with(|| {
+                    natural_cmp(Self::item_title_for_sort(a), Self::item_title_for_sort(b))
+                })
+            });
         }
+        groups
     }
 
-    // ─── BOOKMARKS ──────────────────────────────────────────────────────────
+    fn item_progress_fraction(item: &LibraryItem) -> f64 {
+        item.user_media_progress
+            .as_ref()
+            .and_then(|p| p.progress)
+            .unwrap_or(0.0)
+    }
 
-    fn add_bookmark_at_current_position(&self) {
+    fn item_is_finished(item: &LibraryItem) -> bool {
+        item.user_media_progress
+            .as_ref()
+            .and_then(|p| p.is_finished)
+            .unwrap_or(false)
+    }
+
+    fn load_author_image(&self, avatar: &adw::Avatar, author_id: &str) {
+        let client = self.imp().client.clone();
+        let author_id = author_id.to_string();
+        let avatar = avatar.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                let _ = tx.send_blocking(client.download_author_image(&author_id));
+            });
+            if let Ok(Ok(bytes)) = rx.recv().await {
+                let gbytes = glib::Bytes::from(&bytes);
+                if let Ok(texture) = gtk::gdk::Texture::from_bytes(&gbytes) {
+                    avatar.set_custom_image(Some(&texture));
+                }
+            }
+        });
+    }
+
+    // ─── AUTHORS ────────────────────────────────────────────────────────────
+
+    fn load_library_authors(&self) {
+        let imp = self.imp();
+        let library_id = imp.library_id.borrow().clone();
+        if library_id.is_empty() || imp.offline_mode.get() {
+            return;
+        }
+        let client = imp.client.clone();
+        let win = self.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(client.get_library_authors(&library_id));
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(mut authors)) => {
+                    authors.sort_by(|a, b| {
+                        let key = |a: &AuthorExpanded| {
+                            a.last_first
+                                .clone()
+                                .or_else(|| a.name.clone())
+                                .unwrap_or_default()
+                        };
+                        natural_cmp(&key(a), &key(b))
+                    });
+                    *win.imp().library_authors.borrow_mut() = authors;
+                    win.render_authors_tab();
+                }
+                Ok(Err(err)) => log::warn!("Load authors failed: {}", err),
+                Err(err) => log::warn!("Authors channel error: {}", err),
+            }
+        });
+    }
+
+    fn render_authors_tab(&self) {
+        let imp = self.imp();
+        let (Some(flowbox), Some(stack)) = (
+            imp.authors_flowbox.borrow().clone(),
+            imp.authors_stack.borrow().clone(),
+        ) else {
+            return;
+        };
+
+        while let Some(child) = flowbox.first_child() {
+            flowbox.remove(&child);
+        }
+
+        let query = imp.library_search_query.borrow().trim().to_lowercase();
+        let authors = imp.library_authors.borrow().clone();
+        let mut shown = 0;
+        for author in authors.iter().filter(|a| {
+            query.is_empty()
+                || a.name
+                    .as_deref()
+                    .is_some_and(|n| n.to_lowercase().contains(&query))
+        }) {
+            flowbox.append(&self.create_author_card(author));
+            shown += 1;
+        }
+
+        if let Some(empty) = stack
+            .child_by_name("empty")
+            .and_then(|w| w.downcast::<adw::StatusPage>().ok())
+        {
+            if query.is_empty() {
+                empty.set_title("No Authors");
+                empty.set_description(Some("Authors of your books will appear here"));
+            } else {
+                empty.set_title("No Authors Found");
+                empty.set_description(Some("No authors match your search"));
+            }
+        }
+        stack.set_visible_child_name(if shown == 0 { "empty" } else { "content" });
+    }
+
+    fn create_author_card(&self, author: &AuthorExpanded) -> gtk::Widget {
+        let name = author
+            .name
+            .clone()
+            .unwrap_or_else(|| "Unknown Author".to_string());
+
+        let card_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        card_box.add_css_class("book-card");
+        card_box.set_width_request(140);
+        card_box.set_halign(gtk::Align::Center);
+
+        let avatar = adw::Avatar::new(120, Some(&name), true);
+        avatar.set_halign(gtk::Align::Center);
+        if author.has_image() {
+            if let Some(id) = author.id.as_deref() {
+                self.load_author_image(&avatar, id);
+            }
+        }
+        card_box.append(&avatar);
+
+        let name_label = gtk::Label::new(Some(&name));
+        name_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        name_label.set_max_width_chars(18);
+        name_label.set_lines(2);
+        name_label.set_wrap(true);
+        name_label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        name_label.set_justify(gtk::Justification::Center);
+        name_label.add_css_class("heading");
+        card_box.append(&name_label);
+
+        let books = author.num_books.unwrap_or(0);
+        let count_label = gtk::Label::new(Some(&if books == 1 {
+            "1 book".to_string()
+        } else {
+            format!("{} books", books)
+        }));
+        count_label.add_css_class("dim-label");
+        count_label.add_css_class("caption");
+        card_box.append(&count_label);
+
+        let gesture = gtk::GestureClick::new();
+        let author_id = author.id.clone();
+        let win = self.clone();
+        gesture.connect_released(move |_, _, _, _| {
+            win.show_author_books(author_id.as_deref(), &name);
+        });
+        card_box.add_controller(gesture);
+        card_box.set_cursor_from_name(Some("pointer"));
+
+        card_box.upcast()
+    }
+
+    fn refresh_chapter_indicators(&self, current_time: f64) {
+        let imp = self.imp();
+        let force_finished = imp.detail_is_finished.get();
+
+        // Detail page rows: only reflect playback when the detail shows the playing item.
+        let detail_is_current = imp.detail_play_item_id.borrow().as_deref()
+            == imp.current_item_id.borrow().as_deref();
+        if detail_is_current {
+            Self::style_chapter_rows(
+                &imp.chapter_indicators.borrow(),
+                current_time,
+                force_finished,
+            );
+        }
+
+        // Now Playing rows always track the active playback while the page is open.
+        if imp.now_playing_open.get() {
+            Self::style_chapter_rows(
+                &imp.np_chapter_indicators.borrow(),
+                current_time,
+                force_finished,
+            );
+        }
+    }
+
+    // ─── BOOKMARKS ──────────────────────────────────────────────────────────
+
+    fn add_bookmark_at_current_position(&self) {
         let imp = self.imp();
         let item_id = match imp.current_item_id.borrow().clone() {
             Some(id) => id,
@@ -1451,6 +2610,11 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
     }
 
     fn load_all_bookmarks(&self) {
+        if self.imp().offline_mode.get() {
+            *self.imp().all_bookmarks.borrow_mut() = cache::load_bookmarks().unwrap_or_default();
+            self.render_bookmarks_tab();
+            return;
+        }
         let client = self.imp().client.clone();
         let win = self.clone();
         let (tx, rx) = async_channel::bounded::<Result<Vec<Bookmark>, String>>(1);
@@ -1469,6 +2633,7 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                             .unwrap_or(0)
                             .cmp(&a.created_at.unwrap_or(0))
                     });
+                    cache::save_bookmarks(&bookmarks);
                     *win.imp().all_bookmarks.borrow_mut() = bookmarks;
                     win.render_bookmarks_tab();
                 }
@@ -1703,6 +2868,19 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
     }
 
     fn load_bookmarks(&self, item_id: &str) {
+        if self.imp().offline_mode.get() {
+            let bookmarks: Vec<Bookmark> = self
+                .imp()
+                .all_bookmarks
+                .borrow()
+                .iter()
+                .filter(|b| b.library_item_id.as_deref() == Some(item_id))
+                .cloned()
+                .collect();
+            *self.imp().bookmarks.borrow_mut() = bookmarks;
+            self.render_bookmarks(item_id);
+            return;
+        }
         let client = self.imp().client.clone();
         let item_id_owned = item_id.to_string();
         let target_item_id = item_id.to_string();
@@ -1828,6 +3006,15 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
     fn toggle_play_pause(&self) {
         use gstreamer::prelude::ElementExt;
         let imp = self.imp();
+        if imp.reconnecting.get() {
+            // The stream is being reopened; it starts in the chosen state
+            let playing = !imp.is_playing.get();
+            imp.is_playing.set(playing);
+            self.update_play_pause_icon(playing);
+            self.refresh_detail_play_button();
+            self.update_mpris_status();
+            return;
+        }
         if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
             let playing = imp.is_playing.get();
             if playing {
@@ -1995,11 +3182,43 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
             Some(p) => p,
             None => return,
         };
-        let art_url = imp.client.cover_url(item_id);
         let mut builder = Metadata::builder()
             .title(title)
-            .artist([author.to_string()])
-            .art_url(art_url);
+            .artist([author.to_string()]);
+        // Art is a local file, so no credential leaves the process
+        let download_cover = downloads::item_dir(item_id).join("cover.jpg");
+        let cached_cover = cache::cover_path(item_id);
+        if let Some(cover) = [download_cover, cached_cover]
+            .into_iter()
+            .find(|p| p.exists())
+        {
+            if let Ok(uri) = glib::filename_to_uri(&cover, None) {
+                builder = builder.art_url(uri.as_str());
+            }
+        } else if self.connectivity() == Connectivity::Online {
+            // Publish again once the cover is cached; if it can't be, the
+            // metadata stays without art instead of downloading again
+            let client = imp.client.clone();
+            let id = item_id.to_string();
+            let (title, author) = (title.to_string(), author.to_string());
+            let win = self.clone();
+            glib::spawn_future_local(async move {
+                let (tx, rx) = async_channel::bounded(1);
+                let cover_id = id.clone();
+                std::thread::spawn(move || {
+                    let cached = client
+                        .download_cover(&cover_id)
+                        .is_ok_and(|bytes| cache::save_cover(&cover_id, &bytes));
+                    let _ = tx.send_blocking(cached);
+                });
+                let still_playing = || win.imp().current_item_id.borrow().as_deref() == Some(&id);
+                if let Ok(true) = rx.recv().await {
+                    if still_playing() {
+                        win.update_mpris_metadata(&title, &author, &id, duration);
+                    }
+                }
+            });
+        }
         if duration > 0.0 {
             builder = builder.length(mpris_server::Time::from_micros((duration * 1e6) as i64));
         }
@@ -2588,6 +3807,9 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                 imp.toast_overlay.add_toast(toast);
                 return glib::ControlFlow::Break;
             }
+            if remaining <= 60 {
+                win.warn_sleep_timer("Playback pauses in a minute");
+            }
             win.update_sleep_button_label();
             glib::ControlFlow::Continue
         });
@@ -2614,6 +3836,9 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         // page's chapter rows which may belong to a different/closed item.
         for (start, end) in imp.current_chapters.borrow().iter() {
             if current_time >= *start && current_time < *end {
+                if (end - current_time) <= 60.0 {
+                    self.warn_sleep_timer("Playback pauses at the end of this chapter");
+                }
                 if (end - current_time) <= 1.0 {
                     self.cancel_sleep_timer();
                     if imp.is_playing.get() {
@@ -2627,14 +3852,62 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         }
     }
 
-    fn cancel_sleep_timer(&self) {
+    pub fn cancel_sleep_timer(&self) {
         let imp = self.imp();
         if let Some(id) = imp.sleep_timer_source.borrow_mut().take() {
             id.remove();
         }
         imp.sleep_until_chapter_end.set(false);
         imp.sleep_remaining_secs.set(0);
+        imp.sleep_warned.set(false);
+        if let Some(app) = self.application() {
+            notifications::withdraw(app.upcast_ref(), NotificationKind::SleepTimer);
+        }
+        self.update_sleep_button_label();
+    }
+
+    /// Adds time to a running sleep timer; an end-of-chapter timer becomes
+    /// a timer of `minutes`.
+    pub fn extend_sleep_timer(&self, minutes: i64) {
+        let imp = self.imp();
+        if imp.sleep_timer_source.borrow().is_none() {
+            self.start_sleep_timer_minutes(minutes);
+            return;
+        }
+        imp.sleep_remaining_secs
+            .set(imp.sleep_remaining_secs.get() + minutes * 60);
+        imp.sleep_warned.set(false);
+        if let Some(app) = self.application() {
+            notifications::withdraw(app.upcast_ref(), NotificationKind::SleepTimer);
+        }
         self.update_sleep_button_label();
+        self.show_toast(&format!("Sleep timer extended by {} minutes", minutes));
+    }
+
+    /// Warns once per timer that it is about to pause playback, offering
+    /// more time or to cancel it.
+    fn warn_sleep_timer(&self, message: &str) {
+        let imp = self.imp();
+        if imp.sleep_warned.replace(true) {
+            return;
+        }
+        if self.is_active() {
+            let toast = adw::Toast::new(message);
+            toast.set_button_label(Some("+15 min"));
+            toast.set_action_name(Some("app.extend-sleep-timer"));
+            toast.set_action_target_value(Some(&15i32.to_variant()));
+            imp.toast_overlay.add_toast(toast);
+        } else {
+            self.notify(
+                NotificationKind::SleepTimer,
+                "Sleep Timer",
+                message,
+                &[
+                    ("+15 min", "app.extend-sleep-timer(15)"),
+                    ("Cancel", "app.cancel-sleep-timer"),
+                ],
+            );
+        }
     }
 
     fn update_sleep_button_label(&self) {
@@ -2845,6 +4118,12 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
     fn seek_relative(&self, delta_secs: i64) {
         use gstreamer::prelude::*;
         let imp = self.imp();
+        // A download may need to move on to another file
+        if !imp.local_files.borrow().is_empty() {
+            let position = *imp.current_time.borrow() + delta_secs as f64;
+            self.seek_to(position);
+            return;
+        }
         if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
             if let Some(pos) = pipeline.query_position::<gstreamer::ClockTime>() {
                 let new_pos = if delta_secs < 0 {
@@ -2869,12 +4148,24 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         use gstreamer::prelude::*;
         let imp = self.imp();
         let seconds = seconds.max(0.0);
-        if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
+        // A download seeks within the file holding the position, after
+        // switching to it if needed
+        let mut pipeline_position = Some(seconds);
+        if let Some((index, offset)) = self.locate_local_file(seconds) {
+            if index == imp.local_file_index.get() {
+                pipeline_position = Some(offset);
+            } else {
+                self.play_local_file(index, offset);
+                pipeline_position = None;
+            }
+        }
+        if let (Some(pipeline), Some(target)) = (imp.pipeline.borrow().as_ref(), pipeline_position)
+        {
             // ACCURATE (not KEY_UNIT) so we land exactly on the target instead of
             // snapping back to the previous keyframe (which fell into the prior chapter).
             // Use full seek() to also preserve the current playback rate.
             let position =
-                gstreamer::ClockTime::from_nseconds((seconds * 1_000_000_000.0) as u64);
+                gstreamer::ClockTime::from_nseconds((target * 1_000_000_000.0) as u64);
             let rate = imp.current_speed.get();
             let _ = pipeline.seek(
                 rate,
@@ -2943,6 +4234,9 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         let toolbar_view = adw::ToolbarView::new();
         let header = adw::HeaderBar::new();
         header.set_title_widget(Some(&adw::WindowTitle::new("Shelfily Desktop", "")));
+        let connection_btn = gtk::Button::from_icon_name("network-server-symbolic");
+        connection_btn.set_tooltip_text(Some("Connection Settings"));
+        header.pack_end(&connection_btn);
         toolbar_view.add_top_bar(&header);
 
         let clamp = adw::Clamp::new();
@@ -2977,6 +4271,7 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         server_row.set_title("Server URL");
         server_row.set_text("http://");
         group.add(&server_row);
+        *self.imp().login_server_row.borrow_mut() = Some(server_row.clone());
 
         let username_row = adw::EntryRow::new();
         username_row.set_title("Username");
@@ -2988,6 +4283,12 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
 
         main_box.append(&group);
 
+        let win = self.clone();
+        let srv = server_row.clone();
+        connection_btn.connect_clicked(move |_| {
+            win.show_connection_settings(&srv.text());
+        });
+
         let status_label = gtk::Label::new(None);
         status_label.add_css_class("error");
         status_label.set_visible(false);
@@ -3026,6 +4327,12 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         oauth_btn.set_height_request(42);
         main_box.append(&oauth_btn);
 
+        let api_key_btn = gtk::Button::with_label("Sign In with API Key");
+        api_key_btn.add_css_class("pill");
+        api_key_btn.add_css_class("flat");
+        api_key_btn.set_height_request(42);
+        main_box.append(&api_key_btn);
+
         clamp.set_child(Some(&main_box));
         toolbar_view.set_content(Some(&clamp));
 
@@ -3065,8 +4372,8 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                 let user = username.clone();
                 let pass = password.clone();
 
+                let client = win_c.imp().client.detached();
                 std::thread::spawn(move || {
-                    let client = AudiobookshelfClient::new();
                     client.set_server(&server);
                     let result = client.login(&user, &pass);
                     let _ = tx.send_blocking((result, server));
@@ -3077,44 +4384,17 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                         spn_c.set_spinning(false);
                         spn_c.set_visible(false);
                         btn_c.set_sensitive(true);
-
-                        let legacy_token =
-                            login_resp.user.token.as_deref().unwrap_or_default().to_string();
-                        let raw_access_token = login_resp
-                            .user
-                            .access_token
-                            .as_deref()
-                            .unwrap_or_default()
-                            .to_string();
-                        let refresh_token = login_resp
-                            .user
-                            .refresh_token
-                            .as_deref()
-                            .unwrap_or_default()
-                            .to_string();
-                        let access_token = login_resp
-                            .user
-                            .access_token
-                            .as_deref()
-                            .or(login_resp.user.token.as_deref())
-                            .unwrap_or_default();
-                        let session_token = Self::preferred_session_token(
-                            if raw_access_token.is_empty() {
-                                access_token
-                            } else {
-                                &raw_access_token
-                            },
-                            &legacy_token,
-                            &refresh_token,
-                        );
-                        let default_lib = login_resp.user_default_library_id.unwrap_or_default();
-
-                        win_c.on_login_success(
-                            &server_url,
-                            &session_token,
-                            &refresh_token,
-                            &default_lib,
-                        );
+                        win_c.finish_login(&server_url, login_resp);
+                    }
+                    Ok((Err(ApiError::Certificate(e)), server_url)) => {
+                        spn_c.set_spinning(false);
+                        spn_c.set_visible(false);
+                        btn_c.set_sensitive(true);
+                        log::warn!("Server certificate is not trusted ({})", e);
+                        let btn_retry = btn_c.clone();
+                        win_c.confirm_server_certificate(&server_url, move || {
+                            btn_retry.emit_clicked();
+                        });
                     }
                     Ok((Err(e), _)) => {
                         spn_c.set_spinning(false);
@@ -3170,8 +4450,8 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                 let (tx, rx) = async_channel::bounded(1);
                 let srv = server.clone();
 
+                let client = win_c.imp().client.detached();
                 std::thread::spawn(move || {
-                    let client = AudiobookshelfClient::new();
                     client.set_server(&srv);
                     let result = client.get_status();
                     let _ = tx.send_blocking(result);
@@ -3194,12 +4474,19 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
                                 .auth_form_data
                                 .as_ref()
                                 .and_then(|d| d.auth_openid_button_text.clone());
-                            win_c.show_oauth_webview(&server, button_text.as_deref());
+                            win_c.start_browser_login(&server, button_text.as_deref());
                         } else {
                             lbl_c.set_text("OpenID is not configured on this server");
                             lbl_c.set_visible(true);
                         }
                     }
+                    Ok(Err(ApiError::Certificate(e))) => {
+                        log::warn!("Server certificate is not trusted ({})", e);
+                        let btn_retry = btn_c.clone();
+                        win_c.confirm_server_certificate(&server, move || {
+                            btn_retry.emit_clicked();
+                        });
+                    }
                     Ok(Err(e)) => {
                         lbl_c.set_text(&format!("Failed to fetch server status: {}", e));
                         lbl_c.set_visible(true);
@@ -3212,9 +4499,163 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
             });
         });
 
+        // API key
+        let win = self.clone();
+        let srv = server_row.clone();
+        let lbl = status_label.clone();
+        let spn = spinner.clone();
+
+        api_key_btn.connect_clicked(move |btn| {
+            let server_url = srv.text().to_string().trim().to_string();
+            if server_url.len() <= 7 {
+                lbl.set_text("Please enter the server URL");
+                lbl.set_visible(true);
+                return;
+            }
+            lbl.set_visible(false);
+
+            let alert = adw::AlertDialog::new(
+                Some("Sign In with API Key"),
+                Some("Create a key on the server under Settings → API Keys."),
+            );
+            let key_row = adw::PasswordEntryRow::new();
+            key_row.set_title("API Key");
+            let list = gtk::ListBox::new();
+            list.add_css_class("boxed-list");
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.append(&key_row);
+            alert.set_extra_child(Some(&list));
+            alert.add_response("cancel", "Cancel");
+            alert.add_response("sign-in", "Sign In");
+            alert.set_response_appearance("sign-in", adw::ResponseAppearance::Suggested);
+            alert.set_default_response(Some("sign-in"));
+            alert.set_close_response("cancel");
+            key_row.set_activates_default(true);
+
+            let win_c = win.clone();
+            let lbl_c = lbl.clone();
+            let spn_c = spn.clone();
+            let btn_c = btn.clone();
+            alert.connect_response(Some("sign-in"), move |_, _| {
+                let api_key = key_row.text().trim().to_string();
+                if api_key.is_empty() {
+                    lbl_c.set_text("Please enter an API key");
+                    lbl_c.set_visible(true);
+                    return;
+                }
+                win_c.sign_in_with_api_key(&server_url, &api_key, &lbl_c, &spn_c, &btn_c);
+            });
+            alert.present(Some(&win));
+        });
+
         toolbar_view.upcast()
     }
 
+    /// Checks an API key against /api/me and signs in with it. After the
+    /// user trusts the server's certificate, the same key is tried again.
+    fn sign_in_with_api_key(
+        &self,
+        server_url: &str,
+        api_key: &str,
+        lbl: &gtk::Label,
+        spn: &gtk::Spinner,
+        btn: &gtk::Button,
+    ) {
+        spn.set_visible(true);
+        spn.set_spinning(true);
+        btn.set_sensitive(false);
+
+        let win = self.clone();
+        let lbl = lbl.clone();
+        let spn = spn.clone();
+        let btn = btn.clone();
+        let server = server_url.to_string();
+        let api_key = api_key.to_string();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let srv = server.clone();
+            let key = api_key.clone();
+            let client = win.imp().client.detached();
+            std::thread::spawn(move || {
+                client.set_server(&srv);
+                client.set_api_key(&key);
+                let _ = tx.send_blocking(client.get_me());
+            });
+
+            let result = rx.recv().await;
+            spn.set_spinning(false);
+            spn.set_visible(false);
+            btn.set_sensitive(true);
+            match result {
+                Ok(Ok(user)) => {
+                    *win.imp().current_user.borrow_mut() = Some(user);
+                    win.on_api_key_login(&server, &api_key);
+                }
+                Ok(Err(ApiError::Certificate(e))) => {
+                    log::warn!("Server certificate is not trusted ({})", e);
+                    let win_retry = win.clone();
+                    let srv = server.clone();
+                    win.confirm_server_certificate(&server, move || {
+                        win_retry.sign_in_with_api_key(&srv, &api_key, &lbl, &spn, &btn);
+                    });
+                }
+                Ok(Err(ApiError::Auth(_))) => {
+                    lbl.set_text("The server did not accept this API key");
+                    lbl.set_visible(true);
+                }
+                Ok(Err(e)) => {
+                    lbl.set_text(&format!("Login failed: {}", e));
+                    lbl.set_visible(true);
+                }
+                Err(_) => {
+                    lbl.set_text("Connection error");
+                    lbl.set_visible(true);
+                }
+            }
+        });
+    }
+
+    /// Signs in with the tokens of a password or OpenID login
+    fn finish_login(&self, server_url: &str, login_resp: LoginResponse) {
+        let legacy_token = login_resp
+            .user
+            .token
+            .as_deref()
+            .unwrap_or_default()
+            .to_string();
+        let raw_access_token = login_resp
+            .user
+            .access_token
+            .as_deref()
+            .unwrap_or_default()
+            .to_string();
+        let refresh_token = login_resp
+            .user
+            .refresh_token
+            .as_deref()
+            .unwrap_or_default()
+            .to_string();
+        let access_token = login_resp
+            .user
+            .access_token
+            .as_deref()
+            .or(login_resp.user.token.as_deref())
+            .unwrap_or_default();
+        let session_token = Self::preferred_session_token(
+            if raw_access_token.is_empty() {
+                access_token
+            } else {
+                &raw_access_token
+            },
+            &legacy_token,
+            &refresh_token,
+        );
+        let default_lib = login_resp.user_default_library_id.unwrap_or_default();
+        *self.imp().current_user.borrow_mut() = Some(login_resp.user);
+
+        self.on_login_success(server_url, &session_token, &refresh_token, &default_lib);
+    }
+
     fn on_login_success(
         &self,
         server_url: &str,
@@ -3225,593 +4666,1114 @@ If this is a Flatpak build, ensure org.freedesktop.secrets is allowed. Original
         let imp = self.imp();
         imp.client.set_server(server_url);
         imp.client.set_tokens(access_token, refresh_token);
+        self.start_session(default_library_id);
+    }
+
+    /// Signs in with an API key that `/api/me` already accepted
+    fn on_api_key_login(&self, server_url: &str, api_key: &str) {
+        let imp = self.imp();
+        imp.client.set_server(server_url);
+        imp.client.set_api_key(api_key);
+        self.start_session("");
+    }
 
+    fn start_session(&self, default_library_id: &str) {
+        let imp = self.imp();
         if !default_library_id.is_empty() {
             *imp.library_id.borrow_mut() = default_library_id.to_string();
         }
+        self.select_cache_account();
 
         // Persist credentials
         self.save_credentials();
 
         imp.stack.set_visible_child_name("library");
         self.load_library();
-    }
 
-    // ─── OAUTH WEBVIEW ─────────────────────────────────────────────────────
+        // Password and API key logins already carry the user; OpenID logins
+        // only return tokens.
+        if imp.current_user.borrow().is_some() {
+            self.apply_user_permissions();
+        } else {
+            self.load_current_user();
+        }
+    }
 
-    fn show_oauth_webview(&self, server_url: &str, button_text: Option<&str>) {
-        let dialog = adw::Window::new();
-        let title = button_text.unwrap_or("OpenID Login");
-        dialog.set_title(Some(title));
-        dialog.set_default_width(500);
-        dialog.set_default_height(700);
-        dialog.set_transient_for(Some(self));
-        dialog.set_modal(true);
+    // ─── REMOTE CONTROL ────────────────────────────────────────────────────
 
-        let toolbar_view = adw::ToolbarView::new();
-        let header = adw::HeaderBar::new();
-        header.set_title_widget(Some(&adw::WindowTitle::new(title, server_url)));
-        toolbar_view.add_top_bar(&header);
+    /// Runs command-line requests in order. Once one of them needs the
+    /// library, it and everything after it wait until the library has
+    /// loaded.
+    pub fn handle_remote_commands(&self, commands: Vec<RemoteCommand>) -> Result<(), String> {
+        let imp = self.imp();
+        let mut commands = commands.into_iter();
+        for command in commands.by_ref() {
+            if command.needs_library() && !imp.commands_ready.get() {
+                let mut pending = imp.pending_commands.borrow_mut();
+                pending.push(command);
+                pending.extend(commands);
+                return Ok(());
+            }
+            self.run_remote_command(command)?;
+        }
+        Ok(())
+    }
 
-        let loading_bar = gtk::ProgressBar::new();
-        loading_bar.add_css_class("osd");
-        loading_bar.set_visible(false);
-        toolbar_view.add_top_bar(&loading_bar);
+    fn flush_remote_commands(&self) {
+        let imp = self.imp();
+        imp.commands_ready.set(true);
+        let pending = imp.pending_commands.take();
+        for command in pending {
+            if let Err(err) = self.run_remote_command(command) {
+                log::warn!("Command-line request failed: {}", err);
+                imp.toast_overlay.add_toast(adw::Toast::new(&err));
+            }
+        }
+    }
 
-        let webview = webkit6::WebView::new();
-        webview.set_vexpand(true);
-        webview.set_hexpand(true);
+    fn run_remote_command(&self, command: RemoteCommand) -> Result<(), String> {
+        let imp = self.imp();
+        let has_player = imp.pipeline.borrow().is_some();
+        match command {
+            RemoteCommand::Server(url) => {
+                let url = url.trim().trim_end_matches('/').to_string();
+                if imp.client.is_authenticated() {
+                    let current = imp.client.server_url();
+                    if current != url {
+                        return Err(format!(
+                            "Already signed in to {}; sign out to use {}",
+                            current, url
+                        ));
+                    }
+                } else {
+                    if let Some(row) = imp.login_server_row.borrow().as_ref() {
+                        row.set_text(&url);
+                    }
+                    imp.stack.set_visible_child_name("login");
+                }
+            }
+            RemoteCommand::Library(library_id) => {
+                if *imp.library_id.borrow() != library_id {
+                    if let Some(nav_view) = imp.nav_view.borrow().as_ref() {
+                        while nav_view.pop() {}
+                    }
+                    *imp.library_id.borrow_mut() = library_id;
+                    self.save_credentials();
+                    self.load_library();
+                }
+            }
+            RemoteCommand::Play(item_id) => self.start_playback(&item_id),
+            RemoteCommand::Resume => self.resume_last_item()?,
+            RemoteCommand::TogglePlayback if has_player => self.toggle_play_pause(),
+            RemoteCommand::TogglePlayback => self.resume_last_item()?,
+            RemoteCommand::Seek { .. } if !has_player => {
+                return Err("Nothing is playing".to_string());
+            }
+            RemoteCommand::Seek {
+                seconds,
+                relative: true,
+            } => self.seek_relative(seconds.round() as i64),
+            RemoteCommand::Seek {
+                seconds,
+                relative: false,
+            } => self.seek_to(seconds),
+            RemoteCommand::Speed(rate) => {
+                self.set_playback_rate(rate);
+                if let Some(btn) = imp.now_playing_speed_btn.borrow().as_ref() {
+                    btn.set_label(&format!("{:.1}×", rate));
+                }
+            }
+            RemoteCommand::SleepMinutes(minutes) => self.start_sleep_timer_minutes(minutes),
+            RemoteCommand::SleepEndOfChapter => self.start_sleep_timer_end_of_chapter(),
+            RemoteCommand::CancelSleep => self.cancel_sleep_timer(),
+            RemoteCommand::OpenItem { item_id, position } => {
+                self.open_audiobook_detail(&item_id);
+                if let Some(position) = position {
+                    self.start_playback_at(&item_id, position);
+                }
+            }
+            RemoteCommand::OpenAuthor { author_id, name } => {
+                let name = name
+                    .or_else(|| {
+                        imp.library_authors
+                            .borrow()
+                            .iter()
+                            .find(|a| a.id.as_deref() == Some(author_id.as_str()))
+                            .and_then(|a| a.name.clone())
+                    })
+                    .unwrap_or_else(|| "Author".to_string());
+                self.show_author_books(Some(&author_id), &name);
+            }
+            RemoteCommand::OpenSeries(name) => self.show_browse_entity(BrowseKind::Series, &name),
+            RemoteCommand::OpenCollection(collection_id) => self.show_collection(&collection_id),
+            RemoteCommand::OAuthCallback(uri) => {
+                self.complete_browser_login(oauth::callback_params(&uri))?
+            }
+        }
+        Ok(())
+    }
 
-        let auth_url = format!("{}/login?autoLaunch=1", server_url.trim_end_matches('/'));
-        log::info!("OAuth URL: {}", auth_url);
-        webview.load_uri(&auth_url);
+    /// Shows the library search with `query` filled in.
+    pub fn search_library(&self, query: &str) {
+        let imp = self.imp();
+        if let Some(bar) = imp.library_search_bar.borrow().as_ref() {
+            bar.set_search_mode(true);
+        }
+        if let Some(entry) = imp.library_search_entry.borrow().as_ref() {
+            entry.set_text(query);
+            entry.grab_focus();
+        }
+    }
 
-        toolbar_view.set_content(Some(&webview));
-        dialog.set_content(Some(&toolbar_view));
+    pub fn show_toast(&self, message: &str) {
+        self.imp().toast_overlay.add_toast(adw::Toast::new(message));
+    }
 
-        let lb = loading_bar.clone();
-        webview.connect_estimated_load_progress_notify(move |wv: &webkit6::WebView| {
-            let progress = wv.estimated_load_progress();
-            lb.set_fraction(progress);
-            lb.set_visible(progress < 1.0);
+    fn show_collection(&self, collection_id: &str) {
+        let client = self.imp().client.clone();
+        let id = collection_id.to_string();
+        let win = self.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(client.get_collection(&id));
         });
+        glib::spawn_future_local(async move {
+            let collection = match rx.recv().await {
+                Ok(Ok(collection)) => collection,
+                Ok(Err(err)) => {
+                    log::warn!("Load collection failed: {}", err);
+                    win.show_toast(&format!("Failed to load the collection: {}", err));
+                    return;
+                }
+                Err(_) => return,
+            };
+            let Some((container, loading, _)) = win.push_browse_page(&collection.name) else {
+                return;
+            };
+            container.remove(&loading);
 
-        let win = self.clone();
-        let dlg = dialog.clone();
-        let srv = server_url.to_string();
-        let token_found = Rc::new(Cell::new(false));
-        let token_found_c = token_found.clone();
+            if let Some(description) = collection.description.filter(|d| !d.is_empty()) {
+                let label = gtk::Label::new(Some(&description));
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+                container.append(&label);
+            }
+            let items = collection.books.unwrap_or_default();
+            let count_label = gtk::Label::new(Some(&format!("{} book(s)", items.len())));
+            count_label.add_css_class("dim-label");
+            count_label.add_css_class("caption");
+            count_label.set_halign(gtk::Align::Start);
+            container.append(&count_label);
+
+            let flowbox = gtk::FlowBox::new();
+            flowbox.set_valign(gtk::Align::Start);
+            flowbox.set_selection_mode(gtk::SelectionMode::None);
+            flowbox.set_homogeneous(true);
+            flowbox.set_max_children_per_line(6);
+            flowbox.set_min_children_per_line(1);
+            flowbox.set_column_spacing(12);
+            flowbox.set_row_spacing(12);
+            // Collections keep their own order
+            for item in &items {
+                flowbox.append(&win.create_book_card(item));
+            }
+            container.append(&flowbox);
+        });
+    }
 
-        webview.connect_uri_notify(move |wv: &webkit6::WebView| {
-            if token_found_c.get() {
+    /// Plays the most recently listened book from Continue Listening.
+    fn resume_last_item(&self) -> Result<(), String> {
+        let last = self
+            .imp()
+            .continue_items
+            .borrow()
+            .first()
+            .map(|item| item.id.clone());
+        match last {
+            Some(item_id) => {
+                self.start_playback(&item_id);
+                Ok(())
+            }
+            None => Err("There is no book to resume".to_string()),
+        }
+    }
+
+    // ─── BROWSER SIGN-IN ───────────────────────────────────────────────────
+
+    /// Starts an OpenID sign-in in the system browser with PKCE. The server
+    /// redirects back to the shelfily:// scheme, or to a loopback listener
+    /// on a fixed port when no handler is registered for it; either one
+    /// has to be allowed on the server. The embedded view is only used if
+    /// the browser can't be opened.
+    fn start_browser_login(&self, server_url: &str, button_text: Option<&str>) {
+        self.cancel_browser_login();
+        let pkce = match oauth::Pkce::new() {
+            Ok(pkce) => pkce,
+            Err(e) => {
+                log::warn!("Could not start the sign-in: {}", e);
+                self.show_toast(&format!("Could not start the sign-in: {}", e));
                 return;
             }
-            if let Some(uri) = wv.uri() {
-                let uri_str: String = uri.into();
-                log::debug!("WebView URI changed during OAuth flow");
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
 
-                if let Some(access_token) = extract_access_token(&uri_str) {
-                    token_found_c.set(true);
-                    log::info!("OAuth token received");
-                    let refresh_token = extract_refresh_token(&uri_str).unwrap_or_default();
-                    if !refresh_token.is_empty() {
-                        dlg.close();
-                        win.on_login_success(&srv, &access_token, &refresh_token, "");
-                        return;
+        let redirect_uri = if oauth::scheme_handler_registered() {
+            oauth::REDIRECT_URI.to_string()
+        } else {
+            let listener = match oauth::LoopbackListener::bind() {
+                Ok(listener) => listener,
+                Err(e) => {
+                    log::warn!("Could not listen for the sign-in redirect: {}", e);
+                    self.show_oauth_webview(server_url, button_text);
+                    return;
+                }
+            };
+            let redirect_uri = listener.redirect_uri();
+            let (tx, rx) = async_channel::bounded(1);
+            let cancel_wait = cancel.clone();
+            let state = pkce.state.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send_blocking(listener.wait(&state, cancel_wait));
+            });
+            let win = self.clone();
+            glib::spawn_future_local(async move {
+                let result = match rx.recv().await {
+                    Ok(Ok(Some(params))) => {
+                        win.present();
+                        win.complete_browser_login(params)
+                    }
+                    Ok(Ok(None)) | Err(_) => Ok(()),
+                    Ok(Err(e)) => {
+                        win.imp().pending_sign_in.take();
+                        Err(format!("Sign-in failed: {}", e))
                     }
+                };
+                if let Err(e) = result {
+                    log::warn!("{}", e);
+                    win.show_toast(&e);
+                }
+            });
+            redirect_uri
+        };
 
-                    let win_c = win.clone();
-                    let dlg_c = dlg.clone();
-                    let srv_c = srv.clone();
-                    let access_token_c = access_token.clone();
-                    if let Some(cookie_manager) =
-                        wv.network_session().and_then(|session| session.cookie_manager())
-                    {
-                        let cookie_uri = srv_c.clone();
-                        cookie_manager.cookies(&srv_c, gio::Cancellable::NONE, move |result| {
-                            let refresh_token = match result {
-                                Ok(cookies) => cookies
-                                    .into_iter()
-                                    .find_map(|mut cookie| {
-                                        let name = cookie.name()?.to_string();
-                                        if name == "refresh_token" {
-                                            cookie.value().map(|value| value.to_string())
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .unwrap_or_default(),
-                                Err(err) => {
-                                    log::warn!(
-                                        "Could not read OAuth refresh token cookie: {}",
-                                        err
-                                    );
-                                    String::new()
-                                }
-                            };
-                            if refresh_token.is_empty() {
-                                log::warn!(
-                                    "OAuth login completed without a refresh token; \
-the session may expire and require signing in again"
-                                );
-                            }
-                            dlg_c.close();
-                            win_c.on_login_success(
-                                &cookie_uri,
-                                &access_token_c,
-                                &refresh_token,
-                                "",
-                            );
-                        });
-                    } else {
+        let state = pkce.state.clone();
+        let challenge = pkce.challenge.clone();
+        *self.imp().pending_sign_in.borrow_mut() = Some(PendingSignIn {
+            server_url: server_url.trim_end_matches('/').to_string(),
+            pkce,
+            cookie: String::new(),
+            cancel,
+        });
+
+        let win = self.clone();
+        let server_url = server_url.to_string();
+        let button_text = button_text.map(|t| t.to_string());
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let client = win.imp().client.detached();
+            let srv = server_url.clone();
+            let sent_state = state.clone();
+            std::thread::spawn(move || {
+                client.set_server(&srv);
+                let _ = tx.send_blocking(client.openid_authorize(
+                    &challenge,
+                    &redirect_uri,
+                    &sent_state,
+                ));
+            });
+            let Ok(result) = rx.recv().await else {
+                return;
+            };
+            // Dropped if the sign-in was cancelled or restarted meanwhile
+            let current = win
+                .imp()
+                .pending_sign_in
+                .borrow()
+                .as_ref()
+                .is_some_and(|pending| pending.pkce.state == state);
+            if !current {
+                return;
+            }
+            let url = match result {
+                Ok((url, cookie)) => {
+                    if let Some(pending) = win.imp().pending_sign_in.borrow_mut().as_mut() {
+                        pending.cookie = cookie;
+                    }
+                    url
+                }
+                Err(e) => {
+                    log::warn!("Could not start the OpenID sign-in: {}", e);
+                    win.cancel_browser_login();
+                    win.show_toast(&format!("Sign-in failed: {}", e));
+                    return;
+                }
+            };
+            let launcher_win = win.clone();
+            gtk::UriLauncher::new(&url).launch(Some(&win), gio::Cancellable::NONE, move |result| {
+                match result {
+                    Ok(()) => launcher_win.show_toast("Continue signing in in your browser"),
+                    Err(e) => {
                         log::warn!(
-                            "OAuth cookie manager is unavailable; continuing without a refresh token"
+                            "Could not open the browser ({}), signing in here instead",
+                            e
                         );
-                        dlg.close();
-                        win.on_login_success(&srv, &access_token, "", "");
+                        launcher_win.cancel_browser_login();
+                        launcher_win.show_oauth_webview(&server_url, button_text.as_deref());
                     }
                 }
-            }
+            });
         });
-
-        dialog.present();
     }
 
-    pub fn logout(&self) {
-        self.stop_playback();
-        self.hide_player();
-
-        // Reset NavigationView to library root before switching to login
-        if let Some(nav_view) = self.imp().nav_view.borrow().as_ref() {
-            while nav_view.pop() {}
+    fn cancel_browser_login(&self) {
+        if let Some(pending) = self.imp().pending_sign_in.take() {
+            pending.cancel.store(true, Ordering::Relaxed);
         }
-
-        self.clear_stored_session();
-
-        self.imp().stack.set_visible_child_name("login");
     }
 
-    fn show_library_error(&self, message: &str) {
-        let imp = self.imp();
-        let flowbox = imp.library_flowbox.borrow();
-        let flowbox = flowbox.as_ref().unwrap();
-
-        while let Some(child) = flowbox.first_child() {
-            flowbox.remove(&child);
+    /// Exchanges the code from the sign-in redirect for a session, after
+    /// checking that the redirect belongs to the sign-in we started. A
+    /// redirect that doesn't leaves that sign-in waiting.
+    fn complete_browser_login(&self, params: HashMap<String, String>) -> Result<(), String> {
+        let matches = match self.imp().pending_sign_in.borrow().as_ref() {
+            Some(pending) => oauth::answers_sign_in(&params, &pending.pkce.state),
+            None => return Err("No sign-in is in progress".to_string()),
+        };
+        if !matches {
+            return Err("The sign-in response doesn't match the request; try again".to_string());
         }
+        let Some(pending) = self.imp().pending_sign_in.take() else {
+            return Err("No sign-in is in progress".to_string());
+        };
+        pending.cancel.store(true, Ordering::Relaxed);
+        if let Some(error) = params.get("error") {
+            let description = params.get("error_description").unwrap_or(error);
+            return Err(format!("Sign-in failed: {}", description));
+        }
+        let Some(code) = params.get("code").cloned() else {
+            return Err("The sign-in response has no code".to_string());
+        };
 
-        let status = adw::StatusPage::new();
-        status.set_icon_name(Some("dialog-error-symbolic"));
-        status.set_title("Could Not Load Library");
-        status.set_description(Some(message));
-        status.set_vexpand(true);
-
-        let retry_btn = gtk::Button::with_label("Try Again");
-        retry_btn.add_css_class("pill");
-        retry_btn.add_css_class("suggested-action");
         let win = self.clone();
-        retry_btn.connect_clicked(move |_| {
-            win.load_library();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let server_url = pending.server_url.clone();
+            let pkce = pending.pkce.clone();
+            let cookie = pending.cookie.clone();
+            let client = win.imp().client.detached();
+            std::thread::spawn(move || {
+                client.set_server(&server_url);
+                let _ = tx.send_blocking(client.openid_callback(
+                    &pkce.state,
+                    &code,
+                    &pkce.verifier,
+                    &cookie,
+                ));
+            });
+            match rx.recv().await {
+                Ok(Ok(login_resp)) => {
+                    log::info!("OpenID sign-in completed");
+                    win.finish_login(&pending.server_url, login_resp);
+                }
+                Ok(Err(e)) => {
+                    log::warn!("OpenID sign-in failed: {}", e);
+                    win.show_toast(&format!("Sign-in failed: {}", e));
+                }
+                Err(_) => {}
+            }
         });
-        status.set_child(Some(&retry_btn));
-
-        flowbox.append(&status);
-        self.set_library_loading(false);
+        Ok(())
     }
 
-    // ─── LIBRARY PAGE ──────────────────────────────────────────────────────
-
-    fn build_library_page(&self) -> gtk::Widget {
-        let toolbar_view = adw::ToolbarView::new();
-        let header = adw::HeaderBar::new();
+    // ─── SERVER TRUST ──────────────────────────────────────────────────────
 
-        let refresh_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
-        refresh_btn.set_tooltip_text(Some("Refresh"));
+    /// Shows the certificate of a server that failed verification and lets
+    /// the user pin it or import the CA that issued it. `on_trusted` runs
+    /// once the decision is saved, to retry whatever failed.
+    fn confirm_server_certificate(&self, server_url: &str, on_trusted: impl Fn() + 'static) {
         let win = self.clone();
-        refresh_btn.connect_clicked(move |_| {
-            win.load_library();
+        let server_url = server_url.trim_end_matches('/').to_string();
+        let on_trusted: Rc<dyn Fn()> = Rc::new(on_trusted);
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let srv = server_url.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send_blocking(trust::fetch_certificate(&srv));
+            });
+            match rx.recv().await {
+                Ok(Ok(der)) => win.present_certificate_dialog(&server_url, &der, on_trusted),
+                Ok(Err(e)) => {
+                    log::warn!("Could not read the server certificate: {}", e);
+                    win.show_toast(&format!("Could not read the server certificate: {}", e));
+                }
+                Err(_) => {}
+            }
         });
+    }
 
-        let sort_btn = gtk::MenuButton::new();
-        sort_btn.set_icon_name("view-sort-ascending-symbolic");
-        sort_btn.set_tooltip_text(Some("Sort All Books"));
-        sort_btn.add_css_class("flat");
+    fn present_certificate_dialog(&self, server_url: &str, der: &[u8], on_trusted: Rc<dyn Fn()>) {
+        let fingerprint = trust::fingerprint(der);
+        let existing = trust::load(server_url).unwrap_or_default();
+        let pinned_fingerprint = existing
+            .pinned_certificate
+            .as_deref()
+            .and_then(trust::pem_fingerprint);
+        let changed = pinned_fingerprint.is_some_and(|pinned| pinned != fingerprint);
+        let host = trust::host(server_url).unwrap_or_else(|| server_url.to_string());
+
+        let (heading, body) = if changed {
+            (
+                "Server Certificate Changed",
+                format!(
+                    "The certificate presented by {} is not the one you trusted before. \
+This can happen when the server renews its certificate, but it may also mean \
+someone is intercepting the connection. Only continue if you expected this change.",
+                    host
+                ),
+            )
+        } else {
+            (
+                "Untrusted Certificate",
+                format!(
+                    "The identity of {} could not be verified. If the server uses a \
+self-signed certificate or a private certificate authority, compare the fingerprint \
+below with the one on the server before trusting it.",
+                    host
+                ),
+            )
+        };
 
-        let sort_popover = gtk::Popover::new();
-        let sort_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
-        sort_box.set_margin_top(8);
-        sort_box.set_margin_bottom(8);
-        sort_box.set_margin_start(8);
-        sort_box.set_margin_end(8);
+        let alert = adw::AlertDialog::new(Some(heading), Some(&body));
+        let fingerprint_label = gtk::Label::new(Some(&format!("SHA-256\n{}", fingerprint)));
+        fingerprint_label.add_css_class("monospace");
+        fingerprint_label.add_css_class("caption");
+        fingerprint_label.set_wrap(true);
+        fingerprint_label.set_wrap_mode(gtk::pango::WrapMode::Char);
+        fingerprint_label.set_selectable(true);
+        fingerprint_label.set_justify(gtk::Justification::Center);
+        alert.set_extra_child(Some(&fingerprint_label));
+
+        alert.add_response("cancel", "Cancel");
+        alert.add_response("ca", "Use CA Bundle…");
+        alert.add_response("trust", "Trust Certificate");
+        alert.set_response_appearance(
+            "trust",
+            if changed {
+                adw::ResponseAppearance::Destructive
+            } else {
+                adw::ResponseAppearance::Suggested
+            },
+        );
+        alert.set_default_response(Some("cancel"));
+        alert.set_close_response("cancel");
 
-        let sort_new_btn = gtk::Button::with_label("Newly Added");
-        sort_new_btn.add_css_class("flat");
         let win = self.clone();
-        sort_new_btn.connect_clicked(move |_| {
-            win.imp().library_sort_mode.set(LibrarySortMode::NewlyAdded);
-            win.render_library();
-            win.render_continue_listening();
+        let server_url = server_url.to_string();
+        let pem = trust::der_to_pem(der);
+        alert.connect_response(None, move |_, response| match response {
+            "trust" => {
+                let trust = ServerTrust {
+                    pinned_certificate: Some(pem.clone()),
+                    ..existing.clone()
+                };
+                win.save_server_trust(&server_url, &trust);
+                on_trusted();
+            }
+            "ca" => win.import_ca_bundle(&server_url, existing.clone(), on_trusted.clone()),
+            _ => {}
         });
-        sort_box.append(&sort_new_btn);
+        alert.present(Some(self));
+    }
 
-        let sort_author_btn = gtk::Button::with_label("Author (A-Z)");
-        sort_author_btn.add_css_class("flat");
-        let win = self.clone();
-        sort_author_btn.connect_clicked(move |_| {
-            win.imp().library_sort_mode.set(LibrarySortMode::AuthorAsc);
-            win.render_library();
-            win.render_continue_listening();
-        });
-        sort_box.append(&sort_author_btn);
+    fn import_ca_bundle(&self, server_url: &str, existing: ServerTrust, on_trusted: Rc<dyn Fn()>) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Certificates"));
+        filter.add_mime_type("application/x-x509-ca-cert");
+        filter.add_mime_type("application/x-pem-file");
+        filter.add_suffix("pem");
+        filter.add_suffix("crt");
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+        let file_dialog = gtk::FileDialog::new();
+        file_dialog.set_title("Choose CA Bundle");
+        file_dialog.set_filters(Some(&filters));
 
-        let sort_title_btn = gtk::Button::with_label("Title (A-Z)");
-        sort_title_btn.add_css_class("flat");
         let win = self.clone();
-        sort_title_btn.connect_clicked(move |_| {
-            win.imp().library_sort_mode.set(LibrarySortMode::TitleAsc);
-            win.render_library();
-            win.render_continue_listening();
+        let server_url = server_url.to_string();
+        file_dialog.open(Some(self), gio::Cancellable::NONE, move |result| {
+            let Ok(file) = result else {
+                return;
+            };
+            let pem = match file.load_contents(gio::Cancellable::NONE) {
+                Ok((bytes, _)) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => {
+                    win.show_toast(&format!("Could not read CA bundle: {}", e));
+                    return;
+                }
+            };
+            if let Err(e) = trust::validate_ca_bundle(&pem) {
+                win.show_toast(&format!("Not a valid CA bundle: {}", e));
+                return;
+            }
+            let trust = ServerTrust {
+                ca_bundle: Some(pem),
+                ..existing.clone()
+            };
+            win.save_server_trust(&server_url, &trust);
+            on_trusted();
         });
-        sort_box.append(&sort_title_btn);
+    }
 
-        let sort_played_btn = gtk::Button::with_label("Recently Played");
-        sort_played_btn.add_css_class("flat");
-        let win = self.clone();
-        sort_played_btn.connect_clicked(move |_| {
-            win.imp()
-                .library_sort_mode
-                .set(LibrarySortMode::RecentlyPlayed);
-            win.render_library();
-            win.render_continue_listening();
-        });
-        sort_box.append(&sort_played_btn);
-
-        sort_popover.set_child(Some(&sort_box));
-        sort_btn.set_popover(Some(&sort_popover));
-
-        // Search: a toggle button in the header that reveals a SearchBar below.
-        let search_btn = gtk::ToggleButton::new();
-        search_btn.set_icon_name("system-search-symbolic");
-        search_btn.set_tooltip_text(Some("Search (Ctrl+F)"));
-        search_btn.add_css_class("flat");
-
-        let search_entry = gtk::SearchEntry::new();
-        search_entry.set_placeholder_text(Some("Search books, authors, bookmarks"));
-        search_entry.set_hexpand(true);
-        let win = self.clone();
-        search_entry.connect_search_changed(move |entry| {
-            *win.imp().library_search_query.borrow_mut() = entry.text().to_string();
-            win.render_library();
-            win.render_continue_listening();
-            win.render_bookmarks_tab();
-        });
-        *self.imp().library_search_entry.borrow_mut() = Some(search_entry.clone());
+    fn save_server_trust(&self, server_url: &str, trust: &ServerTrust) {
+        trust::save(server_url, trust);
+        let client = &self.imp().client;
+        if trust::origin(&client.server_url()) == trust::origin(server_url) {
+            client.reload_http_client();
+        }
+    }
 
-        let search_bar = gtk::SearchBar::new();
-        search_bar.set_child(Some(&search_entry));
-        search_bar.connect_entry(&search_entry);
-        search_bar.set_key_capture_widget(Some(self));
-        search_btn
-            .bind_property("active", &search_bar, "search-mode-enabled")
-            .bidirectional()
-            .sync_create()
-            .build();
-        // Clear the query when the search bar is closed.
-        let win = self.clone();
-        let entry_clear = search_entry.clone();
-        search_bar.connect_search_mode_enabled_notify(move |bar| {
-            if !bar.is_search_mode() {
-                entry_clear.set_text("");
-                *win.imp().library_search_query.borrow_mut() = String::new();
-                win.render_library();
-                win.render_continue_listening();
-                win.render_bookmarks_tab();
+    /// Lets the OAuth view accept the pinned certificate, and certificates
+    /// issued by an imported CA, the same way the API client does
+    fn apply_webview_trust(&self, webview: &webkit6::WebView, server_url: &str) {
+        let Some(trust) = trust::load(server_url) else {
+            return;
+        };
+        if let (Some(pem), Some(host), Some(session)) = (
+            trust.pinned_certificate.as_deref(),
+            trust::host(server_url),
+            webview.network_session(),
+        ) {
+            match gio::TlsCertificate::from_pem(pem) {
+                Ok(cert) => session.allow_tls_certificate_for_host(&cert, &host),
+                Err(e) => log::warn!("Could not load pinned certificate: {}", e),
+            }
+        }
+        if trust.ca_bundle.is_none() {
+            return;
+        }
+        let Some(anchors) = trust::anchors_path(server_url) else {
+            return;
+        };
+        webview.connect_load_failed_with_tls_errors(move |wv, failing_uri, certificate, _| {
+            let database = match gio::TlsFileDatabase::new(&anchors) {
+                Ok(database) => database,
+                Err(e) => {
+                    log::warn!("Could not load trusted certificates: {}", e);
+                    return false;
+                }
+            };
+            let Ok(identity) = gio::NetworkAddress::parse_uri(failing_uri, 443) else {
+                return false;
+            };
+            let verified = database
+                .verify_chain(
+                    certificate,
+                    gio::TLS_DATABASE_PURPOSE_AUTHENTICATE_SERVER,
+                    Some(&identity),
+                    gio::TlsInteraction::NONE,
+                    gio::TlsDatabaseVerifyFlags::NONE,
+                    gio::Cancellable::NONE,
+                )
+                .is_ok_and(|flags| flags.is_empty());
+            let (Some(host), Some(session)) = (trust::host(failing_uri), wv.network_session())
+            else {
+                return false;
+            };
+            if !verified {
+                return false;
             }
+            session.allow_tls_certificate_for_host(certificate, &host);
+            wv.load_uri(failing_uri);
+            true
         });
-        *self.imp().library_search_bar.borrow_mut() = Some(search_bar.clone());
-
-        let menu_button = gtk::MenuButton::new();
-        menu_button.set_primary(true);
-        menu_button.set_icon_name("open-menu-symbolic");
-        menu_button.set_tooltip_text(Some("Menu"));
+    }
 
-        let menu = gio::Menu::new();
-        menu.append(Some("Preferences"), Some("app.preferences"));
-        menu.append(Some("Keyboard Shortcuts"), Some("app.shortcuts"));
-        menu.append(Some("About"), Some("app.about"));
-        menu.append(Some("Log Out"), Some("app.logout"));
-        menu.append(Some("Quit"), Some("app.quit"));
-        menu_button.set_menu_model(Some(&menu));
+    // ─── CONNECTION SETTINGS ───────────────────────────────────────────────
 
-        // Left side: refresh, sort, search. Right side: hamburger menu.
-        header.pack_start(&refresh_btn);
-        header.pack_start(&sort_btn);
-        header.pack_start(&search_btn);
-        header.pack_end(&menu_button);
+    fn show_connection_settings(&self, server_url: &str) {
+        let server_url = server_url.trim().trim_end_matches('/').to_string();
+        if server_url.len() <= 7 || trust::origin(&server_url).is_none() {
+            self.show_toast("Enter the server URL first");
+            return;
+        }
+        let settings = connection::load(&server_url);
 
-        // ── ViewStack with two tabs ──
-        let view_stack = adw::ViewStack::new();
+        let dialog = adw::Dialog::new();
+        dialog.set_title("Connection Settings");
+        dialog.set_content_width(480);
+        dialog.set_content_height(640);
 
-        // Tab 1: Continue
-        let continue_scrolled = gtk::ScrolledWindow::new();
-        continue_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
-        continue_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+        let header = adw::HeaderBar::new();
+        header.set_title_widget(Some(&adw::WindowTitle::new(
+            "Connection Settings",
+            &server_url,
+        )));
+        let save_btn = gtk::Button::with_label("Save");
+        save_btn.add_css_class("suggested-action");
+        header.pack_end(&save_btn);
 
-        let continue_clamp = adw::Clamp::new();
-        continue_clamp.set_maximum_size(1200);
-        continue_clamp.set_margin_top(16);
-        continue_clamp.set_margin_bottom(16);
-        continue_clamp.set_margin_start(16);
-        continue_clamp.set_margin_end(16);
+        let page = adw::PreferencesPage::new();
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&page));
+        let toasts = adw::ToastOverlay::new();
+        toasts.set_child(Some(&toolbar_view));
+        dialog.set_child(Some(&toasts));
+
+        // Proxy
+        let proxy_group = adw::PreferencesGroup::new();
+        proxy_group.set_title("Proxy");
+        proxy_group.set_description(Some(
+            "An http://, https:// or socks5:// URL. Leave empty to use the system proxy settings",
+        ));
+        let proxy_row = adw::EntryRow::new();
+        proxy_row.set_title("Proxy URL");
+        proxy_row.set_text(settings.proxy.as_deref().unwrap_or_default());
+        proxy_group.add(&proxy_row);
+        page.add(&proxy_group);
+
+        // Basic auth
+        let basic_group = adw::PreferencesGroup::new();
+        basic_group.set_title("Basic Authentication");
+        basic_group.set_description(Some(
+            "For a reverse proxy that asks for a user name and password",
+        ));
+        let basic_user_row = adw::EntryRow::new();
+        basic_user_row.set_title("User Name");
+        basic_user_row.set_text(settings.basic_auth_user.as_deref().unwrap_or_default());
+        basic_group.add(&basic_user_row);
+        let basic_password_row = adw::PasswordEntryRow::new();
+        basic_password_row.set_title("Password");
+        basic_password_row.set_text(&settings.basic_auth_password);
+        basic_group.add(&basic_password_row);
+        page.add(&basic_group);
+
+        // Extra headers
+        let headers_group = adw::PreferencesGroup::new();
+        headers_group.set_title("Extra Headers");
+        headers_group.set_description(Some(
+            "Sent with every request, e.g. CF-Access-Client-Id and CF-Access-Client-Secret. \
+Values are stored in the keyring.",
+        ));
+        let add_header_btn = gtk::Button::from_icon_name("list-add-symbolic");
+        add_header_btn.add_css_class("flat");
+        add_header_btn.set_valign(gtk::Align::Center);
+        add_header_btn.set_tooltip_text(Some("Add Header"));
+        headers_group.set_header_suffix(Some(&add_header_btn));
+        page.add(&headers_group);
+
+        type HeaderRows = Rc<RefCell<Vec<(adw::EntryRow, adw::PasswordEntryRow)>>>;
+        let header_rows: HeaderRows = Rc::new(RefCell::new(Vec::new()));
+        let add_header_row = {
+            let group = headers_group.clone();
+            let header_rows = header_rows.clone();
+            Rc::new(move |header: &Header| {
+                let name_row = adw::EntryRow::new();
+                name_row.set_title("Header Name");
+                name_row.set_text(&header.name);
+                let value_row = adw::PasswordEntryRow::new();
+                value_row.set_title("Value");
+                value_row.set_text(&header.value);
+                let remove_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+                remove_btn.add_css_class("flat");
+                remove_btn.set_valign(gtk::Align::Center);
+                remove_btn.set_tooltip_text(Some("Remove Header"));
+                name_row.add_suffix(&remove_btn);
+                group.add(&name_row);
+                group.add(&value_row);
+                header_rows
+                    .borrow_mut()
+                    .push((name_row.clone(), value_row.clone()));
+
+                let group = group.clone();
+                let header_rows = header_rows.clone();
+                remove_btn.connect_clicked(move |_| {
+                    group.remove(&name_row);
+                    group.remove(&value_row);
+                    header_rows.borrow_mut().retain(|(row, _)| row != &name_row);
+                });
+            })
+        };
+        for header in &settings.headers {
+            add_header_row(header);
+        }
+        let add_row = add_header_row.clone();
+        add_header_btn.connect_clicked(move |_| add_row(&Header::default()));
+
+        // Client certificate
+        let cert_group = adw::PreferencesGroup::new();
+        cert_group.set_title("Client Certificate");
+        cert_group.set_description(Some(
+            "For servers that require mutual TLS. The key must be an unencrypted PKCS#8 \
+PEM file and is stored in the keyring.",
+        ));
+        page.add(&cert_group);
+        let client_certificate = Rc::new(RefCell::new(settings.client_certificate.clone()));
+        let client_key = Rc::new(RefCell::new(settings.client_key.clone()));
+        let pem_row = |title: &str, value: Rc<RefCell<Option<String>>>| {
+            let row = adw::ActionRow::new();
+            row.set_title(title);
+            row.set_subtitle(if value.borrow().is_some() {
+                "Set"
+            } else {
+                "Not set"
+            });
+            let clear_btn = gtk::Button::from_icon_name("edit-clear-symbolic");
+            clear_btn.add_css_class("flat");
+            clear_btn.set_valign(gtk::Align::Center);
+            clear_btn.set_tooltip_text(Some("Clear"));
+            clear_btn.set_visible(value.borrow().is_some());
+            let choose_btn = gtk::Button::with_label("Choose…");
+            choose_btn.set_valign(gtk::Align::Center);
+            row.add_suffix(&clear_btn);
+            row.add_suffix(&choose_btn);
+            cert_group.add(&row);
+
+            let row_clear = row.clone();
+            let value_clear = value.clone();
+            clear_btn.connect_clicked(move |btn| {
+                value_clear.borrow_mut().take();
+                row_clear.set_subtitle("Not set");
+                btn.set_visible(false);
+            });
 
-        let continue_flowbox = gtk::FlowBox::new();
-        continue_flowbox.set_valign(gtk::Align::Start);
-        continue_flowbox.set_max_children_per_line(6);
-        continue_flowbox.set_min_children_per_line(1);
-        continue_flowbox.set_column_spacing(16);
-        continue_flowbox.set_row_spacing(16);
-        continue_flowbox.set_homogeneous(true);
-        continue_flowbox.set_selection_mode(gtk::SelectionMode::None);
+            let dialog = dialog.clone();
+            let title = title.to_string();
+            choose_btn.connect_clicked(move |_| {
+                let filter = gtk::FileFilter::new();
+                filter.set_name(Some("PEM Files"));
+                filter.add_mime_type("application/x-pem-file");
+                filter.add_mime_type("application/x-x509-ca-cert");
+                filter.add_suffix("pem");
+                filter.add_suffix("crt");
+                filter.add_suffix("key");
+                let filters = gio::ListStore::new::<gtk::FileFilter>();
+                filters.append(&filter);
+                let file_dialog = gtk::FileDialog::new();
+                file_dialog.set_title(&format!("Choose {}", title));
+                file_dialog.set_filters(Some(&filters));
+
+                let row = row.clone();
+                let value = value.clone();
+                let clear_btn = clear_btn.clone();
+                let title = title.clone();
+                let window = dialog.root().and_downcast::<gtk::Window>();
+                file_dialog.open(window.as_ref(), gio::Cancellable::NONE, move |result| {
+                    let Ok(file) = result else {
+                        return;
+                    };
+                    match file.load_contents(gio::Cancellable::NONE) {
+                        Ok((bytes, _)) => {
+                            *value.borrow_mut() =
+                                Some(String::from_utf8_lossy(&bytes).into_owned());
+                            let name = file
+                                .basename()
+                                .map(|n| n.to_string_lossy().into_owned())
+                                .unwrap_or_else(|| "Set".to_string());
+                            row.set_subtitle(&name);
+                            clear_btn.set_visible(true);
+                        }
+                        Err(e) => log::warn!("Could not read {}: {}", title, e),
+                    }
+                });
+            });
+        };
+        pem_row("Certificate", client_certificate.clone());
+        pem_row("Private Key", client_key.clone());
 
-        // Stack so the empty state renders as a proper centered page instead of
-        // a cramped grid cell inside the flowbox.
-        let continue_stack = gtk::Stack::new();
-        continue_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        // Save
+        let win = self.clone();
+        let dialog_save = dialog.clone();
+        save_btn.connect_clicked(move |_| {
+            let text = |row: &adw::EntryRow| {
+                let text = row.text().trim().to_string();
+                (!text.is_empty()).then_some(text)
+            };
+            let settings = ConnectionSettings {
+                headers: header_rows
+                    .borrow()
+                    .iter()
+                    .filter_map(|(name_row, value_row)| {
+                        let name = name_row.text().trim().to_string();
+                        (!name.is_empty()).then(|| Header {
+                            name,
+                            value: value_row.text().trim().to_string(),
+                        })
+                    })
+                    .collect(),
+                proxy: text(&proxy_row),
+                client_certificate: client_certificate.borrow().clone(),
+                client_key: client_key.borrow().clone(),
+                basic_auth_user: text(&basic_user_row),
+                basic_auth_password: basic_password_row.text().to_string(),
+            };
+            if let Err(e) = settings
+                .validate()
+                .and_then(|_| connection::save(&server_url, &settings))
+            {
+                toasts.add_toast(adw::Toast::new(&e));
+                return;
+            }
+            let client = &win.imp().client;
+            if trust::origin(&client.server_url()) == trust::origin(&server_url) {
+                client.reload_http_client();
+            }
+            dialog_save.close();
+            win.show_toast("Connection settings saved");
+        });
 
-        let continue_empty = adw::StatusPage::new();
-        continue_empty.set_icon_name(Some("audio-headphones-symbolic"));
-        continue_empty.set_title("No Books in Progress");
-        continue_empty.set_description(Some("Start listening to a book to see it here"));
+        dialog.present(Some(self));
+    }
 
-        continue_stack.add_named(&continue_flowbox, Some("content"));
-        continue_stack.add_named(&continue_empty, Some("empty"));
-        continue_stack.set_visible_child_name("empty");
+    /// Sends the OAuth view through the configured proxy and answers basic
+    /// auth and client certificate requests with the saved credentials
+    fn apply_webview_connection(
+        &self,
+        webview: &webkit6::WebView,
+        connection: &ConnectionSettings,
+    ) {
+        if let Some(session) = webview.network_session() {
+            match connection.proxy.as_deref() {
+                Some(proxy) => {
+                    let settings = webkit6::NetworkProxySettings::new(Some(proxy), &[]);
+                    session.set_proxy_settings(webkit6::NetworkProxyMode::Custom, Some(&settings));
+                }
+                None => session.set_proxy_settings(webkit6::NetworkProxyMode::Default, None),
+            }
+        }
 
-        continue_clamp.set_child(Some(&continue_stack));
-        continue_scrolled.set_child(Some(&continue_clamp));
+        let basic_auth = connection
+            .basic_auth_user
+            .clone()
+            .map(|user| (user, connection.basic_auth_password.clone()));
+        let certificate = connection.tls_certificate();
+        if basic_auth.is_none() && certificate.is_none() {
+            return;
+        }
+        webview.connect_authenticate(move |_, request| {
+            let credential = match request.scheme() {
+                webkit6::AuthenticationScheme::ClientCertificateRequested => {
+                    certificate.as_ref().map(|cert| {
+                        webkit6::Credential::for_certificate(
+                            Some(cert),
+                            webkit6::CredentialPersistence::ForSession,
+                        )
+                    })
+                }
+                webkit6::AuthenticationScheme::HttpBasic if !request.is_retry() => {
+                    basic_auth.as_ref().map(|(user, password)| {
+                        webkit6::Credential::new(
+                            user,
+                            password,
+                            webkit6::CredentialPersistence::ForSession,
+                        )
+                    })
+                }
+                _ => None,
+            };
+            match credential {
+                Some(credential) => {
+                    request.authenticate(Some(&credential));
+                    true
+                }
+                None => false,
+            }
+        });
+    }
 
-        let continue_page = view_stack.add_titled(&continue_scrolled, Some("continue"), "Continue");
-        continue_page.set_icon_name(Some("media-playback-start-symbolic"));
+    // ─── OAUTH WEBVIEW ─────────────────────────────────────────────────────
 
-        // Tab 2: All Books
-        let library_scrolled = gtk::ScrolledWindow::new();
-        library_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
-        library_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+    fn show_oauth_webview(&self, server_url: &str, button_text: Option<&str>) {
+        let dialog = adw::Window::new();
+        let title = button_text.unwrap_or("OpenID Login");
+        dialog.set_title(Some(title));
+        dialog.set_default_width(500);
+        dialog.set_default_height(700);
+        dialog.set_transient_for(Some(self));
+        dialog.set_modal(true);
 
-        let library_clamp = adw::Clamp::new();
-        library_clamp.set_maximum_size(1200);
-        library_clamp.set_margin_top(16);
-        library_clamp.set_margin_bottom(16);
-        library_clamp.set_margin_start(16);
-        library_clamp.set_margin_end(16);
+        let toolbar_view = adw::ToolbarView::new();
+        let header = adw::HeaderBar::new();
+        header.set_title_widget(Some(&adw::WindowTitle::new(title, server_url)));
+        toolbar_view.add_top_bar(&header);
 
-        let all_books_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        let loading_bar = gtk::ProgressBar::new();
+        loading_bar.add_css_class("osd");
+        loading_bar.set_visible(false);
+        toolbar_view.add_top_bar(&loading_bar);
 
-        let flowbox = gtk::FlowBox::new();
-        flowbox.set_valign(gtk::Align::Start);
-        flowbox.set_max_children_per_line(6);
-        flowbox.set_min_children_per_line(1);
-        flowbox.set_column_spacing(16);
-        flowbox.set_row_spacing(16);
-        flowbox.set_homogeneous(true);
-        flowbox.set_selection_mode(gtk::SelectionMode::None);
+        let webview = webkit6::WebView::new();
+        webview.set_vexpand(true);
+        webview.set_hexpand(true);
 
-        all_books_box.append(&flowbox);
+        self.apply_webview_trust(&webview, server_url);
+        let connection = connection::load(server_url);
+        self.apply_webview_connection(&webview, &connection);
 
-        let library_stack = gtk::Stack::new();
-        library_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
-        let library_empty = adw::StatusPage::new();
-        library_empty.set_icon_name(Some("system-search-symbolic"));
-        library_empty.set_title("No Books Found");
-        library_empty.set_description(Some("No books match your search"));
-        library_stack.add_named(&all_books_box, Some("content"));
-        library_stack.add_named(&library_empty, Some("empty"));
-        library_stack.set_visible_child_name("content");
+        let auth_url = format!("{}/login?autoLaunch=1", server_url.trim_end_matches('/'));
+        log::info!("OAuth URL: {}", auth_url);
+        // Headers only go with the first request; access proxies such as
+        // Cloudflare Access answer it with a session cookie
+        let request = webkit6::URIRequest::new(&auth_url);
+        if let Some(http_headers) = request.http_headers() {
+            for header in &connection.headers {
+                http_headers.replace(&header.name, &header.value);
+            }
+        }
+        webview.load_request(&request);
 
-        library_clamp.set_child(Some(&library_stack));
-        library_scrolled.set_child(Some(&library_clamp));
+        toolbar_view.set_content(Some(&webview));
+        dialog.set_content(Some(&toolbar_view));
 
-        let library_page = view_stack.add_titled(&library_scrolled, Some("all"), "All Books");
-        library_page.set_icon_name(Some("view-grid-symbolic"));
+        let lb = loading_bar.clone();
+        webview.connect_estimated_load_progress_notify(move |wv: &webkit6::WebView| {
+            let progress = wv.estimated_load_progress();
+            lb.set_fraction(progress);
+            lb.set_visible(progress < 1.0);
+        });
 
-        // Tab 3: Bookmarks
-        let bookmarks_scrolled = gtk::ScrolledWindow::new();
-        bookmarks_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
-        bookmarks_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let srv = server_url.to_string();
+        let token_found = Rc::new(Cell::new(false));
+        let token_found_c = token_found.clone();
 
-        let bookmarks_clamp = adw::Clamp::new();
-        bookmarks_clamp.set_maximum_size(1200);
-        bookmarks_clamp.set_margin_top(16);
-        bookmarks_clamp.set_margin_bottom(16);
-        bookmarks_clamp.set_margin_start(16);
-        bookmarks_clamp.set_margin_end(16);
+        webview.connect_uri_notify(move |wv: &webkit6::WebView| {
+            if token_found_c.get() {
+                return;
+            }
+            if let Some(uri) = wv.uri() {
+                let uri_str: String = uri.into();
+                log::debug!("WebView URI changed during OAuth flow");
 
-        let bookmarks_stack_inner = gtk::Stack::new();
-        bookmarks_stack_inner
-            .set_transition_type(gtk::StackTransitionType::Crossfade);
+                if let Some(access_token) = extract_access_token(&uri_str) {
+                    token_found_c.set(true);
+                    log::info!("OAuth token received");
+                    let refresh_token = extract_refresh_token(&uri_str).unwrap_or_default();
+                    if !refresh_token.is_empty() {
+                        dlg.close();
+                        win.on_login_success(&srv, &access_token, &refresh_token, "");
+                        return;
+                    }
 
-        let bookmarks_empty = adw::StatusPage::new();
-        bookmarks_empty.set_icon_name(Some("user-bookmarks-symbolic"));
-        bookmarks_empty.set_title("No bookmarks yet");
-        bookmarks_empty.set_description(Some(
-            "Use the bookmark button in the player bar to save positions",
-        ));
-
-        let bookmarks_list_box = gtk::Box::new(gtk::Orientation::Vertical, 16);
-
-        bookmarks_stack_inner.add_named(&bookmarks_empty, Some("empty"));
-        bookmarks_stack_inner.add_named(&bookmarks_list_box, Some("content"));
-        bookmarks_stack_inner.set_visible_child_name("empty");
-
-        bookmarks_clamp.set_child(Some(&bookmarks_stack_inner));
-        bookmarks_scrolled.set_child(Some(&bookmarks_clamp));
-
-        let bookmarks_page =
-            view_stack.add_titled(&bookmarks_scrolled, Some("bookmarks"), "Bookmarks");
-        bookmarks_page.set_icon_name(Some("user-bookmarks-symbolic"));
-
-        *self.imp().bookmarks_list_box.borrow_mut() = Some(bookmarks_list_box);
-        *self.imp().bookmarks_tab_empty.borrow_mut() = Some(bookmarks_empty);
-
-        // ViewSwitcher in the header
-        let switcher = adw::ViewSwitcher::new();
-        switcher.set_stack(Some(&view_stack));
-        switcher.set_policy(adw::ViewSwitcherPolicy::Wide);
-        header.set_title_widget(Some(&switcher));
-
-        // Modern GTK4/adwaita pattern: show bottom switcher on narrow widths
-        let switcher_bar = adw::ViewSwitcherBar::new();
-        switcher_bar.set_stack(Some(&view_stack));
-        switcher_bar.set_reveal(false);
-
-        toolbar_view.add_top_bar(&header);
-        toolbar_view.add_top_bar(&search_bar);
-        toolbar_view.add_bottom_bar(&switcher_bar);
-
-        // Skeleton loading: placeholder cards while the library loads.
-        let skeleton_scrolled = gtk::ScrolledWindow::new();
-        skeleton_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
-        let skeleton_clamp = adw::Clamp::new();
-        skeleton_clamp.set_maximum_size(1200);
-        skeleton_clamp.set_margin_top(16);
-        skeleton_clamp.set_margin_bottom(16);
-        skeleton_clamp.set_margin_start(16);
-        skeleton_clamp.set_margin_end(16);
-        let skeleton_flow = gtk::FlowBox::new();
-        skeleton_flow.set_valign(gtk::Align::Start);
-        skeleton_flow.set_max_children_per_line(6);
-        skeleton_flow.set_min_children_per_line(1);
-        skeleton_flow.set_column_spacing(16);
-        skeleton_flow.set_row_spacing(16);
-        skeleton_flow.set_homogeneous(true);
-        skeleton_flow.set_selection_mode(gtk::SelectionMode::None);
-        for _ in 0..12 {
-            let card = gtk::Box::new(gtk::Orientation::Vertical, 8);
-            card.set_width_request(160);
-            let cover = gtk::Box::new(gtk::Orientation::Vertical, 0);
-            cover.set_size_request(160, 160);
-            cover.add_css_class("skeleton");
-            cover.add_css_class("skeleton-cover");
-            let line = gtk::Box::new(gtk::Orientation::Vertical, 0);
-            line.set_size_request(120, 14);
-            line.add_css_class("skeleton");
-            line.add_css_class("skeleton-line");
-            card.append(&cover);
-            card.append(&line);
-            skeleton_flow.append(&card);
-        }
-        skeleton_clamp.set_child(Some(&skeleton_flow));
-        skeleton_scrolled.set_child(Some(&skeleton_clamp));
-
-        let content_stack = gtk::Stack::new();
-        content_stack.add_named(&skeleton_scrolled, Some("loading"));
-        content_stack.add_named(&view_stack, Some("content"));
-        content_stack.set_visible_child_name("loading");
-
-        toolbar_view.set_content(Some(&content_stack));
-
-        *self.imp().library_flowbox.borrow_mut() = Some(flowbox);
-        *self.imp().library_content_stack.borrow_mut() = Some(content_stack);
-        *self.imp().continue_flowbox.borrow_mut() = Some(continue_flowbox);
-        *self.imp().continue_stack.borrow_mut() = Some(continue_stack);
-        *self.imp().library_stack.borrow_mut() = Some(library_stack);
-        *self.imp().library_switcher_bar.borrow_mut() = Some(switcher_bar);
-        *self.imp().library_header_switcher.borrow_mut() = Some(switcher);
-
-        toolbar_view.upcast()
-    }
-
-    fn load_library(&self) {
-        let imp = self.imp();
-        let client = imp.client.clone();
-        let library_id = imp.library_id.borrow().clone();
-        let win = self.clone();
-
-        self.set_library_loading(true);
-
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            let lib_id = library_id.clone();
-
-            std::thread::spawn(move || {
-                let result = if lib_id.is_empty() {
-                    match client.get_libraries() {
-                        Ok(libs) => {
-                            if let Some(lib) = libs.first() {
-                                let first_id = lib.id.clone();
-                                match client.get_library_items(&first_id) {
-                                    Ok(items) => Ok((first_id, items)),
-                                    Err(e) => Err(e),
+                    let win_c = win.clone();
+                    let dlg_c = dlg.clone();
+                    let srv_c = srv.clone();
+                    let access_token_c = access_token.clone();
+                    if let Some(cookie_manager) =
+                        wv.network_session().and_then(|session| session.cookie_manager())
+                    {
+                        let cookie_uri = srv_c.clone();
+                        cookie_manager.cookies(&srv_c, gio::Cancellable::NONE, move |result| {
+                            let refresh_token = match result {
+                                Ok(cookies) => cookies
+                                    .into_iter()
+                                    .find_map(|mut cookie| {
+                                        let name = cookie.name()?.to_string();
+                                        if name == "refresh_token" {
+                                            cookie.value().map(|value| value.to_string())
+                                        } else {
+                                            None
+                                        }
+                                    })
+                                    .unwrap_or_default(),
+                                Err(err) => {
+                                    log::warn!(
+                                        "Could not read OAuth refresh token cookie: {}",
+                                        err
+                                    );
+                                    String::new()
                                 }
-                            } else {
-                                Ok((String::new(), vec![]))
+                            };
+                            if refresh_token.is_empty() {
+                                log::warn!(
+                                    "OAuth login completed without a refresh token; \
+the session may expire and require signing in again"
+                                );
                             }
-                        }
-                        Err(e) => Err(e),
-                    }
-                } else {
-                    match client.get_library_items(&lib_id) {
-                        Ok(items) => Ok((lib_id, items)),
-                        Err(e) => Err(e),
-                    }
-                };
-                let _ = tx.send_blocking(result);
-            });
-
-            match rx.recv().await {
-                Ok(Ok((lib_id, items))) => {
-                    log::info!("Library loaded: {} books", items.len());
-                    *win.imp().library_id.borrow_mut() = lib_id;
-                    if win.imp().client.is_authenticated() {
-                        win.save_credentials();
+                            dlg_c.close();
+                            win_c.on_login_success(
+                                &cookie_uri,
+                                &access_token_c,
+                                &refresh_token,
+                                "",
+                            );
+                        });
+                    } else {
+                        log::warn!(
+                            "OAuth cookie manager is unavailable; continuing without a refresh token"
+                        );
+                        dlg.close();
+                        win.on_login_success(&srv, &access_token, "", "");
                     }
-                    win.populate_library(&items);
-                    win.set_library_loading(false);
-                }
-                Ok(Err(e)) => {
-                    log::error!("Failed to load library: {}", e);
-                    win.set_library_loading(false);
-                    win.show_library_error(&format!("Failed to load library: {}", e));
-                }
-                Err(_) => {
-                    log::error!("Channel error while loading library");
-                    win.set_library_loading(false);
-                    win.show_library_error("Connection error");
                 }
             }
         });
+
+        dialog.present();
     }
 
-    fn set_library_loading(&self, loading: bool) {
+    /// Signs out after the server ended the session, keeping the server
+    /// address for signing in again.
+    fn session_expired(&self) {
         let imp = self.imp();
-        if let Some(ref cs) = *imp.library_content_stack.borrow() {
-            cs.set_visible_child_name(if loading { "loading" } else { "content" });
+        if imp.session_expired_shown.get()
+            || imp.stack.visible_child_name().as_deref() == Some("login")
+        {
+            return;
         }
+        imp.session_expired_shown.set(true);
+        let server_url = imp.client.server_url();
+        let body = if imp.client.uses_api_key() {
+            format!(
+                "{} no longer accepts your API key. Sign in again to continue.",
+                server_url
+            )
+        } else {
+            format!(
+                "Your session on {} has ended. Sign in again to continue.",
+                server_url
+            )
+        };
+        let alert = adw::AlertDialog::new(Some("Session Expired"), Some(&body));
+        alert.add_response("sign-in", "Sign In");
+        alert.set_default_response(Some("sign-in"));
+        alert.set_close_response("sign-in");
+        let win = self.clone();
+        alert.connect_response(None, move |_, _| {
+            win.imp().session_expired_shown.set(false);
+            win.logout();
+            if let Some(row) = win.imp().login_server_row.borrow().as_ref() {
+                row.set_text(&server_url);
+            }
+        });
+        alert.present(Some(self));
     }
 
-    fn populate_library(&self, items: &[LibraryItem]) {
-        let mut seen = HashSet::new();
-        let deduped: Vec<LibraryItem> = items
-            .iter()
-            .filter(|item| seen.insert(item.id.clone()))
-            .cloned()
-            .collect();
-
-        let mut cached = self.imp().library_items.borrow_mut();
-        cached.clear();
-        cached.extend(deduped);
-        drop(cached);
+    pub fn logout(&self) {
+        self.stop_playback();
+        self.hide_player();
 
-        self.render_library();
+        // Reset NavigationView to library root before switching to login
+        if let Some(nav_view) = self.imp().nav_view.borrow().as_ref() {
+            while nav_view.pop() {}
+        }
 
-        // Fetch "Continue" items from the server
-        self.load_continue_listening();
+        self.clear_stored_session();
+        *self.imp().current_user.borrow_mut() = None;
+        self.imp().commands_ready.set(false);
+        self.imp().restore_on_reconnect.set(false);
+        self.imp().pending_sync.take();
+        self.imp().deferred_downloads.take();
+        self.imp().offline_mode.set(false);
+        search_provider::clear_index();
+        cache::clear_state();
+        self.apply_user_permissions();
 
-        // Fetch all bookmarks for the Bookmarks tab
-        self.load_all_bookmarks();
+        self.imp().stack.set_visible_child_name("login");
     }
 
-    fn render_library(&self) {
+    fn show_library_error(&self, message: &str) {
         let imp = self.imp();
         let flowbox = imp.library_flowbox.borrow();
         let flowbox = flowbox.as_ref().unwrap();
@@ -3820,1229 +5782,7103 @@ the session may expire and require signing in again"
             flowbox.remove(&child);
         }
 
-        let mut items = imp.library_items.borrow().clone();
-        match imp.library_sort_mode.get() {
-            LibrarySortMode::NewlyAdded => {
-                items.sort_by_key(|item| Reverse(Self::item_added_timestamp(item)));
-            }
-            LibrarySortMode::AuthorAsc => {
-                items.sort_by_key(|item| {
-                    (
-                        Self::item_author_for_sort(item).to_lowercase(),
-                        Self::item_title_for_sort(item).to_lowercase(),
-                    )
-                });
-            }
-            LibrarySortMode::TitleAsc => {
-                items.sort_by_key(|item| {
-                    (
-                        Self::item_title_for_sort(item).to_lowercase(),
-                        Self::item_author_for_sort(item).to_lowercase(),
-                    )
-                });
-            }
-            LibrarySortMode::RecentlyPlayed => {
-                items.sort_by_key(|item| Reverse(Self::item_last_played_timestamp(item)));
-            }
-        }
+        let status = adw::StatusPage::new();
+        status.set_icon_name(Some("dialog-error-symbolic"));
+        status.set_title("Could Not Load Library");
+        status.set_description(Some(message));
+        status.set_vexpand(true);
 
-        let query = imp.library_search_query.borrow().trim().to_lowercase();
-        if !query.is_empty() {
-            items.retain(|item| {
-                let title = Self::item_title_for_sort(item).to_lowercase();
-                let author = Self::item_author_for_sort(item).to_lowercase();
-                title.contains(&query) || author.contains(&query)
-            });
-        }
+        let retry_btn = gtk::Button::with_label("Try Again");
+        retry_btn.add_css_class("pill");
+        retry_btn.add_css_class("suggested-action");
+        let win = self.clone();
+        retry_btn.connect_clicked(move |_| {
+            win.load_library();
+        });
+        status.set_child(Some(&retry_btn));
 
-        for item in &items {
-            let card = self.create_book_card(item);
-            flowbox.append(&card);
-        }
+        flowbox.append(&status);
+        self.set_library_loading(false);
+    }
 
-        // Show empty state when there are no matching books.
-        if let Some(stack) = imp.library_stack.borrow().as_ref() {
-            let has_query = !imp.library_search_query.borrow().trim().is_empty();
-            if let Some(empty) = stack
-                .child_by_name("empty")
-                .and_downcast::<adw::StatusPage>()
-            {
-                if has_query {
-                    empty.set_title("No Results");
-                    empty.set_description(Some("No books match your search"));
-                } else {
-                    empty.set_title("No Books");
-                    empty.set_description(Some("This library has no books yet"));
+    // ─── USER & ADMINISTRATION ─────────────────────────────────────────────
+
+    fn setup_window_actions(&self) {
+        let admin_action = gio::ActionEntry::builder("administration")
+            .activate(move |win: &Self, _, _| win.show_administration())
+            .build();
+        let upload_action = gio::ActionEntry::builder("upload")
+            .activate(move |win: &Self, _, _| win.show_upload_dialog(Vec::new()))
+            .build();
+        let narrators_action = gio::ActionEntry::builder("browse-narrators")
+            .activate(move |win: &Self, _, _| win.show_browse_list(&[BrowseKind::Narrator]))
+            .build();
+        let connection_action = gio::ActionEntry::builder("connection-settings")
+            .activate(move |win: &Self, _, _| {
+                let server_url = win.imp().client.server_url();
+                win.show_connection_settings(&server_url)
+            })
+            .build();
+        let genres_action = gio::ActionEntry::builder("browse-genres")
+            .activate(move |win: &Self, _, _| {
+                win.show_browse_list(&[BrowseKind::Genre, BrowseKind::Tag])
+            })
+            .build();
+        let bulk_actions = [
+            gio::ActionEntry::builder("bulk-mark-finished")
+                .activate(|win: &Self, _, _| win.bulk_mark_finished(true))
+                .build(),
+            gio::ActionEntry::builder("bulk-mark-unfinished")
+                .activate(|win: &Self, _, _| win.bulk_mark_finished(false))
+                .build(),
+            gio::ActionEntry::builder("bulk-reset-progress")
+                .activate(|win: &Self, _, _| win.bulk_reset_progress())
+                .build(),
+            gio::ActionEntry::builder("bulk-add-to-collection")
+                .activate(|win: &Self, _, _| win.bulk_add_to_collection())
+                .build(),
+            gio::ActionEntry::builder("bulk-add-to-queue")
+                .activate(|win: &Self, _, _| win.bulk_add_to_queue())
+                .build(),
+            gio::ActionEntry::builder("bulk-download")
+                .activate(|win: &Self, _, _| win.bulk_download())
+                .build(),
+            gio::ActionEntry::builder("bulk-remove-from-continue")
+                .activate(|win: &Self, _, _| win.bulk_remove_from_continue())
+                .build(),
+        ];
+        self.add_action_entries([
+            admin_action,
+            upload_action,
+            narrators_action,
+            genres_action,
+            connection_action,
+        ]);
+        self.add_action_entries(bulk_actions);
+
+        self.apply_user_permissions();
+    }
+
+    /// Points the offline cache and downloads at the signed-in account.
+    fn select_cache_account(&self) {
+        let client = &self.imp().client;
+        let user_id = client
+            .access_token()
+            .and_then(|token| tokens::user_id(&token))
+            .unwrap_or_default();
+        cache::set_account(&client.server_url(), &user_id);
+        search_provider::rebuild_index();
+        *self.imp().pending_sync.borrow_mut() = cache::load_pending_sync();
+    }
+
+    fn load_current_user(&self) {
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        let (tx, rx) = async_channel::bounded::<Result<User, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_me().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(user)) => {
+                    log::info!(
+                        "Signed in as {} ({})",
+                        user.username,
+                        user.user_type.as_deref().unwrap_or("user")
+                    );
+                    *win.imp().current_user.borrow_mut() = Some(user);
+                    win.apply_user_permissions();
                 }
+                Ok(Err(err)) => log::warn!("Load current user failed: {}", err),
+                Err(err) => log::warn!("Current user channel error: {}", err),
             }
-            stack.set_visible_child_name(if items.is_empty() { "empty" } else { "content" });
-        }
+        });
     }
 
-    fn item_title_for_sort(item: &LibraryItem) -> &str {
-        item.media
+    /// Checks a permission of the signed-in user; false while signed out or
+    /// before the user has been loaded.
+    fn current_user_can(&self, check: fn(&User) -> bool) -> bool {
+        self.imp()
+            .current_user
+            .borrow()
             .as_ref()
-            .and_then(|m| m.metadata.as_ref())
-            .and_then(|md| md.title.as_deref())
-            .unwrap_or("")
+            .map(check)
+            .unwrap_or(false)
     }
 
-    fn item_author_for_sort(item: &LibraryItem) -> &str {
-        item.media
-            .as_ref()
-            .and_then(|m| m.metadata.as_ref())
-            .and_then(|md| md.author_name_lf.as_deref().or(md.author_name.as_deref()))
-            .unwrap_or("")
+    /// Libraries the signed-in user may open, all of them while the user
+    /// isn't loaded yet
+    fn accessible_libraries(&self, libraries: Vec<Library>) -> Vec<Library> {
+        let user = self.imp().current_user.borrow();
+        libraries
+            .into_iter()
+            .filter(|l| user.as_ref().is_none_or(|u| u.can_access_library(&l.id)))
+            .collect()
     }
 
-    fn item_last_played_timestamp(item: &LibraryItem) -> u64 {
-        item.user_media_progress
-            .as_ref()
-            .and_then(|p| p.last_update)
-            .unwrap_or(0)
+    /// Enables or disables permission-gated actions for the signed-in user.
+    fn apply_user_permissions(&self) {
+        for (name, check) in [
+            ("administration", User::is_admin as fn(&User) -> bool),
+            ("upload", User::can_upload),
+            ("bulk-download", User::can_download),
+        ] {
+            let enabled = self.current_user_can(check);
+            if let Some(action) = self.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
+            }
+        }
     }
 
-    fn item_added_timestamp(item: &LibraryItem) -> u64 {
-        item.extra
-            .as_ref()
-            .and_then(|v| v.get("addedAt").and_then(|x| x.as_u64()))
-            .or_else(|| {
-                item.extra
-                    .as_ref()
-                    .and_then(|v| v.get("createdAt").and_then(|x| x.as_u64()))
-            })
-            .or_else(|| {
-                item.extra
-                    .as_ref()
-                    .and_then(|v| v.get("updatedAt").and_then(|x| x.as_u64()))
-            })
-            .unwrap_or(0)
+    fn show_administration(&self) {
+        if !self.current_user_can(User::is_admin) {
+            return;
+        }
+
+        let dialog = adw::PreferencesDialog::new();
+        dialog.set_title("Administration");
+        dialog.set_search_enabled(false);
+        dialog.add(&self.build_admin_libraries_page(&dialog));
+        dialog.add(&self.build_admin_users_page(&dialog));
+        dialog.add(&self.build_admin_logs_page(&dialog));
+        dialog.present(Some(self));
     }
 
-    fn load_continue_listening(&self) {
-        let imp = self.imp();
-        let client = imp.client.clone();
-        let win = self.clone();
+    fn build_admin_libraries_page(&self, dialog: &adw::PreferencesDialog) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::new();
+        page.set_title("Libraries");
+        page.set_icon_name(Some("folder-symbolic"));
 
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            let client_worker = client.clone();
-            std::thread::spawn(move || {
-                // Fetch items-in-progress and the user's media progress array together.
-                let items = client_worker.get_items_in_progress();
-                let me = client_worker.get_me();
-                let _ = tx.send_blocking((items, me));
-            });
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Library Scans");
+        group.set_description(Some(
+            "Scan a library to pick up new, changed and removed files",
+        ));
+        page.add(&group);
 
-            match rx.recv().await {
-                Ok((Ok(items), me_result)) => {
-                    log::info!("Continue listening items: {}", items.len());
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        group.add(&list);
 
-                    // Build lookup map from MediaProgress by libraryItemId
-                    let progress_map: HashMap<String, MediaProgress> = match me_result {
-                        Ok(user) => user
-                            .media_progress
-                            .unwrap_or_default()
-                            .into_iter()
-                            .filter_map(|p| {
-                                p.library_item_id
-                                    .clone()
-                                    .map(|id| (id, p))
-                            })
-                            .collect(),
-                        Err(e) => {
-                            log::warn!(
-                                "Failed to fetch user media progress for continue listening: {}",
-                                e
-                            );
-                            HashMap::new()
+        // library id → (row, spinner) so task polling can update progress in place
+        let rows: Rc<RefCell<HashMap<String, (adw::ActionRow, adw::Spinner)>>> =
+            Rc::new(RefCell::new(HashMap::new()));
+        // library id → polls without a matching task since the scan was requested
+        let scanning: Rc<RefCell<HashMap<String, u8>>> = Rc::new(RefCell::new(HashMap::new()));
+
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let rows_load = rows.clone();
+        let scanning_load = scanning.clone();
+        let (tx, rx) = async_channel::bounded::<Result<Vec<Library>, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_libraries().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(libraries)) => {
+                    for library in libraries {
+                        let row = adw::ActionRow::new();
+                        row.set_title(&library.name);
+                        row.set_subtitle("Idle");
+
+                        let spinner = adw::Spinner::new();
+                        spinner.set_visible(false);
+                        row.add_suffix(&spinner);
+
+                        let scan_btn = gtk::Button::with_label("Scan");
+                        scan_btn.set_valign(gtk::Align::Center);
+                        let force_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
+                        force_btn.add_css_class("flat");
+                        force_btn.set_valign(gtk::Align::Center);
+                        force_btn.set_tooltip_text(Some("Force re-scan of all files"));
+                        row.add_suffix(&scan_btn);
+                        row.add_suffix(&force_btn);
+
+                        for (btn, force) in [(scan_btn, false), (force_btn, true)] {
+                            let win_scan = win.clone();
+                            let dlg_scan = dlg.clone();
+                            let row_scan = row.clone();
+                            let spinner_scan = spinner.clone();
+                            let scanning_scan = scanning_load.clone();
+                            let library_id = library.id.clone();
+                            btn.connect_clicked(move |_| {
+                                win_scan.request_library_scan(
+                                    &dlg_scan,
+                                    &library_id,
+                                    force,
+                                    &row_scan,
+                                    &spinner_scan,
+                                    &scanning_scan,
+                                );
+                            });
                         }
-                    };
 
-                    let mut seen = HashSet::new();
-                    let deduped: Vec<LibraryItem> = items
-                        .into_iter()
-                        .filter(|item| seen.insert(item.id.clone()))
-                        .map(|mut item| {
-                            if item.user_media_progress.is_none() {
-                                if let Some(p) = progress_map.get(&item.id) {
-                                    item.user_media_progress = Some(p.clone());
-                                }
-                            }
-                            item
-                        })
-                        .collect();
-                    *win.imp().continue_items.borrow_mut() = deduped;
-                    win.render_continue_listening();
-                }
-                Ok((Err(e), _)) => {
-                    log::warn!("Failed to load continue listening books: {}", e);
+                        list.append(&row);
+                        rows_load
+                            .borrow_mut()
+                            .insert(library.id.clone(), (row, spinner));
+                    }
                 }
-                Err(_) => {
-                    log::warn!("Channel error while loading continue listening books");
+                Ok(Err(err)) => {
+                    log::warn!("Admin: load libraries failed: {}", err);
+                    dlg.add_toast(adw::Toast::new(&format!(
+                        "Failed to load libraries: {}",
+                        err
+                    )));
                 }
+                Err(err) => log::warn!("Admin libraries channel error: {}", err),
             }
         });
-    }
 
-    fn render_continue_listening(&self) {
-        let imp = self.imp();
-        let continue_flowbox = imp.continue_flowbox.borrow();
-        let continue_flowbox = continue_flowbox.as_ref().unwrap();
-        while let Some(child) = continue_flowbox.first_child() {
-            continue_flowbox.remove(&child);
-        }
+        // Poll the task list while the dialog is open so scans started from
+        // here (or elsewhere) show live progress.
+        let open = Rc::new(Cell::new(true));
+        let open_closed = open.clone();
+        dialog.connect_closed(move |_| open_closed.set(false));
 
-        let mut items = imp.continue_items.borrow().clone();
-        match imp.library_sort_mode.get() {
-            LibrarySortMode::NewlyAdded => {
-                items.sort_by_key(|item| Reverse(Self::item_added_timestamp(item)));
-            }
-            LibrarySortMode::AuthorAsc => {
-                items.sort_by_key(|item| {
-                    (
-                        Self::item_author_for_sort(item).to_lowercase(),
-                        Self::item_title_for_sort(item).to_lowercase(),
-                    )
-                });
+        let in_flight = Rc::new(Cell::new(false));
+        let win = self.clone();
+        glib::timeout_add_seconds_local(2, move || {
+            if !open.get() {
+                return glib::ControlFlow::Break;
             }
-            LibrarySortMode::TitleAsc => {
-                items.sort_by_key(|item| {
-                    (
-                        Self::item_title_for_sort(item).to_lowercase(),
-                        Self::item_author_for_sort(item).to_lowercase(),
-                    )
-                });
+            if in_flight.get() {
+                return glib::ControlFlow::Continue;
             }
-            LibrarySortMode::RecentlyPlayed => {
-                items.sort_by_key(|item| Reverse(Self::item_last_played_timestamp(item)));
+            in_flight.set(true);
+            let client = win.imp().client.clone();
+            let (tx, rx) = async_channel::bounded::<Result<Vec<ServerTask>, String>>(1);
+            std::thread::spawn(move || {
+                let result = client.get_tasks().map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+            let win_poll = win.clone();
+            let rows_poll = rows.clone();
+            let scanning_poll = scanning.clone();
+            let in_flight_poll = in_flight.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(tasks)) => {
+                        win_poll.update_admin_scan_rows(&rows_poll, &scanning_poll, &tasks)
+                    }
+                    Ok(Err(err)) => log::debug!("Admin: task poll failed: {}", err),
+                    Err(err) => log::debug!("Admin task channel error: {}", err),
+                }
+                in_flight_poll.set(false);
+            });
+            glib::ControlFlow::Continue
+        });
+
+        page
+    }
+
+    fn request_library_scan(
+        &self,
+        dialog: &adw::PreferencesDialog,
+        library_id: &str,
+        force: bool,
+        row: &adw::ActionRow,
+        spinner: &adw::Spinner,
+        scanning: &Rc<RefCell<HashMap<String, u8>>>,
+    ) {
+        row.set_subtitle("Scan requested…");
+        spinner.set_visible(true);
+
+        let client = self.imp().client.clone();
+        let id = library_id.to_string();
+        let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
+        std::thread::spawn(move || {
+            let result = client.scan_library(&id, force).map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+
+        let dlg = dialog.clone();
+        let row = row.clone();
+        let spinner = spinner.clone();
+        let scanning = scanning.clone();
+        let library_id = library_id.to_string();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(())) => {
+                    scanning.borrow_mut().insert(library_id, 0);
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Library scan request failed: {}", err);
+                    row.set_subtitle("Scan could not be started");
+                    spinner.set_visible(false);
+                    dlg.add_toast(adw::Toast::new(&format!("Failed to start scan: {}", err)));
+                }
+                Err(err) => log::warn!("Library scan channel error: {}", err),
             }
-        }
+        });
+    }
 
-        let query = imp.library_search_query.borrow().trim().to_lowercase();
-        if !query.is_empty() {
-            items.retain(|item| {
-                let title = Self::item_title_for_sort(item).to_lowercase();
-                let author = Self::item_author_for_sort(item).to_lowercase();
-                title.contains(&query) || author.contains(&query)
-            });
-        }
+    fn update_admin_scan_rows(
+        &self,
+        rows: &RefCell<HashMap<String, (adw::ActionRow, adw::Spinner)>>,
+        scanning: &RefCell<HashMap<String, u8>>,
+        tasks: &[ServerTask],
+    ) {
+        let current_library = self.imp().library_id.borrow().clone();
+        let mut finished_current = false;
+
+        for (library_id, (row, spinner)) in rows.borrow().iter() {
+            let task = tasks
+                .iter()
+                .filter(|t| t.is_library_scan() && t.library_id() == Some(library_id.as_str()))
+                .max_by_key(|t| t.started_at.unwrap_or(0));
+
+            let mut scans = scanning.borrow_mut();
+            let finished = match task {
+                Some(task) if !task.is_finished.unwrap_or(false) => {
+                    let text = task
+                        .description
+                        .as_deref()
+                        .or(task.title.as_deref())
+                        .unwrap_or("Scanning…");
+                    row.set_subtitle(text);
+                    spinner.set_visible(true);
+                    scans.insert(library_id.clone(), 0);
+                    false
+                }
+                Some(task) => {
+                    if task.is_failed.unwrap_or(false) {
+                        row.set_subtitle(&format!(
+                            "Scan failed: {}",
+                            task.error.as_deref().unwrap_or("unknown error")
+                        ));
+                    } else {
+                        row.set_subtitle(task.description.as_deref().unwrap_or("Scan finished"));
+                    }
+                    spinner.set_visible(false);
+                    scans.contains_key(library_id)
+                }
+                None => match scans.get_mut(library_id) {
+                    // Finished tasks drop out of /api/tasks after a few seconds;
+                    // give a freshly queued scan two polls to show up.
+                    Some(misses) if *misses >= 2 => {
+                        row.set_subtitle("Scan finished");
+                        spinner.set_visible(false);
+                        true
+                    }
+                    Some(misses) => {
+                        *misses += 1;
+                        false
+                    }
+                    None => false,
+                },
+            };
 
-        for item in &items {
-            let card = self.create_book_card(item);
-            continue_flowbox.append(&card);
+            if finished {
+                scans.remove(library_id);
+                if *library_id == current_library {
+                    finished_current = true;
+                }
+            }
         }
 
-        if let Some(stack) = imp.continue_stack.borrow().as_ref() {
-            stack.set_visible_child_name(if items.is_empty() { "empty" } else { "content" });
+        if finished_current {
+            let toast = adw::Toast::new("Library scan finished");
+            self.imp().toast_overlay.add_toast(toast);
+            self.load_library();
         }
     }
 
-    fn create_book_card(&self, item: &LibraryItem) -> gtk::Widget {
-        let card_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
-        card_box.add_css_class("book-card");
-        card_box.set_width_request(160);
-        card_box.set_halign(gtk::Align::Center);
-
-        let cover_frame = gtk::Frame::new(None);
-        cover_frame.set_halign(gtk::Align::Center);
-        cover_frame.add_css_class("card");
-        cover_frame.add_css_class("book-cover-frame");
-        // Clip children (cover image + progress overlay) to the rounded corners.
-        cover_frame.set_overflow(gtk::Overflow::Hidden);
+    fn build_admin_users_page(&self, dialog: &adw::PreferencesDialog) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::new();
+        page.set_title("Users");
+        page.set_icon_name(Some("system-users-symbolic"));
 
-        let cover_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
-        let cover_image = gtk::Image::from_icon_name("audio-x-generic-symbolic");
-        cover_image.set_pixel_size(160);
-        cover_image.set_size_request(160, 160);
-        cover_image.add_css_class("dim-label");
-        cover_box.append(&cover_image);
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Users");
+        let add_btn = gtk::Button::from_icon_name("list-add-symbolic");
+        add_btn.add_css_class("flat");
+        add_btn.set_tooltip_text(Some("Add user"));
+        group.set_header_suffix(Some(&add_btn));
+        page.add(&group);
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        group.add(&list);
 
-        let progress_val = item
-            .user_media_progress
-            .as_ref()
-            .and_then(|p| p.progress)
-            .unwrap_or(0.0);
-        let is_finished = item
-            .user_media_progress
-            .as_ref()
-            .and_then(|p| p.is_finished)
-            .unwrap_or(false);
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let list_add = list.clone();
+        add_btn.connect_clicked(move |_| {
+            win.prompt_create_user(&dlg, &list_add);
+        });
 
-        // Gelly tarzı: overlay üzerinde hover'da beliren dairesel play butonu
-        let cover_overlay = gtk::Overlay::new();
-        cover_overlay.set_child(Some(&cover_box));
+        self.load_admin_users(dialog, &list);
+        page
+    }
 
-        // Progress overlay at bottom of cover — a DrawingArea only repaints on
-        // resize/invalidation, unlike a tick callback which would run every
-        // frame forever for every card.
-        if progress_val > 0.0 || is_finished {
-            let frac = if is_finished { 1.0 } else { progress_val.clamp(0.0, 1.0) };
-            let progress = gtk::DrawingArea::new();
-            progress.set_height_request(8);
-            progress.set_hexpand(true);
-            progress.set_valign(gtk::Align::End);
-            progress.set_halign(gtk::Align::Fill);
-            progress.set_draw_func(move |_, cr, width, height| {
-                let w = width as f64;
-                let h = height as f64;
-                // Translucent track for contrast over any cover
-                cr.set_source_rgba(0.0, 0.0, 0.0, 0.55);
-                cr.rectangle(0.0, 0.0, w, h);
-                let _ = cr.fill();
-                // Accent fill
-                cr.set_source_rgba(0.21, 0.56, 1.0, 1.0);
-                cr.rectangle(0.0, 0.0, w * frac, h);
-                let _ = cr.fill();
-            });
-            cover_overlay.add_overlay(&progress);
-
-            // Small badge at top-right: checkmark when finished, else percent.
-            let badge = gtk::Label::new(None);
-            badge.add_css_class("cover-badge");
-            badge.set_halign(gtk::Align::End);
-            badge.set_valign(gtk::Align::Start);
-            badge.set_margin_top(6);
-            badge.set_margin_end(6);
-            if is_finished {
-                badge.set_text("\u{2713}"); // ✓
-                badge.add_css_class("cover-badge-done");
-            } else {
-                badge.set_text(&format!("{}%", (frac * 100.0).round() as i32));
+    fn load_admin_users(&self, dialog: &adw::PreferencesDialog, list: &gtk::ListBox) {
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let list = list.clone();
+        let (tx, rx) = async_channel::bounded::<Result<Vec<User>, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_users().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(users)) => {
+                    list.remove_all();
+                    for user in &users {
+                        let row = win.build_admin_user_row(&dlg, &list, user);
+                        list.append(&row);
+                    }
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Admin: load users failed: {}", err);
+                    dlg.add_toast(adw::Toast::new(&format!("Failed to load users: {}", err)));
+                }
+                Err(err) => log::warn!("Admin users channel error: {}", err),
             }
-            cover_overlay.add_overlay(&badge);
-        }
-
-        let hover_play = gtk::Button::from_icon_name("media-playback-start-symbolic");
-        hover_play.add_css_class("circular");
-        hover_play.add_css_class("suggested-action");
-        hover_play.add_css_class("cover-play-btn");
-        hover_play.set_halign(gtk::Align::Center);
-        hover_play.set_valign(gtk::Align::Center);
-        hover_play.set_opacity(0.0);
-        hover_play.set_size_request(48, 48);
-        cover_overlay.add_overlay(&hover_play);
-
-        let motion = gtk::EventControllerMotion::new();
-        let btn = hover_play.clone();
-        motion.connect_enter(move |_, _, _| btn.set_opacity(1.0));
-        let btn = hover_play.clone();
-        motion.connect_leave(move |_| btn.set_opacity(0.0));
-        cover_overlay.add_controller(motion);
-
-        let item_id_hover = item.id.clone();
-        let win_hover = self.clone();
-        hover_play.connect_clicked(move |_| {
-            win_hover.start_playback(&item_id_hover);
         });
+    }
 
-        cover_frame.set_child(Some(&cover_overlay));
-        card_box.append(&cover_frame);
-
-        let title = item
-            .media
+    fn build_admin_user_row(
+        &self,
+        dialog: &adw::PreferencesDialog,
+        list: &gtk::ListBox,
+        user: &User,
+    ) -> adw::ExpanderRow {
+        const USER_TYPES: [(&str, &str); 3] =
+            [("user", "User"), ("admin", "Admin"), ("guest", "Guest")];
+
+        let row = adw::ExpanderRow::new();
+        row.set_title(&user.username);
+
+        let user_type = user.user_type.clone().unwrap_or_else(|| "user".to_string());
+        let mut subtitle = match user_type.as_str() {
+            "root" => "Root".to_string(),
+            other => USER_TYPES
+                .iter()
+                .find(|(id, _)| *id == other)
+                .map(|(_, label)| label.to_string())
+                .unwrap_or_else(|| other.to_string()),
+        };
+        if !user.is_active.unwrap_or(true) {
+            subtitle.push_str(" · Disabled");
+        }
+        let is_self = self
+            .imp()
+            .current_user
+            .borrow()
             .as_ref()
-            .and_then(|m| m.metadata.as_ref())
-            .and_then(|md| md.title.as_deref())
-            .unwrap_or("Unknown Book");
+            .map(|u| u.id == user.id)
+            .unwrap_or(false);
+        if is_self {
+            subtitle.push_str(" · You");
+        }
+        row.set_subtitle(&subtitle);
 
-        let title_label = gtk::Label::new(Some(title));
-        title_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
-        title_label.set_max_width_chars(20);
-        title_label.set_lines(2);
-        title_label.set_wrap(true);
-        title_label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
-        title_label.add_css_class("heading");
-        title_label.set_halign(gtk::Align::Start);
-        card_box.append(&title_label);
+        // The root account can only be managed from the server itself.
+        if user.is_root() {
+            row.set_enable_expansion(false);
+            return row;
+        }
 
-        let author = item
-            .media
-            .as_ref()
-            .and_then(|m| m.metadata.as_ref())
-            .and_then(|md| md.author_name.as_deref())
-            .unwrap_or("Unknown Author");
+        let type_row = adw::ComboRow::new();
+        type_row.set_title("Account type");
+        let labels: Vec<&str> = USER_TYPES.iter().map(|(_, label)| *label).collect();
+        type_row.set_model(Some(&gtk::StringList::new(&labels)));
+        type_row.set_selected(
+            USER_TYPES
+                .iter()
+                .position(|(id, _)| *id == user_type)
+                .unwrap_or(0) as u32,
+        );
+        row.add_row(&type_row);
+
+        let active_row = adw::SwitchRow::new();
+        active_row.set_title("Active");
+        active_row.set_active(user.is_active.unwrap_or(true));
+        active_row.set_sensitive(!is_self);
+        row.add_row(&active_row);
+
+        let perms = user.permissions.clone().unwrap_or_default();
+        let perm_switch = |title: &str, value: Option<bool>| {
+            let switch = adw::SwitchRow::new();
+            switch.set_title(title);
+            switch.set_active(value.unwrap_or(false));
+            row.add_row(&switch);
+            switch
+        };
+        let download_row = perm_switch("Can download", perms.download);
+        let update_row = perm_switch("Can edit metadata", perms.update);
+        let delete_row = perm_switch("Can delete items", perms.delete);
+        let upload_row = perm_switch("Can upload", perms.upload);
+        let all_libraries_row = perm_switch("Access all libraries", perms.access_all_libraries);
 
-        let author_label = gtk::Label::new(Some(author));
-        author_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
-        author_label.set_max_width_chars(20);
-        author_label.add_css_class("dim-label");
-        author_label.add_css_class("caption");
-        author_label.set_halign(gtk::Align::Start);
-        card_box.append(&author_label);
+        let save_row = adw::ButtonRow::new();
+        save_row.set_title("Save Changes");
+        save_row.add_css_class("suggested-action");
+        row.add_row(&save_row);
 
-        if let Some(duration) = item.media.as_ref().and_then(|m| m.duration) {
-            let hours = (duration / 3600.0) as u32;
-            let mins = ((duration % 3600.0) / 60.0) as u32;
-            let dur_text = if hours > 0 {
-                format!("{} h {} min", hours, mins)
-            } else {
-                format!("{} min", mins)
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let list_save = list.clone();
+        let user_id = user.id.clone();
+        save_row.connect_activated(move |_| {
+            let update = UserUpdate {
+                user_type: USER_TYPES
+                    .get(type_row.selected() as usize)
+                    .map(|(id, _)| id.to_string()),
+                is_active: Some(active_row.is_active()),
+                permissions: Some(UserPermissions {
+                    download: Some(download_row.is_active()),
+                    update: Some(update_row.is_active()),
+                    delete: Some(delete_row.is_active()),
+                    upload: Some(upload_row.is_active()),
+                    access_all_libraries: Some(all_libraries_row.is_active()),
+                    ..perms.clone()
+                }),
+                ..UserUpdate::default()
             };
-            let dur_label = gtk::Label::new(Some(&dur_text));
-            dur_label.add_css_class("dim-label");
-            dur_label.add_css_class("caption");
-            dur_label.set_halign(gtk::Align::Start);
-            card_box.append(&dur_label);
+            win.save_admin_user(&dlg, &list_save, &user_id, update);
+        });
+
+        if !is_self {
+            let delete_btn_row = adw::ButtonRow::new();
+            delete_btn_row.set_title("Delete User");
+            delete_btn_row.add_css_class("destructive-action");
+            row.add_row(&delete_btn_row);
+
+            let win = self.clone();
+            let dlg = dialog.clone();
+            let list_delete = list.clone();
+            let user_id = user.id.clone();
+            let username = user.username.clone();
+            delete_btn_row.connect_activated(move |_| {
+                win.confirm_delete_admin_user(&dlg, &list_delete, &user_id, &username);
+            });
         }
 
-        let gesture = gtk::GestureClick::new();
-        let item_id = item.id.clone();
+        row
+    }
+
+    fn save_admin_user(
+        &self,
+        dialog: &adw::PreferencesDialog,
+        list: &gtk::ListBox,
+        user_id: &str,
+        update: UserUpdate,
+    ) {
+        let client = self.imp().client.clone();
+        let id = user_id.to_string();
+        let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
+        std::thread::spawn(move || {
+            let result = client.update_user(&id, &update).map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
         let win = self.clone();
-        gesture.connect_released(move |_, _, _, _| {
-            win.open_audiobook_detail(&item_id);
+        let dlg = dialog.clone();
+        let list = list.clone();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(())) => {
+                    dlg.add_toast(adw::Toast::new("User updated"));
+                    win.load_admin_users(&dlg, &list);
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Update user failed: {}", err);
+                    dlg.add_toast(adw::Toast::new(&format!("Failed to update user: {}", err)));
+                }
+                Err(err) => log::warn!("Update user channel error: {}", err),
+            }
         });
-        card_box.add_controller(gesture);
-        card_box.set_cursor_from_name(Some("pointer"));
+    }
 
-        // Load cover
-        let client = self.imp().client.clone();
-        let item_id = item.id.clone();
-        let img = cover_image.clone();
+    fn confirm_delete_admin_user(
+        &self,
+        dialog: &adw::PreferencesDialog,
+        list: &gtk::ListBox,
+        user_id: &str,
+        username: &str,
+    ) {
+        let alert = adw::AlertDialog::new(
+            Some("Delete User?"),
+            Some(&format!(
+                "{} will lose access to the server, including their listening progress",
+                username
+            )),
+        );
+        alert.add_response("cancel", "Cancel");
+        alert.add_response("delete", "Delete");
+        alert.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        alert.set_default_response(Some("cancel"));
+        alert.set_close_response("cancel");
 
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            let id = item_id.clone();
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let list = list.clone();
+        let user_id = user_id.to_string();
+        alert.connect_response(None, move |_, response| {
+            if response != "delete" {
+                return;
+            }
+            let client = win.imp().client.clone();
+            let id = user_id.clone();
+            let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
             std::thread::spawn(move || {
-                let result = client.download_cover(&id);
+                let result = client.delete_user(&id).map_err(|e| e.to_string());
                 let _ = tx.send_blocking(result);
             });
-            if let Ok(Ok(bytes)) = rx.recv().await {
-                let gbytes = glib::Bytes::from(&bytes);
-                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
-                if let Ok(pixbuf) =
-                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
-                {
-                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
-                    img.set_paintable(Some(&texture));
-                    img.set_size_request(160, -1);
-                    img.remove_css_class("dim-label");
+            let win_recv = win.clone();
+            let dlg_recv = dlg.clone();
+            let list_recv = list.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(())) => {
+                        dlg_recv.add_toast(adw::Toast::new("User deleted"));
+                        win_recv.load_admin_users(&dlg_recv, &list_recv);
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Delete user failed: {}", err);
+                        dlg_recv
+                            .add_toast(adw::Toast::new(&format!("Failed to delete user: {}", err)));
+                    }
+                    Err(err) => log::warn!("Delete user channel error: {}", err),
                 }
-            }
+            });
         });
 
-        card_box.upcast()
+        alert.present(Some(dialog));
     }
 
-    // ─── DETAIL PAGE ───────────────────────────────────────────────────────
+    fn prompt_create_user(&self, dialog: &adw::PreferencesDialog, list: &gtk::ListBox) {
+        let alert = adw::AlertDialog::new(Some("New User"), None);
 
-    fn open_audiobook_detail(&self, item_id: &str) {
-        let imp = self.imp();
+        let group = adw::PreferencesGroup::new();
+        let username_row = adw::EntryRow::new();
+        username_row.set_title("Username");
+        group.add(&username_row);
+        let password_row = adw::PasswordEntryRow::new();
+        password_row.set_title("Password");
+        group.add(&password_row);
+        let type_row = adw::ComboRow::new();
+        type_row.set_title("Account type");
+        type_row.set_model(Some(&gtk::StringList::new(&["User", "Admin", "Guest"])));
+        group.add(&type_row);
+        alert.set_extra_child(Some(&group));
+
+        alert.add_response("cancel", "Cancel");
+        alert.add_response("create", "Create");
+        alert.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+        alert.set_default_response(Some("create"));
+        alert.set_close_response("cancel");
 
-        let nav_view = match imp.nav_view.borrow().clone() {
-            Some(v) => v,
-            None => return,
-        };
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let list = list.clone();
+        alert.connect_response(None, move |_, response| {
+            if response != "create" {
+                return;
+            }
+            let username = username_row.text().trim().to_string();
+            if username.is_empty() {
+                dlg.add_toast(adw::Toast::new("A username is required"));
+                return;
+            }
+            let user_type = match type_row.selected() {
+                1 => "admin",
+                2 => "guest",
+                _ => "user",
+            };
+            let new_user = UserUpdate {
+                username: Some(username),
+                password: Some(password_row.text().to_string()),
+                user_type: Some(user_type.to_string()),
+                is_active: Some(true),
+                permissions: None,
+            };
 
-        // Pop back to library root (handles re-opening from detail page)
-        while nav_view.pop() {}
+            let client = win.imp().client.clone();
+            let (tx, rx) = async_channel::bounded::<Result<User, String>>(1);
+            std::thread::spawn(move || {
+                let result = client.create_user(&new_user).map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+            let win_recv = win.clone();
+            let dlg_recv = dlg.clone();
+            let list_recv = list.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(user)) => {
+                        dlg_recv
+                            .add_toast(adw::Toast::new(&format!("Created user {}", user.username)));
+                        win_recv.load_admin_users(&dlg_recv, &list_recv);
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Create user failed: {}", err);
+                        dlg_recv
+                            .add_toast(adw::Toast::new(&format!("Failed to create user: {}", err)));
+                    }
+                    Err(err) => log::warn!("Create user channel error: {}", err),
+                }
+            });
+        });
+
+        alert.present(Some(dialog));
+    }
+
+    fn build_admin_logs_page(&self, dialog: &adw::PreferencesDialog) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::new();
+        page.set_title("Logs");
+        page.set_icon_name(Some("utilities-terminal-symbolic"));
+
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Server Logs");
+        group.set_description(Some("Today's log output from the Audiobookshelf server"));
+        let refresh_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
+        refresh_btn.add_css_class("flat");
+        refresh_btn.set_tooltip_text(Some("Refresh"));
+        group.set_header_suffix(Some(&refresh_btn));
+        page.add(&group);
+
+        let text_view = gtk::TextView::new();
+        text_view.set_editable(false);
+        text_view.set_cursor_visible(false);
+        text_view.set_monospace(true);
+        text_view.set_wrap_mode(gtk::WrapMode::WordChar);
+        text_view.set_top_margin(8);
+        text_view.set_bottom_margin(8);
+        text_view.set_left_margin(8);
+        text_view.set_right_margin(8);
 
-        // Build detail content
         let scrolled = gtk::ScrolledWindow::new();
         scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
-        scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+        scrolled.set_min_content_height(420);
+        scrolled.add_css_class("card");
+        scrolled.set_child(Some(&text_view));
+        group.add(&scrolled);
 
-        let clamp = adw::Clamp::new();
-        clamp.set_maximum_size(800);
-        clamp.set_margin_top(24);
-        clamp.set_margin_bottom(24);
-        clamp.set_margin_start(24);
-        clamp.set_margin_end(24);
+        let win = self.clone();
+        let dlg = dialog.clone();
+        let tv = text_view.clone();
+        refresh_btn.connect_clicked(move |_| win.load_admin_logs(&dlg, &tv));
 
-        let detail_box = gtk::Box::new(gtk::Orientation::Vertical, 24);
-        detail_box.set_valign(gtk::Align::Start);
-        clamp.set_child(Some(&detail_box));
-        scrolled.set_child(Some(&clamp));
+        self.load_admin_logs(dialog, &text_view);
+        page
+    }
+
+    fn load_admin_logs(&self, dialog: &adw::PreferencesDialog, text_view: &gtk::TextView) {
+        let client = self.imp().client.clone();
+        let dlg = dialog.clone();
+        let text_view = text_view.clone();
+        let (tx, rx) = async_channel::bounded::<Result<Vec<LogEntry>, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_server_logs().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(entries)) => {
+                    let text = if entries.is_empty() {
+                        "No log entries for today".to_string()
+                    } else {
+                        entries
+                            .iter()
+                            .map(|entry| {
+                                format!(
+                                    "{} [{}] {}",
+                                    entry.timestamp.as_deref().unwrap_or(""),
+                                    entry.level_name.as_deref().unwrap_or("INFO"),
+                                    entry.message.as_deref().unwrap_or("")
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    };
+                    let buffer = text_view.buffer();
+                    buffer.set_text(&text);
+                    // Newest entries are at the bottom; keep them in view.
+                    let mut end = buffer.end_iter();
+                    text_view.scroll_to_iter(&mut end, 0.0, false, 0.0, 1.0);
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Admin: load server logs failed: {}", err);
+                    dlg.add_toast(adw::Toast::new(&format!("Failed to load logs: {}", err)));
+                }
+                Err(err) => log::warn!("Admin logs channel error: {}", err),
+            }
+        });
+    }
+
+    // ─── LIBRARY PAGE ──────────────────────────────────────────────────────
 
+    fn build_library_page(&self) -> gtk::Widget {
         let toolbar_view = adw::ToolbarView::new();
         let header = adw::HeaderBar::new();
-        toolbar_view.add_top_bar(&header);
-        toolbar_view.set_content(Some(&scrolled));
-
-        // Store widget refs before async fetch
-        *imp.detail_content.borrow_mut() = Some(detail_box);
-        *imp.detail_play_btn.borrow_mut() = None;
-        *imp.detail_play_item_id.borrow_mut() = Some(item_id.to_string());
-        *imp.detail_top_box.borrow_mut() = None;
-        *imp.detail_cover_image.borrow_mut() = None;
 
-        let detail_nav_page = adw::NavigationPage::builder()
-            .title("Yükleniyor...")
-            .child(&toolbar_view)
-            .build();
-        let detail_nav_page_ref = detail_nav_page.clone();
-        nav_view.push(&detail_nav_page);
+        let refresh_btn = gtk::Button::from_icon_name("view-refresh-symbolic");
+        refresh_btn.set_tooltip_text(Some("Refresh"));
+        let win = self.clone();
+        refresh_btn.connect_clicked(move |_| {
+            win.load_library();
+        });
 
-        let client = imp.client.clone();
+        let sort_btn = gtk::MenuButton::new();
+        sort_btn.set_icon_name("view-sort-ascending-symbolic");
+        sort_btn.set_tooltip_text(Some("Sort"));
+        sort_btn.add_css_class("flat");
+        let sort_popover = gtk::Popover::new();
         let win = self.clone();
-        let id = item_id.to_string();
+        sort_popover.connect_show(move |popover| win.build_sort_popover(popover));
+        sort_btn.set_popover(Some(&sort_popover));
 
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            let item_id = id.clone();
+        *self.imp().library_view.borrow_mut() = Self::read_library_view();
+        let list_view_btn = gtk::ToggleButton::new();
+        list_view_btn.set_icon_name("view-list-symbolic");
+        list_view_btn.set_tooltip_text(Some("List View"));
+        list_view_btn.add_css_class("flat");
+        list_view_btn.set_active(self.imp().library_view.borrow().mode == LibraryViewMode::List);
+        let selection_btn = gtk::ToggleButton::new();
+        selection_btn.set_icon_name("selection-mode-symbolic");
+        selection_btn.set_tooltip_text(Some("Select"));
+        selection_btn.add_css_class("flat");
+        let win = self.clone();
+        selection_btn.connect_toggled(move |btn| win.set_selection_mode(btn.is_active()));
+        *self.imp().selection_btn.borrow_mut() = Some(selection_btn.clone());
 
-            std::thread::spawn(move || {
-                let result = client.get_library_item(&item_id);
-                let _ = tx.send_blocking(result);
+        let win = self.clone();
+        list_view_btn.connect_toggled(move |btn| {
+            win.set_library_view_mode(if btn.is_active() {
+                LibraryViewMode::List
+            } else {
+                LibraryViewMode::Grid
             });
+        });
 
-            match rx.recv().await {
-                Ok(Ok(item)) => {
-                    let book_title = item
-                        .media
-                        .as_ref()
-                        .and_then(|m| m.metadata.as_ref())
-                        .and_then(|m| m.title.as_deref())
-                        .unwrap_or("Kitap Detayı");
-                    detail_nav_page_ref.set_title(book_title);
-                    win.populate_detail(&item);
-                }
-                Ok(Err(e)) => {
-                    log::error!("Failed to load book: {}", e);
-                }
-                Err(_) => {
-                    log::error!("Channel error");
-                }
+        let filter_btn = gtk::MenuButton::new();
+        filter_btn.set_icon_name("funnel-symbolic");
+        filter_btn.set_tooltip_text(Some("Filter All Books"));
+        filter_btn.add_css_class("flat");
+        let filter_popover = gtk::Popover::new();
+        let win = self.clone();
+        filter_popover.connect_show(move |popover| win.build_filter_popover(popover));
+        filter_btn.set_popover(Some(&filter_popover));
+
+        // Search: a toggle button in the header that reveals a SearchBar below.
+        let search_btn = gtk::ToggleButton::new();
+        search_btn.set_icon_name("system-search-symbolic");
+        search_btn.set_tooltip_text(Some("Search (Ctrl+F)"));
+        search_btn.add_css_class("flat");
+
+        let search_entry = gtk::SearchEntry::new();
+        search_entry.set_placeholder_text(Some("Search books, authors, bookmarks"));
+        search_entry.set_hexpand(true);
+        let win = self.clone();
+        search_entry.connect_search_changed(move |entry| {
+            *win.imp().library_search_query.borrow_mut() = entry.text().to_string();
+            win.render_library();
+            win.render_continue_listening();
+            win.render_bookmarks_tab();
+            win.render_authors_tab();
+        });
+        *self.imp().library_search_entry.borrow_mut() = Some(search_entry.clone());
+
+        let search_bar = gtk::SearchBar::new();
+        search_bar.set_child(Some(&search_entry));
+        search_bar.connect_entry(&search_entry);
+        search_bar.set_key_capture_widget(Some(self));
+        search_btn
+            .bind_property("active", &search_bar, "search-mode-enabled")
+            .bidirectional()
+            .sync_create()
+            .build();
+        // Clear the query when the search bar is closed.
+        let win = self.clone();
+        let entry_clear = search_entry.clone();
+        search_bar.connect_search_mode_enabled_notify(move |bar| {
+            if !bar.is_search_mode() {
+                entry_clear.set_text("");
+                *win.imp().library_search_query.borrow_mut() = String::new();
+                win.render_library();
+                win.render_continue_listening();
+                win.render_bookmarks_tab();
+                win.render_authors_tab();
             }
         });
-    }
+        *self.imp().library_search_bar.borrow_mut() = Some(search_bar.clone());
 
-    fn populate_detail(&self, item: &LibraryItemExpanded) {
-        let imp = self.imp();
-        let detail_box = imp.detail_content.borrow();
-        let detail_box = detail_box.as_ref().unwrap();
+        let menu_button = gtk::MenuButton::new();
+        menu_button.set_primary(true);
+        menu_button.set_icon_name("open-menu-symbolic");
+        menu_button.set_tooltip_text(Some("Menu"));
 
-        while let Some(child) = detail_box.first_child() {
-            detail_box.remove(&child);
-        }
-        *imp.detail_play_btn.borrow_mut() = None;
-        *imp.detail_play_item_id.borrow_mut() = Some(item.id.clone());
-        *imp.detail_top_box.borrow_mut() = None;
-        *imp.detail_cover_image.borrow_mut() = None;
-        imp.chapter_indicators.borrow_mut().clear();
-        imp.bookmarks.borrow_mut().clear();
-        *imp.bookmarks_group.borrow_mut() = None;
-        *imp.bookmarks_section.borrow_mut() = None;
+        let menu = gio::Menu::new();
+        let browse_section = gio::Menu::new();
+        browse_section.append(Some("Narrators"), Some("win.browse-narrators"));
+        browse_section.append(Some("Genres & Tags"), Some("win.browse-genres"));
+        menu.append_section(None, &browse_section);
+        menu.append(Some("Preferences"), Some("app.preferences"));
+        menu.append(
+            Some("Connection Settings…"),
+            Some("win.connection-settings"),
+        );
+        // Permission-gated entries are hidden while their action is disabled.
+        let upload_item = gio::MenuItem::new(Some("Upload Books…"), Some("win.upload"));
+        upload_item.set_attribute_value("hidden-when", Some(&"action-disabled".to_variant()));
+        menu.append_item(&upload_item);
+        let admin_item = gio::MenuItem::new(Some("Administration"), Some("win.administration"));
+        admin_item.set_attribute_value("hidden-when", Some(&"action-disabled".to_variant()));
+        menu.append_item(&admin_item);
+        menu.append(Some("Keyboard Shortcuts"), Some("app.shortcuts"));
+        menu.append(Some("About"), Some("app.about"));
+        menu.append(Some("Log Out"), Some("app.logout"));
+        menu.append(Some("Quit"), Some("app.quit"));
+        menu_button.set_menu_model(Some(&menu));
 
-        let metadata = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+        // Left side: refresh, sort, search. Right side: hamburger menu.
+        header.pack_start(&refresh_btn);
+        header.pack_start(&sort_btn);
+        header.pack_start(&filter_btn);
+        header.pack_start(&search_btn);
+        header.pack_end(&menu_button);
+        header.pack_end(&list_view_btn);
+        header.pack_end(&selection_btn);
 
-        // Top: cover + info
-        let top_box = gtk::Box::new(gtk::Orientation::Horizontal, 24);
+        // ── ViewStack with two tabs ──
+        let view_stack = adw::ViewStack::new();
 
-        let cover_frame = gtk::Frame::new(None);
-        cover_frame.add_css_class("card");
-        cover_frame.add_css_class("book-cover-frame");
-        cover_frame.set_valign(gtk::Align::Start);
-        cover_frame.set_overflow(gtk::Overflow::Hidden);
-        let cover_image = gtk::Image::from_icon_name("audio-x-generic-symbolic");
-        cover_image.set_pixel_size(220);
-        cover_image.set_size_request(220, 220);
-        cover_frame.set_child(Some(&cover_image));
-        top_box.append(&cover_frame);
-        *imp.detail_top_box.borrow_mut() = Some(top_box.clone());
-        *imp.detail_cover_image.borrow_mut() = Some(cover_image.clone());
+        // Tab 1: Continue
+        let continue_scrolled = gtk::ScrolledWindow::new();
+        continue_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        continue_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
 
-        // Load cover
-        let client = imp.client.clone();
-        let item_id = item.id.clone();
-        let img = cover_image.clone();
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            std::thread::spawn(move || {
-                let result = client.download_cover(&item_id);
-                let _ = tx.send_blocking(result);
-            });
-            if let Ok(Ok(bytes)) = rx.recv().await {
-                let gbytes = glib::Bytes::from(&bytes);
-                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
-                if let Ok(pixbuf) =
-                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
-                {
-                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
-                    img.set_paintable(Some(&texture));
-                    img.set_size_request(220, -1);
-                }
-            }
-        });
+        let continue_clamp = adw::Clamp::new();
+        continue_clamp.set_maximum_size(1200);
+        continue_clamp.set_margin_top(16);
+        continue_clamp.set_margin_bottom(16);
+        continue_clamp.set_margin_start(16);
+        continue_clamp.set_margin_end(16);
 
-        let info_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
-        info_box.set_valign(gtk::Align::Center);
-        info_box.set_hexpand(true);
+        let continue_flowbox = gtk::FlowBox::new();
+        continue_flowbox.set_valign(gtk::Align::Start);
+        continue_flowbox.set_max_children_per_line(6);
+        continue_flowbox.set_min_children_per_line(1);
+        continue_flowbox.set_column_spacing(16);
+        continue_flowbox.set_row_spacing(16);
+        continue_flowbox.set_homogeneous(true);
+        continue_flowbox.set_selection_mode(gtk::SelectionMode::None);
+        let win = self.clone();
+        continue_flowbox.connect_selected_children_changed(move |_| win.update_selection_label());
 
-        let title = metadata
-            .and_then(|m| m.title.as_deref())
-            .unwrap_or("Unknown Book");
-        let title_label = gtk::Label::new(Some(title));
-        title_label.add_css_class("title-1");
-        title_label.set_halign(gtk::Align::Start);
-        title_label.set_wrap(true);
-        info_box.append(&title_label);
+        // Stack so the empty state renders as a proper centered page instead of
+        // a cramped grid cell inside the flowbox.
+        let continue_stack = gtk::Stack::new();
+        continue_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
 
-        if let Some(subtitle) = metadata.and_then(|m| m.subtitle.as_deref()) {
-            let sub_label = gtk::Label::new(Some(subtitle));
-            sub_label.add_css_class("title-3");
-            sub_label.add_css_class("dim-label");
-            sub_label.set_halign(gtk::Align::Start);
-            sub_label.set_wrap(true);
-            info_box.append(&sub_label);
-        }
+        let continue_empty = adw::StatusPage::new();
+        continue_empty.set_icon_name(Some("audio-headphones-symbolic"));
+        continue_empty.set_title("No Books in Progress");
+        continue_empty.set_description(Some("Start listening to a book to see it here"));
 
-        if let Some(authors) = metadata.and_then(|m| m.authors.as_ref()) {
-            if !authors.is_empty() {
-                let authors_flow = gtk::FlowBox::new();
-                authors_flow.set_selection_mode(gtk::SelectionMode::None);
-                authors_flow.set_column_spacing(6);
-                authors_flow.set_row_spacing(6);
-                authors_flow.set_max_children_per_line(20);
-                authors_flow.set_halign(gtk::Align::Start);
-                for author in authors {
-                    let btn = gtk::Button::with_label(&author.name);
-                    btn.add_css_class("pill");
-                    btn.add_css_class("flat");
-                    btn.add_css_class("author-chip");
-                    btn.set_tooltip_text(Some(&format!("Show books by {}", author.name)));
-                    let name = author.name.clone();
-                    let id = author.id.clone();
-                    let win = self.clone();
-                    btn.connect_clicked(move |_| {
-                        win.show_author_books(id.as_deref(), &name);
-                    });
-                    authors_flow.append(&btn);
-                }
-                info_box.append(&authors_flow);
-            }
-        }
+        continue_stack.add_named(&continue_flowbox, Some("content"));
+        continue_stack.add_named(&continue_empty, Some("empty"));
+        continue_stack.set_visible_child_name("empty");
 
-        if let Some(narrators) = metadata.and_then(|m| m.narrators.as_ref()) {
-            if !narrators.is_empty() {
-                let narrator_label =
-                    gtk::Label::new(Some(&format!("Narrated by: {}", narrators.join(", "))));
-                narrator_label.add_css_class("dim-label");
-                narrator_label.set_halign(gtk::Align::Start);
-                narrator_label.set_wrap(true);
-                info_box.append(&narrator_label);
-            }
-        }
+        continue_clamp.set_child(Some(&continue_stack));
+        continue_scrolled.set_child(Some(&continue_clamp));
 
-        if let Some(duration) = item.media.as_ref().and_then(|m| m.duration) {
-            let hours = (duration / 3600.0) as u32;
-            let mins = ((duration % 3600.0) / 60.0) as u32;
-            let dur_label = gtk::Label::new(Some(&format!("Duration: {} h {} min", hours, mins)));
-            dur_label.add_css_class("dim-label");
-            dur_label.set_halign(gtk::Align::Start);
-            info_box.append(&dur_label);
-        }
+        let continue_page = view_stack.add_titled(&continue_scrolled, Some("continue"), "Continue");
+        continue_page.set_icon_name(Some("media-playback-start-symbolic"));
 
-        if let Some(series) = metadata.and_then(|m| m.series.as_ref()) {
-            for s in series {
-                let mut text = s.name.clone().unwrap_or_default();
-                if let Some(seq) = &s.sequence {
-                    text = format!("{} #{}", text, seq);
-                }
-                if !text.is_empty() {
-                    let series_label = gtk::Label::new(Some(&format!("Series: {}", text)));
-                    series_label.add_css_class("dim-label");
-                    series_label.set_halign(gtk::Align::Start);
-                    info_box.append(&series_label);
-                }
-            }
-        }
+        // Tab 2: All Books
+        let library_scrolled = gtk::ScrolledWindow::new();
+        library_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        library_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
 
-        if let Some(genres) = metadata.and_then(|m| m.genres.as_ref()) {
-            if !genres.is_empty() {
-                let genre_flow = gtk::FlowBox::new();
-                genre_flow.set_selection_mode(gtk::SelectionMode::None);
-                genre_flow.set_max_children_per_line(10);
-                genre_flow.set_column_spacing(4);
-                genre_flow.set_row_spacing(4);
-                for genre in genres {
-                    let badge = gtk::Label::new(Some(genre));
-                    badge.add_css_class("caption");
-                    badge.add_css_class("card");
-                    badge.set_margin_start(4);
-                    badge.set_margin_end(4);
-                    badge.set_margin_top(2);
-                    badge.set_margin_bottom(2);
-                    genre_flow.append(&badge);
-                }
-                info_box.append(&genre_flow);
-            }
-        }
+        let library_clamp = adw::Clamp::new();
+        library_clamp.set_maximum_size(1200);
+        library_clamp.set_margin_top(16);
+        library_clamp.set_margin_bottom(16);
+        library_clamp.set_margin_start(16);
+        library_clamp.set_margin_end(16);
 
-        top_box.append(&info_box);
-        detail_box.append(&top_box);
-        // Apply the current compact state to the freshly built detail widgets.
-        self.set_compact_layout(self.imp().compact_mode.get());
+        let all_books_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
 
-        // Play button (Gelly tarzı dairesel büyük buton)
-        let play_button = gtk::Button::from_icon_name("media-playback-start-symbolic");
-        play_button.add_css_class("suggested-action");
-        play_button.add_css_class("circular");
-        play_button.add_css_class("detail-play-btn");
-        play_button.set_size_request(64, 64);
-        play_button.set_valign(gtk::Align::Center);
-        *imp.detail_play_btn.borrow_mut() = Some(play_button.clone());
+        // Active filters as removable chips, kept outside the stack so they stay
+        // reachable when nothing matches
+        let filter_chips = gtk::FlowBox::new();
+        filter_chips.set_selection_mode(gtk::SelectionMode::None);
+        filter_chips.set_max_children_per_line(20);
+        filter_chips.set_column_spacing(6);
+        filter_chips.set_row_spacing(6);
+        filter_chips.set_halign(gtk::Align::Start);
+        filter_chips.set_visible(false);
+        *self.imp().library_filter_chips.borrow_mut() = Some(filter_chips.clone());
 
-        let item_id = item.id.clone();
+        let flowbox = gtk::FlowBox::new();
+        flowbox.set_valign(gtk::Align::Start);
+        flowbox.set_max_children_per_line(6);
+        flowbox.set_min_children_per_line(1);
+        flowbox.set_column_spacing(16);
+        flowbox.set_row_spacing(16);
+        flowbox.set_homogeneous(true);
+        flowbox.set_selection_mode(gtk::SelectionMode::None);
         let win = self.clone();
-        play_button.connect_clicked(move |_| {
-            let imp = win.imp();
-            let same_item = imp.current_item_id.borrow().as_deref() == Some(item_id.as_str());
-            if same_item && imp.is_playing.get() {
-                win.stop_playback();
-                win.hide_player();
-                win.refresh_detail_play_button();
-            } else {
-                win.start_playback(&item_id);
-            }
-        });
-
-        // Mark as read/unread toggle button
-        let is_finished_init = item
-            .user_media_progress
-            .as_ref()
-            .and_then(|p| p.is_finished)
-            .unwrap_or(false);
-        let saved_progress_time = item
-            .user_media_progress
-            .as_ref()
-            .and_then(|p| p.current_time)
-            .unwrap_or(0.0);
-        imp.detail_is_finished.set(is_finished_init);
+        flowbox.connect_selected_children_changed(move |_| win.update_selection_label());
 
-        let mark_button = gtk::Button::new();
-        mark_button.add_css_class("pill");
-        mark_button.set_valign(gtk::Align::Center);
-        let mark_content = adw::ButtonContent::new();
-        mark_button.set_child(Some(&mark_content));
+        all_books_box.append(&flowbox);
 
-        let mark_state = Rc::new(Cell::new(is_finished_init));
-        let apply_mark_visual = |state: bool, content: &adw::ButtonContent| {
-            if state {
-                content.set_icon_name("edit-undo-symbolic");
-                content.set_label("Mark as Unfinished");
-            } else {
-                content.set_icon_name("object-select-symbolic");
-                content.set_label("Mark as Finished");
-            }
-        };
-        apply_mark_visual(mark_state.get(), &mark_content);
+        let library_stack = gtk::Stack::new();
+        library_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        let library_empty = adw::StatusPage::new();
+        library_empty.set_icon_name(Some("system-search-symbolic"));
+        library_empty.set_title("No Books Found");
+        library_empty.set_description(Some("No books match your search"));
+        library_clamp.set_child(Some(&all_books_box));
+        library_scrolled.set_child(Some(&library_clamp));
 
-        let win_mark = self.clone();
-        let item_id_mark = item.id.clone();
-        let mark_state_cb = mark_state.clone();
-        let mark_button_cb = mark_button.clone();
-        let mark_content_cb = mark_content.clone();
-        mark_button.connect_clicked(move |_| {
-            let new_state = !mark_state_cb.get();
-            mark_button_cb.set_sensitive(false);
-            let (sender, receiver) = async_channel::bounded::<Result<(), String>>(1);
-            let client = win_mark.imp().client.clone();
-            let item_id = item_id_mark.clone();
-            std::thread::spawn(move || {
-                let result = client
-                    .update_progress(&item_id, new_state)
-                    .map_err(|e| e.to_string());
-                let _ = sender.send_blocking(result);
-            });
-            let win_recv = win_mark.clone();
-            let mark_button_recv = mark_button_cb.clone();
-            let mark_content_recv = mark_content_cb.clone();
-            let mark_state_recv = mark_state_cb.clone();
-            glib::spawn_future_local(async move {
-                match receiver.recv().await {
-                    Ok(Ok(())) => {
-                        mark_state_recv.set(new_state);
-                        win_recv.imp().detail_is_finished.set(new_state);
-                        if new_state {
-                            mark_content_recv.set_icon_name("edit-undo-symbolic");
-                            mark_content_recv.set_label("Mark as Unfinished");
-                        } else {
-                            mark_content_recv.set_icon_name("object-select-symbolic");
-                            mark_content_recv.set_label("Mark as Finished");
-                        }
-                        let imp_recv = win_recv.imp();
-                        let detail_is_current = imp_recv.detail_play_item_id.borrow().as_deref()
-                            == imp_recv.current_item_id.borrow().as_deref();
-                        let current = if detail_is_current {
-                            *imp_recv.current_time.borrow()
-                        } else {
-                            saved_progress_time
-                        };
-                        win_recv.apply_chapter_indicators(current);
-                        let toast = adw::Toast::new(if new_state {
-                            "Marked as finished"
-                        } else {
-                            "Marked as unfinished"
-                        });
-                        win_recv.imp().toast_overlay.add_toast(toast);
-                    }
-                    Ok(Err(err)) => {
-                        log::warn!("Update progress error: {}", err);
-                        let toast =
-                            adw::Toast::new(&format!("Failed to update progress: {}", err));
-                        win_recv.imp().toast_overlay.add_toast(toast);
-                    }
-                    Err(err) => {
-                        log::warn!("Update progress channel error: {}", err);
-                        let toast = adw::Toast::new("Failed to update progress");
-                        win_recv.imp().toast_overlay.add_toast(toast);
-                    }
-                }
-                mark_button_recv.set_sensitive(true);
-            });
+        // Cover grid or dense list, as last chosen
+        let library_mode_stack = gtk::Stack::new();
+        library_mode_stack.add_named(&library_scrolled, Some("grid"));
+        library_mode_stack.add_named(&self.build_library_list(), Some("list"));
+        library_mode_stack.set_visible_child_name(match self.imp().library_view.borrow().mode {
+            LibraryViewMode::Grid => "grid",
+            LibraryViewMode::List => "list",
         });
+        *self.imp().library_mode_stack.borrow_mut() = Some(library_mode_stack.clone());
 
-        let actions_row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
-        actions_row.set_halign(gtk::Align::Start);
-        actions_row.append(&play_button);
-        actions_row.append(&mark_button);
-        detail_box.append(&actions_row);
-        self.refresh_detail_play_button();
-
-        // Description
-        if let Some(desc) = metadata.and_then(|m| m.description.as_deref()) {
-            if !desc.is_empty() {
-                let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
-                detail_box.append(&sep);
-
-                let desc_title = gtk::Label::new(Some("Description"));
-                desc_title.add_css_class("title-4");
-                desc_title.set_halign(gtk::Align::Start);
-                detail_box.append(&desc_title);
+        library_stack.add_named(&library_mode_stack, Some("content"));
+        library_stack.add_named(&library_empty, Some("empty"));
+        library_stack.set_visible_child_name("content");
+        library_stack.set_vexpand(true);
 
-                let desc_label = gtk::Label::new(None);
-                let pango_markup = markdown_to_pango(desc);
-                if gtk::pango::parse_markup(&pango_markup, '\0').is_ok() {
-                    desc_label.set_markup(&pango_markup);
-                } else {
-                    log::warn!("Description markup invalid, falling back to plain text");
-                    desc_label.set_label(desc);
-                }
-                desc_label.set_wrap(true);
-                desc_label.set_halign(gtk::Align::Start);
-                desc_label.set_xalign(0.0);
-                desc_label.set_selectable(true);
-                desc_label.add_css_class("body");
-                detail_box.append(&desc_label);
-            }
-        }
+        let chips_clamp = adw::Clamp::new();
+        chips_clamp.set_maximum_size(1200);
+        chips_clamp.set_margin_start(16);
+        chips_clamp.set_margin_end(16);
+        filter_chips.set_margin_top(16);
+        chips_clamp.set_child(Some(&filter_chips));
 
-        // Chapters
-        if let Some(chapters) = item.media.as_ref().and_then(|m| m.chapters.as_ref()) {
-            if !chapters.is_empty() {
-                let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
-                detail_box.append(&sep);
+        let library_column = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        library_column.append(&chips_clamp);
+        library_column.append(&library_stack);
 
-                let ch_title = gtk::Label::new(Some("Chapters"));
-                ch_title.add_css_class("title-4");
-                ch_title.set_halign(gtk::Align::Start);
-                detail_box.append(&ch_title);
+        let library_page = view_stack.add_titled(&library_column, Some("all"), "All Books");
+        library_page.set_icon_name(Some("view-grid-symbolic"));
 
-                // Get current listening position
-                let listen_pos = item
-                    .user_media_progress
-                    .as_ref()
-                    .and_then(|p| p.current_time)
-                    .unwrap_or(0.0);
-                let is_finished = item
-                    .user_media_progress
-                    .as_ref()
-                    .and_then(|p| p.is_finished)
-                    .unwrap_or(false);
+        // Tab 3: Authors
+        let authors_scrolled = gtk::ScrolledWindow::new();
+        authors_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        authors_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+
+        let authors_clamp = adw::Clamp::new();
+        authors_clamp.set_maximum_size(1200);
+        authors_clamp.set_margin_top(16);
+        authors_clamp.set_margin_bottom(16);
+        authors_clamp.set_margin_start(16);
+        authors_clamp.set_margin_end(16);
+
+        let authors_flowbox = gtk::FlowBox::new();
+        authors_flowbox.set_valign(gtk::Align::Start);
+        authors_flowbox.set_max_children_per_line(8);
+        authors_flowbox.set_min_children_per_line(1);
+        authors_flowbox.set_column_spacing(16);
+        authors_flowbox.set_row_spacing(16);
+        authors_flowbox.set_homogeneous(true);
+        authors_flowbox.set_selection_mode(gtk::SelectionMode::None);
+
+        let authors_stack = gtk::Stack::new();
+        authors_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        let authors_empty = adw::StatusPage::new();
+        authors_empty.set_icon_name(Some("system-users-symbolic"));
+        authors_empty.set_title("No Authors");
+        authors_stack.add_named(&authors_flowbox, Some("content"));
+        authors_stack.add_named(&authors_empty, Some("empty"));
+        authors_stack.set_visible_child_name("empty");
+
+        authors_clamp.set_child(Some(&authors_stack));
+        authors_scrolled.set_child(Some(&authors_clamp));
+
+        let authors_page = view_stack.add_titled(&authors_scrolled, Some("authors"), "Authors");
+        authors_page.set_icon_name(Some("system-users-symbolic"));
+
+        *self.imp().authors_flowbox.borrow_mut() = Some(authors_flowbox);
+        *self.imp().authors_stack.borrow_mut() = Some(authors_stack);
+
+        // Tab 4: Bookmarks
+        let bookmarks_scrolled = gtk::ScrolledWindow::new();
+        bookmarks_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        bookmarks_scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
 
-                let chapters_group = adw::PreferencesGroup::new();
-                for (i, chapter) in chapters.iter().enumerate() {
-                    let ch_name = chapter.title.as_deref().unwrap_or("Chapter");
-                    let start = chapter.start.unwrap_or(0.0);
-                    let end = chapter.end.unwrap_or(0.0);
-                    let dur = end - start;
-                    let mins = (dur / 60.0) as u32;
-                    let secs = (dur % 60.0) as u32;
+        let bookmarks_clamp = adw::Clamp::new();
+        bookmarks_clamp.set_maximum_size(1200);
+        bookmarks_clamp.set_margin_top(16);
+        bookmarks_clamp.set_margin_bottom(16);
+        bookmarks_clamp.set_margin_start(16);
+        bookmarks_clamp.set_margin_end(16);
 
-                    let row = adw::ActionRow::new();
-                    row.set_title(ch_name);
-                    row.set_subtitle(&format!("{} min {} sec", mins, secs));
+        let bookmarks_stack_inner = gtk::Stack::new();
+        bookmarks_stack_inner.set_transition_type(gtk::StackTransitionType::Crossfade);
+
+        let bookmarks_empty = adw::StatusPage::new();
+        bookmarks_empty.set_icon_name(Some("user-bookmarks-symbolic"));
+        bookmarks_empty.set_title("No bookmarks yet");
+        bookmarks_empty.set_description(Some(
+            "Use the bookmark button in the player bar to save positions",
+        ));
+
+        let bookmarks_list_box = gtk::Box::new(gtk::Orientation::Vertical, 16);
+
+        bookmarks_stack_inner.add_named(&bookmarks_empty, Some("empty"));
+        bookmarks_stack_inner.add_named(&bookmarks_list_box, Some("content"));
+        bookmarks_stack_inner.set_visible_child_name("empty");
+
+        bookmarks_clamp.set_child(Some(&bookmarks_stack_inner));
+        bookmarks_scrolled.set_child(Some(&bookmarks_clamp));
+
+        let bookmarks_page =
+            view_stack.add_titled(&bookmarks_scrolled, Some("bookmarks"), "Bookmarks");
+        bookmarks_page.set_icon_name(Some("user-bookmarks-symbolic"));
+
+        *self.imp().bookmarks_list_box.borrow_mut() = Some(bookmarks_list_box);
+        *self.imp().bookmarks_tab_empty.borrow_mut() = Some(bookmarks_empty);
+
+        // ViewSwitcher in the header
+        let switcher = adw::ViewSwitcher::new();
+        switcher.set_stack(Some(&view_stack));
+        switcher.set_policy(adw::ViewSwitcherPolicy::Wide);
+        header.set_title_widget(Some(&switcher));
+
+        // Modern GTK4/adwaita pattern: show bottom switcher on narrow widths
+        let switcher_bar = adw::ViewSwitcherBar::new();
+        switcher_bar.set_stack(Some(&view_stack));
+        switcher_bar.set_reveal(false);
+
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.add_top_bar(&search_bar);
+        toolbar_view.add_bottom_bar(&switcher_bar);
+
+        // Bulk actions for the selected books, shown in selection mode
+        let selection_bar = gtk::ActionBar::new();
+        selection_bar.set_revealed(false);
+        let selection_label = gtk::Label::new(Some("No books selected"));
+        selection_label.add_css_class("dim-label");
+        let select_all_btn = gtk::Button::with_label("Select All");
+        let win = self.clone();
+        select_all_btn.connect_clicked(move |_| win.select_all_items());
+        selection_bar.pack_start(&select_all_btn);
+        selection_bar.pack_start(&selection_label);
+
+        let bulk_menu = gio::Menu::new();
+        let progress_section = gio::Menu::new();
+        progress_section.append(Some("Mark as Finished"), Some("win.bulk-mark-finished"));
+        progress_section.append(
+            Some("Mark as Not Finished"),
+            Some("win.bulk-mark-unfinished"),
+        );
+        progress_section.append(Some("Reset Progress…"), Some("win.bulk-reset-progress"));
+        progress_section.append(
+            Some("Remove from Continue Listening"),
+            Some("win.bulk-remove-from-continue"),
+        );
+        bulk_menu.append_section(None, &progress_section);
+        let lists_section = gio::Menu::new();
+        lists_section.append(Some("Add to Queue"), Some("win.bulk-add-to-queue"));
+        lists_section.append(
+            Some("Add to Collection or Playlist…"),
+            Some("win.bulk-add-to-collection"),
+        );
+        let download_item = gio::MenuItem::new(Some("Download"), Some("win.bulk-download"));
+        download_item.set_attribute_value("hidden-when", Some(&"action-disabled".to_variant()));
+        lists_section.append_item(&download_item);
+        bulk_menu.append_section(None, &lists_section);
+
+        let bulk_btn = gtk::MenuButton::new();
+        bulk_btn.set_label("Actions");
+        bulk_btn.set_menu_model(Some(&bulk_menu));
+        bulk_btn.add_css_class("suggested-action");
+        selection_bar.pack_end(&bulk_btn);
+        let done_btn = gtk::Button::with_label("Done");
+        let win = self.clone();
+        done_btn.connect_clicked(move |_| win.set_selection_mode(false));
+        selection_bar.pack_end(&done_btn);
+        toolbar_view.add_bottom_bar(&selection_bar);
+        *self.imp().selection_bar.borrow_mut() = Some(selection_bar);
+        *self.imp().selection_label.borrow_mut() = Some(selection_label);
+
+        // The selection belongs to the tab it was made in
+        let win = self.clone();
+        view_stack.connect_visible_child_name_notify(move |_| {
+            if let Some(flowbox) = win.imp().library_flowbox.borrow().as_ref() {
+                flowbox.unselect_all();
+            }
+            if let Some(flowbox) = win.imp().continue_flowbox.borrow().as_ref() {
+                flowbox.unselect_all();
+            }
+            if let Some(selection) = win.library_list_selection() {
+                selection.unselect_all();
+            }
+            win.update_selection_label();
+        });
+
+        // Skeleton loading: placeholder cards while the library loads.
+        let skeleton_scrolled = gtk::ScrolledWindow::new();
+        skeleton_scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        let skeleton_clamp = adw::Clamp::new();
+        skeleton_clamp.set_maximum_size(1200);
+        skeleton_clamp.set_margin_top(16);
+        skeleton_clamp.set_margin_bottom(16);
+        skeleton_clamp.set_margin_start(16);
+        skeleton_clamp.set_margin_end(16);
+        let skeleton_flow = gtk::FlowBox::new();
+        skeleton_flow.set_valign(gtk::Align::Start);
+        skeleton_flow.set_max_children_per_line(6);
+        skeleton_flow.set_min_children_per_line(1);
+        skeleton_flow.set_column_spacing(16);
+        skeleton_flow.set_row_spacing(16);
+        skeleton_flow.set_homogeneous(true);
+        skeleton_flow.set_selection_mode(gtk::SelectionMode::None);
+        for _ in 0..12 {
+            let card = gtk::Box::new(gtk::Orientation::Vertical, 8);
+            card.set_width_request(160);
+            let cover = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            cover.set_size_request(160, 160);
+            cover.add_css_class("skeleton");
+            cover.add_css_class("skeleton-cover");
+            let line = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            line.set_size_request(120, 14);
+            line.add_css_class("skeleton");
+            line.add_css_class("skeleton-line");
+            card.append(&cover);
+            card.append(&line);
+            skeleton_flow.append(&card);
+        }
+        skeleton_clamp.set_child(Some(&skeleton_flow));
+        skeleton_scrolled.set_child(Some(&skeleton_clamp));
+
+        let content_stack = gtk::Stack::new();
+        content_stack.add_named(&skeleton_scrolled, Some("loading"));
+        content_stack.add_named(&view_stack, Some("content"));
+        content_stack.set_visible_child_name("loading");
+
+        toolbar_view.set_content(Some(&content_stack));
+
+        // Dropping files or folders onto the library starts an upload.
+        let drop_target = gtk::DropTarget::new(
+            gtk::gdk::FileList::static_type(),
+            gtk::gdk::DragAction::COPY,
+        );
+        let win = self.clone();
+        drop_target.connect_accept(move |_, _| win.current_user_can(User::can_upload));
+        let win = self.clone();
+        drop_target.connect_drop(move |_, value, _, _| {
+            let Ok(list) = value.get::<gtk::gdk::FileList>() else {
+                return false;
+            };
+            win.show_upload_dialog(list.files().iter().filter_map(|f| f.path()).collect());
+            true
+        });
+        toolbar_view.add_controller(drop_target);
+
+        *self.imp().library_flowbox.borrow_mut() = Some(flowbox);
+        *self.imp().library_content_stack.borrow_mut() = Some(content_stack);
+        *self.imp().continue_flowbox.borrow_mut() = Some(continue_flowbox);
+        *self.imp().continue_stack.borrow_mut() = Some(continue_stack);
+        *self.imp().library_stack.borrow_mut() = Some(library_stack);
+        *self.imp().library_switcher_bar.borrow_mut() = Some(switcher_bar);
+        *self.imp().library_header_switcher.borrow_mut() = Some(switcher);
+
+        toolbar_view.upcast()
+    }
+
+    fn load_library(&self) {
+        let imp = self.imp();
+        let client = imp.client.clone();
+        let library_id = imp.library_id.borrow().clone();
+        let win = self.clone();
+
+        if !library_id.is_empty() {
+            *imp.library_filter.borrow_mut() = Self::read_library_filter(&library_id);
+            self.load_library_sorts(&library_id);
+        }
+        let server_filter = imp.library_filter.borrow().server_filter();
+        self.render_filter_chips();
+        if imp.offline_mode.get() {
+            self.load_cached_library();
+            return;
+        }
+        self.set_library_loading(true);
+
+        let user = imp.current_user.borrow().clone();
+
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let lib_id = library_id.clone();
+
+            std::thread::spawn(move || {
+                let result = if lib_id.is_empty() {
+                    match client.get_libraries() {
+                        Ok(libs) => {
+                            cache::save_libraries(&libs);
+                            let accessible = libs.iter().find(|l| {
+                                user.as_ref().is_none_or(|u| u.can_access_library(&l.id))
+                            });
+                            if let Some(lib) = accessible {
+                                let first_id = lib.id.clone();
+                                match client.get_library_items(&first_id) {
+                                    Ok(items) => Ok((first_id, items, None)),
+                                    Err(e) => Err(e),
+                                }
+                            } else {
+                                Ok((String::new(), vec![], None))
+                            }
+                        }
+                        Err(e) => Err(e),
+                    }
+                } else if let Some(filter) = server_filter.as_deref() {
+                    // Only the filtered items are shown, but the rest of the
+                    // window keeps the whole library from the last full load.
+                    // Without one it is downloaded after the filtered view.
+                    client
+                        .get_library_items_filtered(&lib_id, Some(filter))
+                        .map(|filtered| {
+                            let items = cache::load_library_items(&lib_id).unwrap_or_default();
+                            (lib_id, items, Some(filtered))
+                        })
+                } else {
+                    match client.get_library_items(&lib_id) {
+                        Ok(items) => Ok((lib_id, items, None)),
+                        Err(e) => Err(e),
+                    }
+                };
+                if let Ok((id, items, None)) = &result {
+                    cache::save_library_items(id, items);
+                }
+                let _ = tx.send_blocking(result);
+            });
+
+            match rx.recv().await {
+                Ok(Ok((lib_id, items, filtered))) => {
+                    log::info!("Library loaded: {} books", items.len());
+                    if library_id.is_empty() {
+                        *win.imp().library_filter.borrow_mut() = Self::read_library_filter(&lib_id);
+                        win.load_library_sorts(&lib_id);
+                        win.render_filter_chips();
+                    }
+                    *win.imp().library_id.borrow_mut() = lib_id;
+                    if win.imp().client.is_authenticated() {
+                        win.save_credentials();
+                    }
+                    let fill = filtered.is_some() && items.is_empty();
+                    win.populate_library(&items, filtered);
+                    win.set_library_loading(false);
+                    if fill {
+                        win.fill_library_items();
+                    }
+                }
+                Ok(Err(e)) => {
+                    log::error!("Failed to load library: {}", e);
+                    win.set_library_loading(false);
+                    win.show_library_error(&format!("Failed to load library: {}", e));
+                    win.report_api_error(&e);
+                }
+                Err(_) => {
+                    log::error!("Channel error while loading library");
+                    win.set_library_loading(false);
+                    win.show_library_error("Connection error");
+                }
+            }
+        });
+    }
+
+    /// Shows the library as it was last loaded, for starting offline.
+    fn load_cached_library(&self) {
+        let imp = self.imp();
+        let mut library_id = imp.library_id.borrow().clone();
+        if library_id.is_empty() {
+            library_id = cache::load_libraries()
+                .and_then(|libraries| libraries.into_iter().next())
+                .map(|library| library.id)
+                .unwrap_or_default();
+        }
+        let Some(items) = cache::load_library_items(&library_id) else {
+            self.set_library_loading(false);
+            self.show_library_error("Can't reach the server. The library loads once it's back.");
+            return;
+        };
+        log::info!("Showing {} cached books while offline", items.len());
+        *imp.library_id.borrow_mut() = library_id;
+        self.populate_library(&items, None);
+        self.set_library_loading(false);
+    }
+
+    fn set_library_loading(&self, loading: bool) {
+        let imp = self.imp();
+        if let Some(ref cs) = *imp.library_content_stack.borrow() {
+            cs.set_visible_child_name(if loading { "loading" } else { "content" });
+        }
+    }
+
+    /// `filtered` is what the server returned for the active filters, shown
+    /// in place of `items`, which stay the whole library.
+    fn populate_library(&self, items: &[LibraryItem], filtered: Option<Vec<LibraryItem>>) {
+        // Only full loads refresh the search index; filtered ones reuse the
+        // library from the last full load.
+        self.store_library_items(items, filtered.is_none());
+        *self.imp().library_filtered_items.borrow_mut() = filtered;
+
+        self.render_library();
+
+        // Fetch "Continue" items from the server
+        self.load_continue_listening();
+
+        // Fetch all bookmarks for the Bookmarks tab
+        self.load_all_bookmarks();
+
+        // Fetch the authors for the Authors tab
+        self.load_library_authors();
+    }
+
+    /// Keeps the whole library for the parts of the window that don't
+    /// follow the filters
+    fn store_library_items(&self, items: &[LibraryItem], full_load: bool) {
+        let mut seen = HashSet::new();
+        let deduped: Vec<LibraryItem> = items
+            .iter()
+            .filter(|item| seen.insert(item.id.clone()))
+            .cloned()
+            .collect();
+        if full_load {
+            search_provider::write_index(&self.imp().library_id.borrow(), &deduped);
+        }
+        *self.imp().library_items.borrow_mut() = deduped;
+    }
+
+    /// Downloads the whole library behind a filtered view that was shown
+    /// before any full load of it, without holding up the view
+    fn fill_library_items(&self) {
+        let library_id = self.imp().library_id.borrow().clone();
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let lib_id = library_id.clone();
+            std::thread::spawn(move || {
+                let result = client.get_library_items(&lib_id);
+                if let Ok(items) = &result {
+                    cache::save_library_items(&lib_id, items);
+                }
+                let _ = tx.send_blocking(result);
+            });
+            match rx.recv().await {
+                Ok(Ok(items)) => {
+                    if *win.imp().library_id.borrow() == library_id {
+                        win.store_library_items(&items, true);
+                    }
+                }
+                Ok(Err(e)) => log::warn!("Failed to load the whole library: {}", e),
+                Err(_) => {}
+            }
+        });
+    }
+
+    fn render_library(&self) {
+        let imp = self.imp();
+        let flowbox = imp.library_flowbox.borrow();
+        let flowbox = flowbox.as_ref().unwrap();
+
+        while let Some(child) = flowbox.first_child() {
+            flowbox.remove(&child);
+        }
+
+        let mut items = match imp.library_filtered_items.borrow().as_ref() {
+            Some(filtered) => filtered.clone(),
+            None => imp.library_items.borrow().clone(),
+        };
+        let sort = imp.library_sort.get();
+        items.sort_by(|a, b| Self::compare_items(a, b, sort));
+
+        let query = imp.library_search_query.borrow().trim().to_lowercase();
+        if !query.is_empty() {
+            items.retain(|item| {
+                let title = Self::item_title_for_sort(item).to_lowercase();
+                let author = Self::item_author_for_sort(item).to_lowercase();
+                title.contains(&query) || author.contains(&query)
+            });
+        }
+
+        let filter = imp.library_filter.borrow().clone();
+        if !filter.is_empty() {
+            items.retain(|item| filter.matches(item));
+        }
+
+        // Only the visible view is filled; switching views renders again.
+        match imp.library_view.borrow().mode {
+            LibraryViewMode::Grid => {
+                for item in &items {
+                    let card = self.create_book_card(item);
+                    flowbox.append(&card);
+                }
+                *imp.library_rendered.borrow_mut() = items.clone();
+            }
+            LibraryViewMode::List => {
+                if let Some(store) = imp.library_list_store.borrow().as_ref() {
+                    let rows: Vec<glib::BoxedAnyObject> = items
+                        .iter()
+                        .map(|item| glib::BoxedAnyObject::new(item.clone()))
+                        .collect();
+                    store.splice(0, store.n_items(), &rows);
+                }
+            }
+        }
+
+        // Show empty state when there are no matching books.
+        if let Some(stack) = imp.library_stack.borrow().as_ref() {
+            let has_query = !imp.library_search_query.borrow().trim().is_empty();
+            if let Some(empty) = stack
+                .child_by_name("empty")
+                .and_downcast::<adw::StatusPage>()
+            {
+                if has_query {
+                    empty.set_title("No Results");
+                    empty.set_description(Some("No books match your search"));
+                } else if !filter.is_empty() {
+                    empty.set_title("No Results");
+                    empty.set_description(Some("No books match the selected filters"));
+                } else {
+                    empty.set_title("No Books");
+                    empty.set_description(Some("This library has no books yet"));
+                }
+            }
+            stack.set_visible_child_name(if items.is_empty() { "empty" } else { "content" });
+        }
+    }
+
+    fn item_title_for_sort(item: &LibraryItem) -> &str {
+        item.media
+            .as_ref()
+            .and_then(|m| m.metadata.as_ref())
+            .and_then(|md| md.title.as_deref())
+            .unwrap_or("")
+    }
+
+    fn item_author_for_sort(item: &LibraryItem) -> &str {
+        item.media
+            .as_ref()
+            .and_then(|m| m.metadata.as_ref())
+            .and_then(|md| md.author_name_lf.as_deref().or(md.author_name.as_deref()))
+            .unwrap_or("")
+    }
+
+    fn item_last_played_timestamp(item: &LibraryItem) -> u64 {
+        item.user_media_progress
+            .as_ref()
+            .and_then(|p| p.last_update)
+            .unwrap_or(0)
+    }
+
+    fn item_added_timestamp(item: &LibraryItem) -> u64 {
+        item.extra
+            .as_ref()
+            .and_then(|v| v.get("addedAt").and_then(|x| x.as_u64()))
+            .or_else(|| {
+                item.extra
+                    .as_ref()
+                    .and_then(|v| v.get("createdAt").and_then(|x| x.as_u64()))
+            })
+            .or_else(|| {
+                item.extra
+                    .as_ref()
+                    .and_then(|v| v.get("updatedAt").and_then(|x| x.as_u64()))
+            })
+            .unwrap_or(0)
+    }
+
+    fn load_continue_listening(&self) {
+        let imp = self.imp();
+        if imp.offline_mode.get() {
+            *imp.continue_items.borrow_mut() = cache::load_continue_listening().unwrap_or_default();
+            self.render_continue_listening();
+            self.flush_remote_commands();
+            return;
+        }
+        let client = imp.client.clone();
+        let win = self.clone();
+
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let client_worker = client.clone();
+            std::thread::spawn(move || {
+                // Fetch items-in-progress and the user's media progress array together.
+                let items = client_worker.get_items_in_progress();
+                let me = client_worker.get_me();
+                let _ = tx.send_blocking((items, me));
+            });
+
+            match rx.recv().await {
+                Ok((Ok(items), me_result)) => {
+                    log::info!("Continue listening items: {}", items.len());
+
+                    // Build lookup map from MediaProgress by libraryItemId
+                    let progress_map: HashMap<String, MediaProgress> = match me_result {
+                        Ok(user) => user
+                            .media_progress
+                            .unwrap_or_default()
+                            .into_iter()
+                            .filter_map(|p| p.library_item_id.clone().map(|id| (id, p)))
+                            .collect(),
+                        Err(e) => {
+                            log::warn!(
+                                "Failed to fetch user media progress for continue listening: {}",
+                                e
+                            );
+                            HashMap::new()
+                        }
+                    };
+
+                    let mut seen = HashSet::new();
+                    let deduped: Vec<LibraryItem> = items
+                        .into_iter()
+                        .filter(|item| seen.insert(item.id.clone()))
+                        .map(|mut item| {
+                            if item.user_media_progress.is_none() {
+                                if let Some(p) = progress_map.get(&item.id) {
+                                    item.user_media_progress = Some(p.clone());
+                                }
+                            }
+                            item
+                        })
+                        .collect();
+                    cache::save_continue_listening(&deduped);
+                    *win.imp().continue_items.borrow_mut() = deduped;
+                    win.render_continue_listening();
+                    win.flush_remote_commands();
+                }
+                Ok((Err(e), _)) => {
+                    log::warn!("Failed to load continue listening books: {}", e);
+                    win.flush_remote_commands();
+                }
+                Err(_) => {
+                    log::warn!("Channel error while loading continue listening books");
+                }
+            }
+        });
+    }
+
+    fn render_continue_listening(&self) {
+        let imp = self.imp();
+        let continue_flowbox = imp.continue_flowbox.borrow();
+        let continue_flowbox = continue_flowbox.as_ref().unwrap();
+        while let Some(child) = continue_flowbox.first_child() {
+            continue_flowbox.remove(&child);
+        }
+
+        let mut items = imp.continue_items.borrow().clone();
+        let sort = imp.continue_sort.get();
+        items.sort_by(|a, b| Self::compare_items(a, b, sort));
+
+        let query = imp.library_search_query.borrow().trim().to_lowercase();
+        if !query.is_empty() {
+            items.retain(|item| {
+                let title = Self::item_title_for_sort(item).to_lowercase();
+                let author = Self::item_author_for_sort(item).to_lowercase();
+                title.contains(&query) || author.contains(&query)
+            });
+        }
+
+        for item in &items {
+            let card = self.create_book_card(item);
+            continue_flowbox.append(&card);
+        }
+        *imp.continue_rendered.borrow_mut() = items.clone();
+
+        if let Some(stack) = imp.continue_stack.borrow().as_ref() {
+            stack.set_visible_child_name(if items.is_empty() { "empty" } else { "content" });
+        }
+    }
+
+    fn create_book_card(&self, item: &LibraryItem) -> gtk::Widget {
+        let card_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        card_box.add_css_class("book-card");
+        card_box.set_width_request(160);
+        card_box.set_halign(gtk::Align::Center);
+
+        let cover_frame = gtk::Frame::new(None);
+        cover_frame.set_halign(gtk::Align::Center);
+        cover_frame.add_css_class("card");
+        cover_frame.add_css_class("book-cover-frame");
+        // Clip children (cover image + progress overlay) to the rounded corners.
+        cover_frame.set_overflow(gtk::Overflow::Hidden);
+
+        let cover_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let cover_image = gtk::Image::from_icon_name("audio-x-generic-symbolic");
+        cover_image.set_pixel_size(160);
+        cover_image.set_size_request(160, 160);
+        cover_image.add_css_class("dim-label");
+        cover_box.append(&cover_image);
+
+        let progress_val = item
+            .user_media_progress
+            .as_ref()
+            .and_then(|p| p.progress)
+            .unwrap_or(0.0);
+        let is_finished = item
+            .user_media_progress
+            .as_ref()
+            .and_then(|p| p.is_finished)
+            .unwrap_or(false);
+
+        // Gelly tarzı: overlay üzerinde hover'da beliren dairesel play butonu
+        let cover_overlay = gtk::Overlay::new();
+        cover_overlay.set_child(Some(&cover_box));
+
+        // Progress overlay at bottom of cover — a DrawingArea only repaints on
+        // resize/invalidation, unlike a tick callback which would run every
+        // frame forever for every card.
+        if progress_val > 0.0 || is_finished {
+            let frac = if is_finished {
+                1.0
+            } else {
+                progress_val.clamp(0.0, 1.0)
+            };
+            let progress = gtk::DrawingArea::new();
+            progress.set_height_request(8);
+            progress.set_hexpand(true);
+            progress.set_valign(gtk::Align::End);
+            progress.set_halign(gtk::Align::Fill);
+            progress.set_draw_func(move |_, cr, width, height| {
+                let w = width as f64;
+                let h = height as f64;
+                // Translucent track for contrast over any cover
+                cr.set_source_rgba(0.0, 0.0, 0.0, 0.55);
+                cr.rectangle(0.0, 0.0, w, h);
+                let _ = cr.fill();
+                // Accent fill
+                cr.set_source_rgba(0.21, 0.56, 1.0, 1.0);
+                cr.rectangle(0.0, 0.0, w * frac, h);
+                let _ = cr.fill();
+            });
+            cover_overlay.add_overlay(&progress);
+
+            // Small badge at top-right: checkmark when finished, else percent.
+            let badge = gtk::Label::new(None);
+            badge.add_css_class("cover-badge");
+            badge.set_halign(gtk::Align::End);
+            badge.set_valign(gtk::Align::Start);
+            badge.set_margin_top(6);
+            badge.set_margin_end(6);
+            if is_finished {
+                badge.set_text("\u{2713}"); // ✓
+                badge.add_css_class("cover-badge-done");
+            } else {
+                badge.set_text(&format!("{}%", (frac * 100.0).round() as i32));
+            }
+            cover_overlay.add_overlay(&badge);
+        }
+
+        let hover_play = gtk::Button::from_icon_name("media-playback-start-symbolic");
+        hover_play.add_css_class("circular");
+        hover_play.add_css_class("suggested-action");
+        hover_play.add_css_class("cover-play-btn");
+        hover_play.set_halign(gtk::Align::Center);
+        hover_play.set_valign(gtk::Align::Center);
+        hover_play.set_opacity(0.0);
+        hover_play.set_size_request(48, 48);
+        cover_overlay.add_overlay(&hover_play);
+
+        let motion = gtk::EventControllerMotion::new();
+        let btn = hover_play.clone();
+        motion.connect_enter(move |_, _, _| btn.set_opacity(1.0));
+        let btn = hover_play.clone();
+        motion.connect_leave(move |_| btn.set_opacity(0.0));
+        cover_overlay.add_controller(motion);
+
+        let item_id_hover = item.id.clone();
+        let win_hover = self.clone();
+        hover_play.connect_clicked(move |_| {
+            win_hover.start_playback(&item_id_hover);
+        });
+
+        cover_frame.set_child(Some(&cover_overlay));
+        card_box.append(&cover_frame);
+
+        let title = item
+            .media
+            .as_ref()
+            .and_then(|m| m.metadata.as_ref())
+            .and_then(|md| md.title.as_deref())
+            .unwrap_or("Unknown Book");
+
+        let title_label = gtk::Label::new(Some(title));
+        title_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        title_label.set_max_width_chars(20);
+        title_label.set_lines(2);
+        title_label.set_wrap(true);
+        title_label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        title_label.add_css_class("heading");
+        title_label.set_halign(gtk::Align::Start);
+        card_box.append(&title_label);
+
+        let author = item
+            .media
+            .as_ref()
+            .and_then(|m| m.metadata.as_ref())
+            .and_then(|md| md.author_name.as_deref())
+            .unwrap_or("Unknown Author");
+
+        let author_label = gtk::Label::new(Some(author));
+        author_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        author_label.set_max_width_chars(20);
+        author_label.add_css_class("dim-label");
+        author_label.add_css_class("caption");
+        author_label.set_halign(gtk::Align::Start);
+        card_box.append(&author_label);
+
+        if let Some(duration) = item.media.as_ref().and_then(|m| m.duration) {
+            let hours = (duration / 3600.0) as u32;
+            let mins = ((duration % 3600.0) / 60.0) as u32;
+            let dur_text = if hours > 0 {
+                format!("{} h {} min", hours, mins)
+            } else {
+                format!("{} min", mins)
+            };
+            let dur_label = gtk::Label::new(Some(&dur_text));
+            dur_label.add_css_class("dim-label");
+            dur_label.add_css_class("caption");
+            dur_label.set_halign(gtk::Align::Start);
+            card_box.append(&dur_label);
+        }
+
+        let gesture = gtk::GestureClick::new();
+        let item_id = item.id.clone();
+        let win = self.clone();
+        gesture.connect_released(move |gesture, _, _, _| {
+            // In selection mode the click selects the card instead
+            let selecting = gesture
+                .widget()
+                .and_then(|w| w.ancestor(gtk::FlowBox::static_type()))
+                .and_downcast::<gtk::FlowBox>()
+                .is_some_and(|f| f.selection_mode() == gtk::SelectionMode::Multiple);
+            if !selecting {
+                win.open_audiobook_detail(&item_id);
+            }
+        });
+        card_box.add_controller(gesture);
+        card_box.set_cursor_from_name(Some("pointer"));
+
+        let context_click = gtk::GestureClick::new();
+        context_click.set_button(gtk::gdk::BUTTON_SECONDARY);
+        let item_id = item.id.clone();
+        let win = self.clone();
+        context_click.connect_pressed(move |gesture, _, x, y| {
+            let Some(card) = gesture.widget() else {
+                return;
+            };
+            gesture.set_state(gtk::EventSequenceState::Claimed);
+            win.popup_item_menu(&card, &item_id, x, y);
+        });
+        card_box.add_controller(context_click);
+
+        let long_press = gtk::GestureLongPress::new();
+        long_press.set_touch_only(true);
+        let item_id = item.id.clone();
+        let win = self.clone();
+        long_press.connect_pressed(move |gesture, x, y| {
+            let Some(card) = gesture.widget() else {
+                return;
+            };
+            gesture.set_state(gtk::EventSequenceState::Claimed);
+            win.popup_item_menu(&card, &item_id, x, y);
+        });
+        card_box.add_controller(long_press);
+
+        // Keyboard focus sits on the flowbox child wrapping the card, so the
+        // menu keys go there once the card has been added.
+        let item_id = item.id.clone();
+        let win = self.clone();
+        card_box.connect_parent_notify(move |card| {
+            let Some(parent) = card.parent() else {
+                return;
+            };
+            let card = card.clone();
+            let item_id = item_id.clone();
+            let win = win.clone();
+            let action = gtk::CallbackAction::new(move |_, _| {
+                let x = card.width() as f64 / 2.0;
+                let y = card.height() as f64 / 2.0;
+                win.popup_item_menu(card.upcast_ref(), &item_id, x, y);
+                glib::Propagation::Stop
+            });
+            let controller = gtk::ShortcutController::new();
+            controller.add_shortcut(gtk::Shortcut::new(
+                gtk::ShortcutTrigger::parse_string("Menu|<Shift>F10"),
+                Some(action),
+            ));
+            parent.add_controller(controller);
+        });
+
+        // Load cover; off an unmetered connection only cached ones show
+        let client = self.imp().client.clone();
+        let item_id = item.id.clone();
+        let img = cover_image.clone();
+        let fetch = self.connectivity() == Connectivity::Online && !self.imp().offline_mode.get();
+
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let id = item_id.clone();
+            std::thread::spawn(move || {
+                let result = if fetch {
+                    client.download_cover(&id)
+                } else {
+                    cache::load_cover(&id).ok_or(ApiError::Cancelled)
+                };
+                if let (true, Ok(bytes)) = (fetch, &result) {
+                    cache::save_cover(&id, bytes);
+                }
+                let _ = tx.send_blocking(result);
+            });
+            if let Ok(Ok(bytes)) = rx.recv().await {
+                let gbytes = glib::Bytes::from(&bytes);
+                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
+                if let Ok(pixbuf) =
+                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
+                {
+                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
+                    img.set_paintable(Some(&texture));
+                    img.set_size_request(160, -1);
+                    img.remove_css_class("dim-label");
+                }
+            }
+        });
+
+        // Offline, only downloaded books can play
+        if self.imp().offline_mode.get() && !downloads::is_downloaded(&item.id) {
+            card_box.set_sensitive(false);
+            card_box.set_tooltip_text(Some("Not downloaded, unavailable offline"));
+        }
+
+        card_box.upcast()
+    }
+
+    // ─── ITEM ACTIONS ──────────────────────────────────────────────────────
+
+    /// Per-item actions, exported by the application as `app.<name>` with the
+    /// library item id as parameter.
+    pub const ITEM_ACTIONS: [&'static str; 15] = [
+        "play-item",
+        "play-item-from-start",
+        "queue-item",
+        "mark-item-finished",
+        "mark-item-unfinished",
+        "set-progress-position",
+        "reset-progress",
+        "remove-from-continue",
+        "add-item-to-collection",
+        "download-item",
+        "go-to-author",
+        "go-to-series",
+        "copy-item-link",
+        "copy-item-link-at-position",
+        "show-item-info",
+    ];
+
+    pub fn activate_item_action(&self, action: &str, item_id: &str) {
+        match action {
+            "play-item" => self.start_playback(item_id),
+            "play-item-from-start" => self.start_playback_at(item_id, 0.0),
+            "queue-item" => self.enqueue_items(vec![item_id.to_string()]),
+            "mark-item-finished" => self.mark_item_finished(item_id, true),
+            "mark-item-unfinished" => self.mark_item_finished(item_id, false),
+            "set-progress-position" => self.prompt_progress_position(item_id),
+            "reset-progress" => self.reset_item_progress(item_id),
+            "remove-from-continue" => self.remove_item_from_continue(item_id),
+            "add-item-to-collection" => self.add_to_collection(vec![item_id.to_string()]),
+            "download-item" => {
+                if let Some(item) = self.cached_item(item_id) {
+                    self.download_items(vec![item]);
+                }
+            }
+            "go-to-author" => self.go_to_item_author(item_id),
+            "go-to-series" => self.go_to_item_series(item_id),
+            "copy-item-link" => self.copy_item_link(item_id),
+            "copy-item-link-at-position" => self.copy_item_link_at_position(item_id),
+            "show-item-info" => self.show_item_info(item_id),
+            _ => log::warn!("Unknown item action {}", action),
+        }
+    }
+
+    /// Opens the item menu at a point of `widget`; the popover goes away with
+    /// the menu.
+    fn popup_item_menu(&self, widget: &gtk::Widget, item_id: &str, x: f64, y: f64) {
+        let popover = gtk::PopoverMenu::from_model(Some(&self.item_context_menu(item_id)));
+        popover.set_parent(widget);
+        popover.set_has_arrow(false);
+        popover.set_halign(gtk::Align::Start);
+        popover.set_pointing_to(Some(&gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+        popover.connect_closed(|popover| {
+            // Unparent after the activated action ran
+            let popover = popover.clone();
+            glib::idle_add_local_once(move || popover.unparent());
+        });
+        popover.popup();
+    }
+
+    /// Per-item actions shared by the card context menu and the detail page.
+    /// Entries that can't apply to the item are left out.
+    fn item_context_menu(&self, item_id: &str) -> gio::Menu {
+        let item = self.cached_item(item_id);
+        let metadata = item
+            .as_ref()
+            .and_then(|i| i.media.as_ref())
+            .and_then(|m| m.metadata.as_ref());
+        let finished = item.as_ref().is_some_and(Self::item_is_finished);
+        let has_author = metadata
+            .and_then(|m| m.author_name.as_deref())
+            .is_some_and(|a| !a.is_empty());
+        let has_series = item
+            .as_ref()
+            .is_some_and(|i| !BrowseKind::Series.values(i).is_empty());
+
+        let section = |entries: &[(&str, &str)]| {
+            let section = gio::Menu::new();
+            for (label, action) in entries {
+                let entry = gio::MenuItem::new(Some(label), None);
+                entry.set_action_and_target_value(
+                    Some(&format!("app.{}", action)),
+                    Some(&item_id.to_variant()),
+                );
+                section.append_item(&entry);
+            }
+            section
+        };
+
+        let menu = gio::Menu::new();
+        menu.append_section(
+            None,
+            &section(&[
+                ("Play", "play-item"),
+                ("Play from Beginning", "play-item-from-start"),
+                ("Add to Queue", "queue-item"),
+            ]),
+        );
+        menu.append_section(
+            None,
+            &section(&[
+                if finished {
+                    ("Mark as Not Finished", "mark-item-unfinished")
+                } else {
+                    ("Mark as Finished", "mark-item-finished")
+                },
+                ("Set Position…", "set-progress-position"),
+                ("Reset Progress", "reset-progress"),
+                ("Remove from Continue Listening", "remove-from-continue"),
+            ]),
+        );
+        let mut lists = vec![("Add to Collection…", "add-item-to-collection")];
+        if item.is_some() && self.current_user_can(User::can_download) {
+            lists.push(("Download", "download-item"));
+        }
+        menu.append_section(None, &section(&lists));
+        let mut navigation = Vec::new();
+        if has_author {
+            navigation.push(("Go to Author", "go-to-author"));
+        }
+        if has_series {
+            navigation.push(("Go to Series", "go-to-series"));
+        }
+        navigation.push(("Copy Link", "copy-item-link"));
+        navigation.push((
+            "Copy Link at Current Position",
+            "copy-item-link-at-position",
+        ));
+        navigation.push(("Show Info", "show-item-info"));
+        menu.append_section(None, &section(&navigation));
+        menu
+    }
+
+    /// The item from the loaded library or Continue Listening shelf.
+    fn cached_item(&self, item_id: &str) -> Option<LibraryItem> {
+        let imp = self.imp();
+        let library = imp.library_items.borrow();
+        let continue_items = imp.continue_items.borrow();
+        library
+            .iter()
+            .chain(continue_items.iter())
+            .find(|item| item.id == item_id)
+            .cloned()
+    }
+
+    fn mark_item_finished(&self, item_id: &str, finished: bool) {
+        let item_id = item_id.to_string();
+        self.with_item_progress(&item_id.clone(), move |win, progress, _, title| {
+            let message = if finished {
+                format!("Marked “{}” as finished", title)
+            } else {
+                format!("Marked “{}” as not finished", title)
+            };
+            let id = item_id.clone();
+            win.apply_progress_change(&item_id, message, progress, move |client| {
+                client.update_progress(&id, finished)
+            });
+        });
+    }
+
+    fn go_to_item_author(&self, item_id: &str) {
+        let Some(name) = self
+            .cached_item(item_id)
+            .and_then(|item| item.media)
+            .and_then(|m| m.metadata)
+            .and_then(|m| m.author_name)
+            .and_then(|names| split_list(&names).into_iter().next())
+        else {
+            return;
+        };
+        let author_id = self
+            .imp()
+            .library_authors
+            .borrow()
+            .iter()
+            .find(|a| a.name.as_deref() == Some(name.as_str()))
+            .and_then(|a| a.id.clone());
+        self.show_author_books(author_id.as_deref(), &name);
+    }
+
+    fn go_to_item_series(&self, item_id: &str) {
+        let series = self
+            .cached_item(item_id)
+            .and_then(|item| BrowseKind::Series.values(&item).into_iter().next());
+        if let Some(series) = series {
+            self.show_browse_entity(BrowseKind::Series, &series);
+        }
+    }
+
+    /// Copies the item's page on the server's web client.
+    fn copy_item_link(&self, item_id: &str) {
+        let url = format!("{}/item/{}", self.imp().client.server_url(), item_id);
+        self.clipboard().set_text(&url);
+        self.imp()
+            .toast_overlay
+            .add_toast(adw::Toast::new("Link copied to clipboard"));
+    }
+
+    /// Copies a shelfily:// link that plays the item from where it is now:
+    /// the player position while it plays, its saved progress otherwise.
+    fn copy_item_link_at_position(&self, item_id: &str) {
+        let item_id = item_id.to_string();
+        self.with_item_progress(&item_id.clone(), move |win, progress, _, _| {
+            let imp = win.imp();
+            let position = if imp.current_item_id.borrow().as_deref() == Some(item_id.as_str()) {
+                *imp.current_time.borrow()
+            } else {
+                progress.and_then(|p| p.current_time).unwrap_or(0.0)
+            };
+            let link = links::item_link(&imp.client.server_url(), &item_id, Some(position));
+            win.clipboard().set_text(&link);
+            win.show_toast(&format!(
+                "Link at {} copied to clipboard",
+                format_time(position)
+            ));
+        });
+    }
+
+    fn show_item_info(&self, item_id: &str) {
+        let Some(item) = self.cached_item(item_id) else {
+            self.open_audiobook_detail(item_id);
+            return;
+        };
+        let media = item.media.as_ref();
+        let metadata = media.and_then(|m| m.metadata.as_ref());
+
+        let dialog = adw::PreferencesDialog::new();
+        dialog.set_title("Book Info");
+        dialog.set_search_enabled(false);
+        let page = adw::PreferencesPage::new();
+        let group = adw::PreferencesGroup::new();
+        group.set_title(Self::item_title_for_sort(&item));
+
+        let size = item
+            .size
+            .as_ref()
+            .and_then(|s| s.as_u64())
+            .map(|s| glib::format_size(s).to_string());
+        let progress = item.user_media_progress.as_ref().map(|p| {
+            if p.is_finished.unwrap_or(false) {
+                "Finished".to_string()
+            } else {
+                format!("{:.0}%", p.progress.unwrap_or(0.0) * 100.0)
+            }
+        });
+        let genres = metadata
+            .and_then(|m| m.genres.as_ref())
+            .filter(|g| !g.is_empty())
+            .map(|g| g.join(", "));
+        let rows = [
+            ("Subtitle", metadata.and_then(|m| m.subtitle.clone())),
+            ("Author", metadata.and_then(|m| m.author_name.clone())),
+            ("Narrator", metadata.and_then(|m| m.narrator_name.clone())),
+            ("Series", metadata.and_then(|m| m.series_name.clone())),
+            ("Genres", genres),
+            ("Published", metadata.and_then(|m| m.published_year.clone())),
+            ("Publisher", metadata.and_then(|m| m.publisher.clone())),
+            ("Language", metadata.and_then(|m| m.language.clone())),
+            ("ISBN", metadata.and_then(|m| m.isbn.clone())),
+            ("ASIN", metadata.and_then(|m| m.asin.clone())),
+            ("Duration", media.and_then(|m| m.duration).map(format_time)),
+            ("Size", size),
+            ("Files", item.num_files.map(|n| n.to_string())),
+            ("Progress", progress),
+            ("Item ID", Some(item.id.clone())),
+        ];
+        for (title, value) in rows {
+            let Some(value) = value.filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            let row = adw::ActionRow::new();
+            row.add_css_class("property");
+            row.set_title(title);
+            row.set_subtitle(&glib::markup_escape_text(&value));
+            row.set_subtitle_selectable(true);
+            group.add(&row);
+        }
+        page.add(&group);
+        dialog.add(&page);
+        dialog.present(Some(self));
+    }
+
+    // ─── PROGRESS ACTIONS ───────────────────────────────────────────────────
+
+    /// Hands the item's current progress, duration and title to `on_ready`,
+    /// from the cached library when possible.
+    fn with_item_progress(
+        &self,
+        item_id: &str,
+        on_ready: impl FnOnce(&Self, Option<MediaProgress>, f64, String) + 'static,
+    ) {
+        if let Some(item) = self.cached_item(item_id) {
+            let duration = item.media.as_ref().and_then(|m| m.duration).unwrap_or(0.0);
+            let title = Self::item_title_for_sort(&item).to_string();
+            on_ready(self, item.user_media_progress, duration, title);
+            return;
+        }
+
+        let client = self.imp().client.clone();
+        let id = item_id.to_string();
+        let win = self.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(client.get_library_item(&id));
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(item)) => {
+                    let media = item.media.as_ref();
+                    let duration = media.and_then(|m| m.duration).unwrap_or(0.0);
+                    let title = media
+                        .and_then(|m| m.metadata.as_ref())
+                        .and_then(|m| m.title.clone())
+                        .unwrap_or_default();
+                    on_ready(&win, item.user_media_progress, duration, title);
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Load item progress failed: {}", err);
+                    win.imp().toast_overlay.add_toast(adw::Toast::new(&format!(
+                        "Failed to load the book: {}",
+                        err
+                    )));
+                }
+                Err(err) => log::warn!("Item progress channel error: {}", err),
+            }
+        });
+    }
+
+    /// Runs a progress change off the main thread, then refreshes the views
+    /// and offers to put the previous progress back.
+    fn apply_progress_change(
+        &self,
+        item_id: &str,
+        message: String,
+        previous: Option<MediaProgress>,
+        change: impl FnOnce(&AudiobookshelfClient) -> Result<(), ApiError> + Send + 'static,
+    ) {
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        let item_id = item_id.to_string();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(change(&client));
+        });
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(())) => {
+                    let toast = adw::Toast::new(&message);
+                    if let Some(previous) = previous {
+                        toast.set_button_label(Some("Undo"));
+                        let win = win.clone();
+                        let item_id = item_id.clone();
+                        toast.connect_button_clicked(move |_| {
+                            let previous = previous.clone();
+                            let restore_id = item_id.clone();
+                            if win.imp().current_item_id.borrow().as_deref()
+                                == Some(item_id.as_str())
+                            {
+                                win.seek_to(previous.current_time.unwrap_or(0.0));
+                            }
+                            win.apply_progress_change(
+                                &item_id,
+                                "Progress restored".to_string(),
+                                None,
+                                move |client| client.restore_media_progress(&restore_id, &previous),
+                            );
+                        });
+                    }
+                    win.imp().toast_overlay.add_toast(toast);
+                    win.load_library();
+                    if win.imp().detail_play_item_id.borrow().as_deref() == Some(item_id.as_str()) {
+                        win.refresh_detail_item(&item_id);
+                    }
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Progress change failed: {}", err);
+                    win.imp().toast_overlay.add_toast(adw::Toast::new(&format!(
+                        "Failed to update progress: {}",
+                        err
+                    )));
+                }
+                Err(err) => log::warn!("Progress change channel error: {}", err),
+            }
+        });
+    }
+
+    fn reset_item_progress(&self, item_id: &str) {
+        let item_id = item_id.to_string();
+        self.with_item_progress(&item_id.clone(), move |win, progress, _, title| {
+            let Some(progress) = progress else {
+                win.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("This book has no progress to reset"));
+                return;
+            };
+            let Some(progress_id) = progress.id.clone() else {
+                return;
+            };
+            if win.imp().current_item_id.borrow().as_deref() == Some(item_id.as_str()) {
+                win.seek_to(0.0);
+            }
+            win.apply_progress_change(
+                &item_id,
+                format!("Progress of “{}” reset", title),
+                Some(progress),
+                move |client| client.delete_media_progress(&progress_id),
+            );
+        });
+    }
+
+    fn remove_item_from_continue(&self, item_id: &str) {
+        let item_id = item_id.to_string();
+        self.with_item_progress(&item_id.clone(), move |win, progress, _, title| {
+            let Some(progress_id) = progress.as_ref().and_then(|p| p.id.clone()) else {
+                win.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("This book isn't in Continue Listening"));
+                return;
+            };
+            win.apply_progress_change(
+                &item_id,
+                format!("Removed “{}” from Continue Listening", title),
+                progress,
+                move |client| client.remove_from_continue_listening(&progress_id),
+            );
+        });
+    }
+
+    fn prompt_progress_position(&self, item_id: &str) {
+        let item_id = item_id.to_string();
+        self.with_item_progress(&item_id.clone(), move |win, progress, duration, title| {
+            let current = progress
+                .as_ref()
+                .and_then(|p| p.current_time)
+                .unwrap_or(0.0);
+            let dialog = adw::AlertDialog::new(
+                Some("Set Position"),
+                Some(&format!("{} · {} long", title, format_time(duration))),
+            );
+            let entry = gtk::Entry::new();
+            entry.set_text(&format_time(current));
+            entry.set_placeholder_text(Some("h:mm:ss"));
+            entry.set_activates_default(true);
+            dialog.set_extra_child(Some(&entry));
+            dialog.add_response("cancel", "Cancel");
+            dialog.add_response("set", "Set");
+            dialog.set_response_appearance("set", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("set"));
+            dialog.set_close_response("cancel");
+
+            let win_response = win.clone();
+            dialog.connect_response(None, move |_, response| {
+                if response != "set" {
+                    return;
+                }
+                let win = &win_response;
+                let Some(position) = parse_time(&entry.text()) else {
+                    win.imp()
+                        .toast_overlay
+                        .add_toast(adw::Toast::new("Enter a time like 1:23:45"));
+                    return;
+                };
+                let position = if duration > 0.0 {
+                    position.min(duration)
+                } else {
+                    position
+                };
+                if win.imp().current_item_id.borrow().as_deref() == Some(item_id.as_str()) {
+                    win.seek_to(position);
+                }
+                let id = item_id.clone();
+                win.apply_progress_change(
+                    &item_id,
+                    format!("Position set to {}", format_time(position)),
+                    progress.clone(),
+                    move |client| client.set_progress_position(&id, position, duration),
+                );
+            });
+            dialog.present(Some(win));
+        });
+    }
+
+    // ─── SELECTION & BULK ACTIONS ──────────────────────────────────────────
+
+    fn set_selection_mode(&self, enabled: bool) {
+        let imp = self.imp();
+        if imp.selection_mode.get() == enabled {
+            return;
+        }
+        imp.selection_mode.set(enabled);
+        let mode = if enabled {
+            gtk::SelectionMode::Multiple
+        } else {
+            gtk::SelectionMode::None
+        };
+        for flowbox in [&imp.library_flowbox, &imp.continue_flowbox] {
+            if let Some(flowbox) = flowbox.borrow().as_ref() {
+                flowbox.unselect_all();
+                flowbox.set_selection_mode(mode);
+            }
+        }
+        if let Some(selection) = self.library_list_selection() {
+            selection.unselect_all();
+        }
+        if let Some(btn) = imp.selection_btn.borrow().as_ref() {
+            btn.set_active(enabled);
+        }
+        if let Some(bar) = imp.selection_bar.borrow().as_ref() {
+            bar.set_revealed(enabled);
+        }
+        self.update_selection_label();
+    }
+
+    fn library_list_selection(&self) -> Option<gtk::MultiSelection> {
+        self.imp()
+            .library_column_view
+            .borrow()
+            .as_ref()
+            .and_then(|view| view.model())
+            .and_downcast::<gtk::MultiSelection>()
+    }
+
+    /// Items selected in the visible tab, in display order.
+    fn selected_items(&self) -> Vec<LibraryItem> {
+        let imp = self.imp();
+        let from_flowbox = |flowbox: &RefCell<Option<gtk::FlowBox>>,
+                            rendered: &RefCell<Vec<LibraryItem>>| {
+            let Some(flowbox) = flowbox.borrow().clone() else {
+                return Vec::new();
+            };
+            let rendered = rendered.borrow();
+            let mut indices: Vec<usize> = flowbox
+                .selected_children()
+                .iter()
+                .filter_map(|child| usize::try_from(child.index()).ok())
+                .collect();
+            indices.sort_unstable();
+            indices
+                .into_iter()
+                .filter_map(|i| rendered.get(i).cloned())
+                .collect()
+        };
+
+        match self.sort_tab() {
+            "continue" => from_flowbox(&imp.continue_flowbox, &imp.continue_rendered),
+            _ if self.library_tab_name().as_deref() != Some("all") => Vec::new(),
+            _ if imp.library_view.borrow().mode == LibraryViewMode::List => {
+                let Some(selection) = self.library_list_selection() else {
+                    return Vec::new();
+                };
+                let selected = selection.selection();
+                (0..selected.size())
+                    .filter_map(|n| selection.item(selected.nth(n as u32)))
+                    .filter_map(|obj| obj.downcast::<glib::BoxedAnyObject>().ok())
+                    .map(|obj| obj.borrow::<LibraryItem>().clone())
+                    .collect()
+            }
+            _ => from_flowbox(&imp.library_flowbox, &imp.library_rendered),
+        }
+    }
+
+    fn library_tab_name(&self) -> Option<String> {
+        self.imp()
+            .library_header_switcher
+            .borrow()
+            .as_ref()
+            .and_then(|s| s.stack())
+            .and_then(|s| s.visible_child_name())
+            .map(|name| name.to_string())
+    }
+
+    fn update_selection_label(&self) {
+        let imp = self.imp();
+        let Some(label) = imp.selection_label.borrow().clone() else {
+            return;
+        };
+        let count = if imp.selection_mode.get() {
+            self.selected_items().len()
+        } else {
+            0
+        };
+        label.set_text(&match count {
+            0 => "No books selected".to_string(),
+            1 => "1 book selected".to_string(),
+            n => format!("{} books selected", n),
+        });
+    }
+
+    fn select_all_items(&self) {
+        let imp = self.imp();
+        match self.sort_tab() {
+            "continue" => {
+                if let Some(flowbox) = imp.continue_flowbox.borrow().as_ref() {
+                    flowbox.select_all();
+                }
+            }
+            _ if imp.library_view.borrow().mode == LibraryViewMode::List => {
+                if let Some(selection) = self.library_list_selection() {
+                    selection.select_all();
+                }
+            }
+            _ => {
+                if let Some(flowbox) = imp.library_flowbox.borrow().as_ref() {
+                    flowbox.select_all();
+                }
+            }
+        }
+        self.update_selection_label();
+    }
+
+    /// Selected items for a bulk action, or a hint toast when there are none.
+    fn bulk_targets(&self) -> Option<Vec<LibraryItem>> {
+        let items = self.selected_items();
+        if items.is_empty() {
+            self.imp()
+                .toast_overlay
+                .add_toast(adw::Toast::new("Select one or more books first"));
+            return None;
+        }
+        Some(items)
+    }
+
+    /// Runs `op` for every item on a worker thread, with a progress toast
+    /// that can cancel the rest and a summary listing the failures.
+    fn run_bulk_action(
+        &self,
+        verb: &'static str,
+        done: &'static str,
+        items: Vec<LibraryItem>,
+        reload: bool,
+        notify: Option<NotificationKind>,
+        op: BulkOp,
+    ) {
+        let imp = self.imp();
+        let total = items.len();
+
+        let toast = adw::Toast::new(&format!("{}… 0/{}", verb, total));
+        toast.set_timeout(0);
+        toast.set_button_label(Some("Cancel"));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let cancel_flag = cancel.clone();
+        toast.connect_button_clicked(move |_| cancel_flag.store(true, Ordering::Relaxed));
+        imp.toast_overlay.add_toast(toast.clone());
+
+        let client = imp.client.clone();
+        let (tx, rx) = async_channel::unbounded::<BulkEvent>();
+        std::thread::spawn(move || {
+            for (index, item) in items.iter().enumerate() {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let progress_tx = tx.clone();
+                let last_percent = Cell::new(0u32);
+                let progress = move |fraction: f64| {
+                    let percent = (fraction * 100.0) as u32;
+                    if percent != last_percent.get() {
+                        last_percent.set(percent);
+                        let _ = progress_tx.send_blocking(BulkEvent::Progress { index, percent });
+                    }
+                };
+                let result = op(&client, item, &progress, &cancel);
+                let error = match result {
+                    Ok(()) => None,
+                    Err(ApiError::Cancelled) => break,
+                    Err(e) => Some(format!("{}: {}", Self::item_title_for_sort(item), e)),
+                };
+                let _ = tx.send_blocking(BulkEvent::ItemDone { error });
+            }
+            let _ = tx.send_blocking(BulkEvent::Finished {
+                cancelled: cancel.load(Ordering::Relaxed),
+            });
+        });
+
+        let win = self.clone();
+        glib::spawn_future_local(async move {
+            let mut completed = 0;
+            let mut failures: Vec<String> = Vec::new();
+            while let Ok(event) = rx.recv().await {
+                match event {
+                    BulkEvent::Progress { index, percent } => {
+                        toast.set_title(&format!(
+                            "{}… {}/{} ({}%)",
+                            verb,
+                            index + 1,
+                            total,
+                            percent
+                        ));
+                    }
+                    BulkEvent::ItemDone { error } => {
+                        completed += 1;
+                        if let Some(error) = error {
+                            log::warn!("{} failed for {}", verb, error);
+                            failures.push(error);
+                        }
+                        toast.set_title(&format!("{}… {}/{}", verb, completed, total));
+                    }
+                    BulkEvent::Finished { cancelled } => {
+                        toast.dismiss();
+                        let succeeded = completed - failures.len();
+                        let message = if cancelled {
+                            format!("Cancelled after {} of {} books", completed, total)
+                        } else if failures.is_empty() {
+                            format!("{} {} book(s)", done, succeeded)
+                        } else {
+                            format!("{} of {} books failed", failures.len(), total)
+                        };
+                        let summary = adw::Toast::new(&message);
+                        if !failures.is_empty() {
+                            summary.set_timeout(0);
+                            summary.set_button_label(Some("Details"));
+                            let win = win.clone();
+                            let failures = std::mem::take(&mut failures);
+                            summary.connect_button_clicked(move |_| {
+                                let dialog = adw::AlertDialog::new(
+                                    Some("Some Books Failed"),
+                                    Some(&failures.join("\n")),
+                                );
+                                dialog.add_response("close", "Close");
+                                dialog.present(Some(&win));
+                            });
+                        }
+                        win.imp().toast_overlay.add_toast(summary);
+                        if let Some(kind) = notify.filter(|_| !cancelled) {
+                            win.notify(kind, &message, "", &[]);
+                        }
+                        if reload && succeeded > 0 {
+                            win.load_library();
+                        }
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    fn bulk_mark_finished(&self, finished: bool) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        let (verb, done) = if finished {
+            ("Marking as finished", "Marked as finished:")
+        } else {
+            ("Marking as not finished", "Marked as not finished:")
+        };
+        self.run_bulk_action(
+            verb,
+            done,
+            items,
+            true,
+            None,
+            Arc::new(move |client, item, _, _| client.update_progress(&item.id, finished)),
+        );
+    }
+
+    fn bulk_reset_progress(&self) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        let dialog = adw::AlertDialog::new(
+            Some("Reset Progress?"),
+            Some(&format!(
+                "Listening progress of {} book(s) will be removed. This cannot be undone.",
+                items.len()
+            )),
+        );
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("reset", "Reset");
+        dialog.set_response_appearance("reset", adw::ResponseAppearance::Destructive);
+        dialog.set_close_response("cancel");
+        let win = self.clone();
+        dialog.connect_response(None, move |_, response| {
+            if response != "reset" {
+                return;
+            }
+            win.run_bulk_action(
+                "Resetting progress",
+                "Reset progress of",
+                items.clone(),
+                true,
+                None,
+                Arc::new(|client, item, _, _| {
+                    match item
+                        .user_media_progress
+                        .as_ref()
+                        .and_then(|p| p.id.as_deref())
+                    {
+                        Some(progress_id) => client.delete_media_progress(progress_id),
+                        // Never started, nothing to reset
+                        None => Ok(()),
+                    }
+                }),
+            );
+        });
+        dialog.present(Some(self));
+    }
+
+    fn bulk_remove_from_continue(&self) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        self.run_bulk_action(
+            "Removing from Continue Listening",
+            "Removed from Continue Listening:",
+            items,
+            true,
+            None,
+            Arc::new(|client, item, _, _| {
+                match item
+                    .user_media_progress
+                    .as_ref()
+                    .and_then(|p| p.id.as_deref())
+                {
+                    Some(progress_id) => client.remove_from_continue_listening(progress_id),
+                    None => Ok(()),
+                }
+            }),
+        );
+    }
+
+    fn bulk_download(&self) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        self.download_items(items);
+    }
+
+    fn download_items(&self, items: Vec<LibraryItem>) {
+        if !self.current_user_can(User::can_download) {
+            return;
+        }
+        match self.connectivity() {
+            Connectivity::Online => {}
+            Connectivity::Metered => {
+                self.imp().deferred_downloads.borrow_mut().extend(items);
+                self.show_toast("Downloads start once you're on an unmetered connection");
+                return;
+            }
+            Connectivity::Offline | Connectivity::Unreachable => {
+                self.show_toast("Can't download while the server is unreachable");
+                return;
+            }
+        }
+        self.run_bulk_action(
+            "Downloading",
+            "Downloaded",
+            items,
+            false,
+            Some(NotificationKind::DownloadFinished),
+            Arc::new(|client, item, progress, cancel| {
+                if downloads::is_downloaded(&item.id) {
+                    return Ok(());
+                }
+                downloads::download_item(
+                    client,
+                    &item.id,
+                    &|sent, total| {
+                        if let Some(total) = std::num::NonZeroU64::new(total) {
+                            progress(sent as f64 / total.get() as f64);
+                        }
+                    },
+                    cancel,
+                )
+                .map(|_| ())
+            }),
+        );
+    }
+
+    fn bulk_add_to_queue(&self) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        self.enqueue_items(items.iter().map(|item| item.id.clone()).collect());
+    }
+
+    /// Appends items to the play queue, skipping ones already queued, and
+    /// starts playing when nothing is.
+    fn enqueue_items(&self, item_ids: Vec<String>) {
+        let imp = self.imp();
+        let mut added = 0;
+        {
+            let mut queue = imp.play_queue.borrow_mut();
+            for id in item_ids {
+                if !queue.contains(&id) && imp.current_item_id.borrow().as_ref() != Some(&id) {
+                    queue.push_back(id);
+                    added += 1;
+                }
+            }
+        }
+        imp.toast_overlay.add_toast(adw::Toast::new(&match added {
+            0 => "Already in the queue".to_string(),
+            1 => "Added 1 book to the queue".to_string(),
+            n => format!("Added {} books to the queue", n),
+        }));
+        if imp.current_item_id.borrow().is_none() {
+            self.play_next_in_queue();
+        }
+    }
+
+    fn play_next_in_queue(&self) -> bool {
+        let next = self.imp().play_queue.borrow_mut().pop_front();
+        match next {
+            Some(item_id) => {
+                self.start_playback(&item_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn bulk_add_to_collection(&self) {
+        let Some(items) = self.bulk_targets() else {
+            return;
+        };
+        self.add_to_collection(items.into_iter().map(|item| item.id).collect());
+    }
+
+    /// Loads the collections and playlists, then asks where the items go.
+    fn add_to_collection(&self, item_ids: Vec<String>) {
+        let imp = self.imp();
+        let library_id = imp.library_id.borrow().clone();
+        let can_update = self.current_user_can(User::can_update);
+        let client = imp.client.clone();
+        let win = self.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        let lib_id = library_id.clone();
+        std::thread::spawn(move || {
+            let collections = if can_update {
+                client.get_collections(&lib_id)
+            } else {
+                Ok(Vec::new())
+            };
+            let playlists = client.get_playlists(&lib_id);
+            let _ = tx.send_blocking((collections, playlists));
+        });
+        glib::spawn_future_local(async move {
+            let Ok((collections, playlists)) = rx.recv().await else {
+                return;
+            };
+            let collections = collections.unwrap_or_else(|e| {
+                log::warn!("Load collections failed: {}", e);
+                Vec::new()
+            });
+            let playlists = playlists.unwrap_or_else(|e| {
+                log::warn!("Load playlists failed: {}", e);
+                Vec::new()
+            });
+            win.show_add_to_collection_dialog(
+                library_id,
+                item_ids,
+                can_update,
+                collections,
+                playlists,
+            );
+        });
+    }
+
+    fn show_add_to_collection_dialog(
+        &self,
+        library_id: String,
+        item_ids: Vec<String>,
+        can_update: bool,
+        collections: Vec<Collection>,
+        playlists: Vec<Playlist>,
+    ) {
+        #[derive(Clone)]
+        enum Target {
+            NewCollection,
+            NewPlaylist,
+            Collection(String, String),
+            Playlist(String, String),
+        }
+
+        let mut targets = Vec::new();
+        if can_update {
+            targets.push(("New Collection…".to_string(), Target::NewCollection));
+        }
+        targets.push(("New Playlist…".to_string(), Target::NewPlaylist));
+        for c in &collections {
+            targets.push((
+                format!("Collection: {}", c.name),
+                Target::Collection(c.id.clone(), c.name.clone()),
+            ));
+        }
+        for p in &playlists {
+            targets.push((
+                format!("Playlist: {}", p.name),
+                Target::Playlist(p.id.clone(), p.name.clone()),
+            ));
+        }
+
+        let dialog = adw::AlertDialog::new(
+            Some("Add to Collection or Playlist"),
+            Some(&format!("{} book(s) selected", item_ids.len())),
+        );
+        let group = adw::PreferencesGroup::new();
+        let labels: Vec<&str> = targets.iter().map(|(label, _)| label.as_str()).collect();
+        let target_row = adw::ComboRow::new();
+        target_row.set_title("Add To");
+        target_row.set_model(Some(&gtk::StringList::new(&labels)));
+        let name_row = adw::EntryRow::new();
+        name_row.set_title("Name");
+        group.add(&target_row);
+        group.add(&name_row);
+        dialog.set_extra_child(Some(&group));
+
+        let targets = Rc::new(targets);
+        let targets_for_row = targets.clone();
+        let name_for_row = name_row.clone();
+        let sync_name = move |row: &adw::ComboRow| {
+            let is_new = matches!(
+                targets_for_row.get(row.selected() as usize).map(|(_, t)| t),
+                Some(Target::NewCollection | Target::NewPlaylist)
+            );
+            name_for_row.set_visible(is_new);
+        };
+        sync_name(&target_row);
+        target_row.connect_selected_notify(sync_name);
+
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("add", "Add");
+        dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("add"));
+        dialog.set_close_response("cancel");
+
+        let win = self.clone();
+        dialog.connect_response(None, move |_, response| {
+            if response != "add" {
+                return;
+            }
+            let Some((_, target)) = targets.get(target_row.selected() as usize).cloned() else {
+                return;
+            };
+            let name = name_row.text().trim().to_string();
+            if matches!(target, Target::NewCollection | Target::NewPlaylist) && name.is_empty() {
+                win.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("Enter a name for the new list"));
+                return;
+            }
+
+            let ids = item_ids.clone();
+            let client = win.imp().client.clone();
+            let library_id = library_id.clone();
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                let result = match target {
+                    Target::NewCollection => client
+                        .create_collection(&library_id, &name, &ids)
+                        .map(|c| c.name),
+                    Target::NewPlaylist => client
+                        .create_playlist(&library_id, &name, &ids)
+                        .map(|p| p.name),
+                    Target::Collection(id, name) => {
+                        client.add_to_collection(&id, &ids).map(|_| name)
+                    }
+                    Target::Playlist(id, name) => client.add_to_playlist(&id, &ids).map(|_| name),
+                };
+                let _ = tx.send_blocking(result.map(|name| (name, ids.len())));
+            });
+            let win = win.clone();
+            glib::spawn_future_local(async move {
+                let message = match rx.recv().await {
+                    Ok(Ok((name, count))) => format!("Added {} book(s) to {}", count, name),
+                    Ok(Err(e)) => {
+                        log::warn!("Add to collection failed: {}", e);
+                        format!("Could not add books: {}", e)
+                    }
+                    Err(_) => return,
+                };
+                win.imp().toast_overlay.add_toast(adw::Toast::new(&message));
+            });
+        });
+        dialog.present(Some(self));
+    }
+
+    // ─── LIBRARY LIST VIEW ─────────────────────────────────────────────────
+
+    fn library_view_path() -> std::path::PathBuf {
+        let mut path = glib::user_config_dir();
+        path.push("shelfily-desktop");
+        path.push("library-view.json");
+        path
+    }
+
+    fn read_library_view() -> LibraryViewSettings {
+        fs::read_to_string(Self::library_view_path())
+            .ok()
+            .and_then(|content| serde_json::from_str::<LibraryViewSettings>(&content).ok())
+            .unwrap_or_default()
+            .normalized()
+    }
+
+    /// Persists the view mode and the column layout as currently shown.
+    fn write_library_view(&self) {
+        let imp = self.imp();
+        if let Some(column_view) = imp.library_column_view.borrow().as_ref() {
+            let columns = column_view.columns();
+            let layout: Vec<ColumnLayout> = (0..columns.n_items())
+                .filter_map(|i| columns.item(i).and_downcast::<gtk::ColumnViewColumn>())
+                .filter_map(|col| {
+                    let id = col.id()?;
+                    let column = LibraryColumn::ALL.into_iter().find(|c| c.id() == id)?;
+                    Some(ColumnLayout {
+                        column,
+                        visible: col.is_visible(),
+                        width: col.fixed_width(),
+                    })
+                })
+                .collect();
+            imp.library_view.borrow_mut().columns = layout;
+        }
+
+        let path = Self::library_view_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(&*imp.library_view.borrow()) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    fn set_library_view_mode(&self, mode: LibraryViewMode) {
+        let imp = self.imp();
+        if imp.library_view.borrow().mode == mode {
+            return;
+        }
+        imp.library_view.borrow_mut().mode = mode;
+        self.write_library_view();
+        if let Some(stack) = imp.library_mode_stack.borrow().as_ref() {
+            stack.set_visible_child_name(match mode {
+                LibraryViewMode::Grid => "grid",
+                LibraryViewMode::List => "list",
+            });
+        }
+        self.render_library();
+    }
+
+    fn library_column_text(column: LibraryColumn, item: &LibraryItem) -> String {
+        let metadata = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+        let format_date = |ms: u64| {
+            if ms == 0 {
+                return String::new();
+            }
+            glib::DateTime::from_unix_local((ms / 1000) as i64)
+                .and_then(|d| d.format("%x"))
+                .map(|s| s.to_string())
+                .unwrap_or_default()
+        };
+        match column {
+            LibraryColumn::Title => Self::item_title_for_sort(item).to_string(),
+            LibraryColumn::Author => metadata
+                .and_then(|m| m.author_name.clone())
+                .unwrap_or_default(),
+            LibraryColumn::Narrator => metadata
+                .and_then(|m| m.narrator_name.clone())
+                .unwrap_or_default(),
+            LibraryColumn::Series => metadata
+                .and_then(|m| m.series_name.clone())
+                .unwrap_or_default(),
+            LibraryColumn::Duration => item
+                .media
+                .as_ref()
+                .and_then(|m| m.duration)
+                .map(|d| {
+                    let hours = (d / 3600.0) as u32;
+                    let mins = ((d % 3600.0) / 60.0) as u32;
+                    if hours > 0 {
+                        format!("{} h {} min", hours, mins)
+                    } else {
+                        format!("{} min", mins)
+                    }
+                })
+                .unwrap_or_default(),
+            LibraryColumn::Progress => {
+                if Self::item_is_finished(item) {
+                    "Finished".to_string()
+                } else {
+                    let progress = Self::item_progress_fraction(item);
+                    if progress > 0.0 {
+                        format!("{}%", (progress * 100.0).round() as i32)
+                    } else {
+                        String::new()
+                    }
+                }
+            }
+            LibraryColumn::Added => format_date(Self::item_added_timestamp(item)),
+            LibraryColumn::LastPlayed => format_date(Self::item_last_played_timestamp(item)),
+        }
+    }
+
+    /// Dense, sortable table of the library shown instead of the cover grid.
+    fn build_library_list(&self) -> gtk::Widget {
+        let imp = self.imp();
+        let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        let column_view = gtk::ColumnView::new(None::<gtk::MultiSelection>);
+        column_view.set_show_row_separators(true);
+        column_view.set_reorderable(true);
+        column_view.add_css_class("data-table");
+
+        let sorted = gtk::SortListModel::new(Some(store.clone()), column_view.sorter());
+        let selection = gtk::MultiSelection::new(Some(sorted));
+        let win = self.clone();
+        selection.connect_selection_changed(move |_, _, _| win.update_selection_label());
+        column_view.set_model(Some(&selection));
+        column_view.set_enable_rubberband(true);
+
+        // Header menus toggle the optional columns
+        let column_actions = gio::SimpleActionGroup::new();
+        let columns_menu = gio::Menu::new();
+        for column in LibraryColumn::ALL.into_iter().skip(1) {
+            columns_menu.append(
+                Some(column.label()),
+                Some(&format!("library-columns.{}", column.id())),
+            );
+        }
+
+        let layout = imp.library_view.borrow().columns.clone();
+        for ColumnLayout {
+            column,
+            visible,
+            width,
+        } in layout
+        {
+            let factory = gtk::SignalListItemFactory::new();
+            factory.connect_setup(|_, list_item| {
+                let label = gtk::Label::new(None);
+                label.set_xalign(0.0);
+                label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+                list_item
+                    .downcast_ref::<gtk::ListItem>()
+                    .unwrap()
+                    .set_child(Some(&label));
+            });
+            factory.connect_bind(move |_, list_item| {
+                let list_item = list_item.downcast_ref::<gtk::ListItem>().unwrap();
+                let (Some(label), Some(obj)) = (
+                    list_item.child().and_downcast::<gtk::Label>(),
+                    list_item.item().and_downcast::<glib::BoxedAnyObject>(),
+                ) else {
+                    return;
+                };
+                let text = Self::library_column_text(column, &obj.borrow::<LibraryItem>());
+                label.set_tooltip_text((!text.is_empty()).then_some(text.as_str()));
+                label.set_text(&text);
+            });
+
+            let view_column = gtk::ColumnViewColumn::new(Some(column.label()), Some(factory));
+            view_column.set_id(Some(column.id()));
+            view_column.set_resizable(true);
+            view_column.set_header_menu(Some(&columns_menu));
+            if column == LibraryColumn::Title {
+                view_column.set_expand(true);
+            } else {
+                view_column.set_visible(visible);
+            }
+            if width > 0 {
+                view_column.set_fixed_width(width);
+            }
+            let sort = LibrarySort {
+                mode: column.sort_mode(),
+                descending: false,
+                ignore_prefix: true,
+            };
+            view_column.set_sorter(Some(&gtk::CustomSorter::new(move |a, b| {
+                let (Some(a), Some(b)) = (
+                    a.downcast_ref::<glib::BoxedAnyObject>(),
+                    b.downcast_ref::<glib::BoxedAnyObject>(),
+                ) else {
+                    return gtk::Ordering::Equal;
+                };
+                Self::compare_items(&a.borrow::<LibraryItem>(), &b.borrow::<LibraryItem>(), sort)
+                    .into()
+            })));
+
+            if column != LibraryColumn::Title {
+                let action =
+                    gio::SimpleAction::new_stateful(column.id(), None, &visible.to_variant());
+                let win = self.clone();
+                let view_column_weak = view_column.downgrade();
+                action.connect_activate(move |action, _| {
+                    let visible = !action.state().and_then(|s| s.get::<bool>()).unwrap_or(true);
+                    action.set_state(&visible.to_variant());
+                    if let Some(view_column) = view_column_weak.upgrade() {
+                        view_column.set_visible(visible);
+                    }
+                    win.write_library_view();
+                });
+                column_actions.add_action(&action);
+            }
+
+            let win = self.clone();
+            view_column.connect_fixed_width_notify(move |_| win.write_library_view());
+            column_view.append_column(&view_column);
+        }
+        column_view.insert_action_group("library-columns", Some(&column_actions));
+
+        // Dragging a header to a new position
+        let win = self.clone();
+        column_view
+            .columns()
+            .connect_items_changed(move |_, _, _, _| win.write_library_view());
+
+        let win = self.clone();
+        column_view.connect_activate(move |column_view, position| {
+            let item_id = column_view
+                .model()
+                .and_then(|model| model.item(position))
+                .and_downcast::<glib::BoxedAnyObject>()
+                .map(|obj| obj.borrow::<LibraryItem>().id.clone());
+            if let Some(item_id) = item_id {
+                win.open_audiobook_detail(&item_id);
+            }
+        });
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_hscrollbar_policy(gtk::PolicyType::Automatic);
+        scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&column_view));
+
+        *imp.library_list_store.borrow_mut() = Some(store);
+        *imp.library_column_view.borrow_mut() = Some(column_view);
+
+        scrolled.upcast()
+    }
+
+    // ─── NARRATOR & GENRE BROWSING ──────────────────────────────────────────
+
+    /// Hands every item of the current library to `on_ready`, whatever
+    /// the library filters show.
+    fn with_browse_items(&self, on_ready: impl FnOnce(&Self, Vec<LibraryItem>) + 'static) {
+        let items = self.imp().library_items.borrow().clone();
+        on_ready(self, items);
+    }
+
+    /// Pushes a page with a spinner and returns its content box.
+    fn push_browse_page(&self, title: &str) -> Option<(gtk::Box, adw::StatusPage, adw::HeaderBar)> {
+        let nav_view = self.imp().nav_view.borrow().clone()?;
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+
+        let clamp = adw::Clamp::new();
+        clamp.set_maximum_size(960);
+        clamp.set_margin_top(24);
+        clamp.set_margin_bottom(24);
+        clamp.set_margin_start(24);
+        clamp.set_margin_end(24);
+
+        let container = gtk::Box::new(gtk::Orientation::Vertical, 16);
+        clamp.set_child(Some(&container));
+        scrolled.set_child(Some(&clamp));
+
+        let toolbar_view = adw::ToolbarView::new();
+        let header = adw::HeaderBar::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&scrolled));
+
+        let nav_page = adw::NavigationPage::builder()
+            .title(title)
+            .child(&toolbar_view)
+            .build();
+        nav_view.push(&nav_page);
+
+        let loading = adw::StatusPage::new();
+        let spinner = adw::Spinner::new();
+        spinner.set_size_request(48, 48);
+        loading.set_child(Some(&spinner));
+        loading.set_title("Loading");
+        container.append(&loading);
+
+        Some((container, loading, header))
+    }
+
+    /// Lists every value of the given kinds with its number of books.
+    fn show_browse_list(&self, kinds: &'static [BrowseKind]) {
+        let title = kinds
+            .iter()
+            .map(|k| k.plural())
+            .collect::<Vec<_>>()
+            .join(" & ");
+        let Some((container, loading, header)) = self.push_browse_page(&title) else {
+            return;
+        };
+
+        let search_entry = gtk::SearchEntry::new();
+        search_entry.set_placeholder_text(Some(&format!("Search {}", title.to_lowercase())));
+        search_entry.set_hexpand(true);
+        header.set_title_widget(Some(&search_entry));
+
+        self.with_browse_items(move |win, items| {
+            container.remove(&loading);
+
+            let mut rows: Vec<(adw::ActionRow, String)> = Vec::new();
+            let mut groups: Vec<adw::PreferencesGroup> = Vec::new();
+            for &kind in kinds {
+                let counts = kind.counts(&items);
+                let group = adw::PreferencesGroup::new();
+                if kinds.len() > 1 {
+                    group.set_title(kind.plural());
+                }
+                if counts.is_empty() {
+                    let row = adw::ActionRow::new();
+                    row.set_title(&format!(
+                        "No {} in this library",
+                        kind.plural().to_lowercase()
+                    ));
+                    row.add_css_class("dim-label");
+                    group.add(&row);
+                }
+                for (value, count) in counts {
+                    let row = adw::ActionRow::new();
+                    row.set_title(&glib::markup_escape_text(&value));
+                    row.set_activatable(true);
+                    let count_label = gtk::Label::new(Some(&if count == 1 {
+                        "1 book".to_string()
+                    } else {
+                        format!("{} books", count)
+                    }));
+                    count_label.add_css_class("dim-label");
+                    row.add_suffix(&count_label);
+                    row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+                    let win = win.clone();
+                    let name = value.clone();
+                    row.connect_activated(move |_| win.show_browse_entity(kind, &name));
+                    group.add(&row);
+                    rows.push((row, value.to_lowercase()));
+                }
+                container.append(&group);
+                groups.push(group);
+            }
+
+            search_entry.connect_search_changed(move |entry| {
+                let query = entry.text().trim().to_lowercase();
+                for (row, value) in &rows {
+                    row.set_visible(query.is_empty() || value.contains(&query));
+                }
+            });
+        });
+    }
+
+    /// Books with one narrator, genre or tag, with their progress and a sort.
+    fn show_browse_entity(&self, kind: BrowseKind, name: &str) {
+        let Some((container, loading, header)) = self.push_browse_page(name) else {
+            return;
+        };
+
+        let sort_dropdown = gtk::DropDown::from_strings(
+            &LibrarySortMode::ALL
+                .iter()
+                .map(|m| m.label())
+                .collect::<Vec<_>>(),
+        );
+        sort_dropdown.set_tooltip_text(Some("Sort"));
+        let direction_btn = gtk::ToggleButton::new();
+        direction_btn.set_icon_name("view-sort-descending-symbolic");
+        direction_btn.set_tooltip_text(Some("Descending"));
+        direction_btn.set_active(true);
+        header.pack_end(&direction_btn);
+        header.pack_end(&sort_dropdown);
+
+        let name = name.to_string();
+        self.with_browse_items(move |win, items| {
+            container.remove(&loading);
+
+            let items: Vec<LibraryItem> = items
+                .into_iter()
+                .filter(|item| kind.values(item).contains(&name))
+                .collect();
+
+            let count_label = gtk::Label::new(Some(&format!("{} book(s)", items.len())));
+            count_label.add_css_class("dim-label");
+            count_label.add_css_class("caption");
+            count_label.set_halign(gtk::Align::Start);
+            container.append(&count_label);
+
+            let flowbox = gtk::FlowBox::new();
+            flowbox.set_valign(gtk::Align::Start);
+            flowbox.set_selection_mode(gtk::SelectionMode::None);
+            flowbox.set_homogeneous(true);
+            flowbox.set_max_children_per_line(6);
+            flowbox.set_min_children_per_line(1);
+            flowbox.set_column_spacing(12);
+            flowbox.set_row_spacing(12);
+            container.append(&flowbox);
+
+            let render: Rc<dyn Fn()> = {
+                let win = win.clone();
+                let flowbox = flowbox.clone();
+                let sort_dropdown = sort_dropdown.clone();
+                let direction_btn = direction_btn.clone();
+                Rc::new(move || {
+                    while let Some(child) = flowbox.first_child() {
+                        flowbox.remove(&child);
+                    }
+                    let mode = LibrarySortMode::ALL
+                        .get(sort_dropdown.selected() as usize)
+                        .copied()
+                        .unwrap_or(LibrarySortMode::Title);
+                    let sort = LibrarySort {
+                        mode,
+                        descending: direction_btn.is_active(),
+                        ..LibrarySort::default()
+                    };
+                    let mut items = items.clone();
+                    items.sort_by(|a, b| Self::compare_items(a, b, sort));
+                    for item in &items {
+                        flowbox.append(&win.create_book_card(item));
+                    }
+                })
+            };
+
+            let render_sort = render.clone();
+            let render_initial = render.clone();
+            let direction = direction_btn.clone();
+            sort_dropdown.connect_selected_notify(move |dropdown| {
+                // Each key starts in its natural direction
+                let mode = LibrarySortMode::ALL
+                    .get(dropdown.selected() as usize)
+                    .copied()
+                    .unwrap_or(LibrarySortMode::Title);
+                if direction.is_active() == mode.default_descending() {
+                    render_sort();
+                } else {
+                    direction.set_active(mode.default_descending());
+                }
+            });
+            direction_btn.connect_toggled(move |btn| {
+                if btn.is_active() {
+                    btn.set_icon_name("view-sort-descending-symbolic");
+                    btn.set_tooltip_text(Some("Descending"));
+                } else {
+                    btn.set_icon_name("view-sort-ascending-symbolic");
+                    btn.set_tooltip_text(Some("Ascending"));
+                }
+                render();
+            });
+            // Start with title order, or reading order for a series
+            let initial = if kind == BrowseKind::Series {
+                LibrarySortMode::Series
+            } else {
+                LibrarySortMode::Title
+            };
+            let initial_index = LibrarySortMode::ALL
+                .iter()
+                .position(|m| *m == initial)
+                .unwrap_or(0);
+            if sort_dropdown.selected() as usize == initial_index {
+                render_initial();
+            } else {
+                sort_dropdown.set_selected(initial_index as u32);
+            }
+        });
+    }
+
+    // ─── LIBRARY SORTING ───────────────────────────────────────────────────
+
+    fn library_sort_path() -> std::path::PathBuf {
+        let mut path = glib::user_config_dir();
+        path.push("shelfily-desktop");
+        path.push("library-sort.json");
+        path
+    }
+
+    /// library id → tab name ("all", "continue") → sort
+    fn read_all_library_sorts() -> HashMap<String, HashMap<String, LibrarySort>> {
+        fs::read_to_string(Self::library_sort_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn load_library_sorts(&self, library_id: &str) {
+        let imp = self.imp();
+        let sorts = Self::read_all_library_sorts()
+            .remove(library_id)
+            .unwrap_or_default();
+        imp.library_sort
+            .set(sorts.get("all").copied().unwrap_or_default());
+        imp.continue_sort
+            .set(sorts.get("continue").copied().unwrap_or_default());
+    }
+
+    fn sort_tab(&self) -> &'static str {
+        let visible = self
+            .imp()
+            .library_header_switcher
+            .borrow()
+            .as_ref()
+            .and_then(|s| s.stack())
+            .and_then(|s| s.visible_child_name());
+        if visible.as_deref() == Some("continue") {
+            "continue"
+        } else {
+            "all"
+        }
+    }
+
+    fn set_tab_sort(&self, tab: &str, sort: LibrarySort) {
+        let imp = self.imp();
+        if tab == "continue" {
+            imp.continue_sort.set(sort);
+            self.render_continue_listening();
+        } else {
+            imp.library_sort.set(sort);
+            self.render_library();
+        }
+
+        let library_id = imp.library_id.borrow().clone();
+        if library_id.is_empty() {
+            return;
+        }
+        let mut sorts = Self::read_all_library_sorts();
+        sorts
+            .entry(library_id)
+            .or_default()
+            .insert(tab.to_string(), sort);
+        let path = Self::library_sort_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(&sorts) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    fn build_sort_popover(&self, popover: &gtk::Popover) {
+        let tab = self.sort_tab();
+        let current = if tab == "continue" {
+            self.imp().continue_sort.get()
+        } else {
+            self.imp().library_sort.get()
+        };
+
+        let sort_box = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        sort_box.set_margin_top(8);
+        sort_box.set_margin_bottom(8);
+        sort_box.set_margin_start(8);
+        sort_box.set_margin_end(8);
+
+        let heading = gtk::Label::new(Some(if tab == "continue" {
+            "Sort Continue"
+        } else {
+            "Sort All Books"
+        }));
+        heading.add_css_class("heading");
+        heading.set_xalign(0.0);
+        heading.set_margin_bottom(4);
+        sort_box.append(&heading);
+
+        let mut group: Option<gtk::CheckButton> = None;
+        for mode in LibrarySortMode::ALL {
+            let check = gtk::CheckButton::with_label(mode.label());
+            check.set_group(group.as_ref());
+            check.set_active(mode == current.mode);
+            group.get_or_insert_with(|| check.clone());
+            let win = self.clone();
+            check.connect_toggled(move |check| {
+                if !check.is_active() {
+                    return;
+                }
+                let sort = if tab == "continue" {
+                    win.imp().continue_sort.get()
+                } else {
+                    win.imp().library_sort.get()
+                };
+                win.set_tab_sort(
+                    tab,
+                    LibrarySort {
+                        mode,
+                        descending: mode.default_descending(),
+                        ..sort
+                    },
+                );
+                if let Some(popover) = check.ancestor(gtk::Popover::static_type()) {
+                    popover.downcast_ref::<gtk::Popover>().unwrap().popdown();
+                }
+            });
+            sort_box.append(&check);
+        }
+
+        sort_box.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+        let ascending = gtk::CheckButton::with_label("Ascending");
+        let descending = gtk::CheckButton::with_label("Descending");
+        descending.set_group(Some(&ascending));
+        descending.set_active(current.descending);
+        ascending.set_active(!current.descending);
+        let win = self.clone();
+        descending.connect_toggled(move |check| {
+            let sort = if tab == "continue" {
+                win.imp().continue_sort.get()
+            } else {
+                win.imp().library_sort.get()
+            };
+            win.set_tab_sort(
+                tab,
+                LibrarySort {
+                    descending: check.is_active(),
+                    ..sort
+                },
+            );
+        });
+        sort_box.append(&ascending);
+        sort_box.append(&descending);
+
+        sort_box.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+        let ignore_prefix = gtk::CheckButton::with_label("Ignore Leading Articles");
+        ignore_prefix.set_tooltip_text(Some("Sort “The Hobbit” under H"));
+        ignore_prefix.set_active(current.ignore_prefix);
+        let win = self.clone();
+        ignore_prefix.connect_toggled(move |check| {
+            let sort = if tab == "continue" {
+                win.imp().continue_sort.get()
+            } else {
+                win.imp().library_sort.get()
+            };
+            win.set_tab_sort(
+                tab,
+                LibrarySort {
+                    ignore_prefix: check.is_active(),
+                    ..sort
+                },
+            );
+        });
+        sort_box.append(&ignore_prefix);
+
+        popover.set_child(Some(&sort_box));
+    }
+
+    /// Orders by the sort's key (missing values last in either direction),
+    /// then by title and author.
+    fn compare_items(a: &LibraryItem, b: &LibraryItem, sort: LibrarySort) -> std::cmp::Ordering {
+        fn by<T: PartialOrd>(a: Option<T>, b: Option<T>, descending: bool) -> std::cmp::Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => {
+                    let ord = a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal);
+                    if descending {
+                        ord.reverse()
+                    } else {
+                        ord
+                    }
+                }
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+        fn by_text(a: &str, b: &str, descending: bool) -> std::cmp::Ordering {
+            match (a.is_empty(), b.is_empty()) {
+                (false, false) => {
+                    let ord = natural_cmp(a, b);
+                    if descending {
+                        ord.reverse()
+                    } else {
+                        ord
+                    }
+                }
+                (false, true) => std::cmp::Ordering::Less,
+                (true, false) => std::cmp::Ordering::Greater,
+                (true, true) => std::cmp::Ordering::Equal,
+            }
+        }
+
+        fn metadata(item: &LibraryItem) -> Option<&Metadata> {
+            item.media.as_ref().and_then(|m| m.metadata.as_ref())
+        }
+        // Items carry the sequence in the series name, "Series #2, Other #1";
+        // the first series counts
+        fn series(item: &LibraryItem) -> (&str, &str) {
+            let name = metadata(item)
+                .and_then(|m| m.series_name.as_deref())
+                .unwrap_or_default();
+            match name.split_once(" #") {
+                Some((series, rest)) => (series, rest.split(',').next().unwrap_or_default().trim()),
+                None => (name, ""),
+            }
+        }
+
+        let desc = sort.descending;
+        let title = |item: &LibraryItem| {
+            let md = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+            let ignoring = md
+                .filter(|_| sort.ignore_prefix)
+                .and_then(|m| m.title_ignore_prefix.as_deref());
+            ignoring
+                .unwrap_or_else(|| Self::item_title_for_sort(item))
+                .to_string()
+        };
+        let progress = |item: &LibraryItem| {
+            item.user_media_progress.as_ref().map(|p| {
+                if p.is_finished.unwrap_or(false) {
+                    1.0
+                } else {
+                    p.progress.unwrap_or(0.0)
+                }
+            })
+        };
+
+        let primary = match sort.mode {
+            LibrarySortMode::NewlyAdded => by(
+                Some(Self::item_added_timestamp(a)),
+                Some(Self::item_added_timestamp(b)),
+                desc,
+            ),
+            LibrarySortMode::Title => by_text(&title(a), &title(b), desc),
+            LibrarySortMode::Author => by_text(
+                Self::item_author_for_sort(a),
+                Self::item_author_for_sort(b),
+                desc,
+            ),
+            LibrarySortMode::RecentlyPlayed => by(
+                Some(Self::item_last_played_timestamp(a)).filter(|t| *t > 0),
+                Some(Self::item_last_played_timestamp(b)).filter(|t| *t > 0),
+                desc,
+            ),
+            LibrarySortMode::Duration => by(
+                a.media.as_ref().and_then(|m| m.duration),
+                b.media.as_ref().and_then(|m| m.duration),
+                desc,
+            ),
+            LibrarySortMode::PublishedYear => {
+                let year = |item: &LibraryItem| {
+                    metadata(item)
+                        .and_then(|m| m.published_year.as_deref())
+                        .and_then(|y| y.trim().get(..4).and_then(|y| y.parse::<u32>().ok()))
+                };
+                by(year(a), year(b), desc)
+            }
+            LibrarySortMode::Progress => by(progress(a), progress(b), desc),
+            LibrarySortMode::Series => {
+                let ((series_a, sequence_a), (series_b, sequence_b)) = (series(a), series(b));
+                // Books within a series stay in reading order
+                by_text(series_a, series_b, desc).then_with(|| natural_cmp(sequence_a, sequence_b))
+            }
+            LibrarySortMode::Narrator => by_text(
+                metadata(a)
+                    .and_then(|m| m.narrator_name.as_deref())
+                    .unwrap_or_default(),
+                metadata(b)
+                    .and_then(|m| m.narrator_name.as_deref())
+                    .unwrap_or_default(),
+                desc,
+            ),
+            LibrarySortMode::Size => {
+                let size = |item: &LibraryItem| {
+                    item.size
+                        .as_ref()
+                        .or_else(|| item.media.as_ref().and_then(|m| m.size.as_ref()))
+                        .and_then(|v| v.as_u64())
+                };
+                by(size(a), size(b), desc)
+            }
+            LibrarySortMode::DateFinished => {
+                let finished = |item: &LibraryItem| {
+                    item.user_media_progress
+                        .as_ref()
+                        .filter(|p| p.is_finished.unwrap_or(false))
+                        .and_then(|p| p.finished_at)
+                };
+                by(finished(a), finished(b), desc)
+            }
+        };
+
+        primary
+            .then_with(|| natural_cmp(&title(a), &title(b)))
+            .then_with(|| natural_cmp(Self::item_author_for_sort(a), Self::item_author_for_sort(b)))
+    }
+
+    // ─── LIBRARY FILTERS ───────────────────────────────────────────────────
+
+    fn library_filters_path() -> std::path::PathBuf {
+        let mut path = glib::user_config_dir();
+        path.push("shelfily-desktop");
+        path.push("library-filters.json");
+        path
+    }
+
+    fn read_all_library_filters() -> HashMap<String, LibraryFilter> {
+        fs::read_to_string(Self::library_filters_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn read_library_filter(library_id: &str) -> LibraryFilter {
+        Self::read_all_library_filters()
+            .remove(library_id)
+            .unwrap_or_default()
+    }
+
+    fn write_library_filter(library_id: &str, filter: &LibraryFilter) {
+        let mut filters = Self::read_all_library_filters();
+        if filter.is_empty() {
+            filters.remove(library_id);
+        } else {
+            filters.insert(library_id.to_string(), filter.clone());
+        }
+        let path = Self::library_filters_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(&filters) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    fn update_library_filter(&self, update: impl FnOnce(&mut LibraryFilter)) {
+        let imp = self.imp();
+        let old_server_filter = imp.library_filter.borrow().server_filter();
+        let filter = {
+            let mut filter = imp.library_filter.borrow_mut();
+            update(&mut filter);
+            filter.clone()
+        };
+        let library_id = imp.library_id.borrow().clone();
+        if !library_id.is_empty() {
+            Self::write_library_filter(&library_id, &filter);
+        }
+        self.render_filter_chips();
+
+        // A different server-side filter means a different item set to fetch.
+        if filter.server_filter() != old_server_filter {
+            self.load_library();
+        } else {
+            self.render_library();
+        }
+    }
+
+    fn render_filter_chips(&self) {
+        let imp = self.imp();
+        let Some(chips) = imp.library_filter_chips.borrow().clone() else {
+            return;
+        };
+        chips.remove_all();
+
+        let fields = imp.library_filter.borrow().active_fields();
+        for (field, label) in &fields {
+            let content = adw::ButtonContent::new();
+            content.set_label(label);
+            content.set_icon_name("window-close-symbolic");
+            let chip = gtk::Button::new();
+            chip.set_child(Some(&content));
+            chip.add_css_class("pill");
+            chip.add_css_class("small");
+            chip.set_tooltip_text(Some("Remove filter"));
+            let win = self.clone();
+            let field = *field;
+            chip.connect_clicked(move |_| {
+                win.update_library_filter(|f| f.clear(field));
+            });
+            chips.append(&chip);
+        }
+
+        if fields.len() > 1 {
+            let clear = gtk::Button::with_label("Clear All");
+            clear.add_css_class("flat");
+            clear.add_css_class("small");
+            let win = self.clone();
+            clear.connect_clicked(move |_| {
+                win.update_library_filter(|f| *f = LibraryFilter::default());
+            });
+            chips.append(&clear);
+        }
+        chips.set_visible(!fields.is_empty());
+    }
+
+    fn build_filter_popover(&self, popover: &gtk::Popover) {
+        let imp = self.imp();
+        let library_id = imp.library_id.borrow().clone();
+        let data = imp
+            .library_filter_data
+            .borrow()
+            .as_ref()
+            .filter(|(id, _)| *id == library_id)
+            .map(|(_, data)| data.clone());
+
+        // Fetch the library's genres, tags, ... once, then rebuild if still open.
+        if data.is_none() && !library_id.is_empty() {
+            let client = imp.client.clone();
+            let id = library_id.clone();
+            let (tx, rx) = async_channel::bounded::<Result<LibraryFilterData, String>>(1);
+            std::thread::spawn(move || {
+                let result = client
+                    .get_library_filter_data(&id)
+                    .map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+            let win = self.clone();
+            let popover_recv = popover.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(data)) => {
+                        *win.imp().library_filter_data.borrow_mut() = Some((library_id, data));
+                        if popover_recv.is_visible() {
+                            win.build_filter_popover(&popover_recv);
+                        }
+                    }
+                    Ok(Err(err)) => log::warn!("Load filter data failed: {}", err),
+                    Err(err) => log::warn!("Filter data channel error: {}", err),
+                }
+            });
+        }
+        let data = data.unwrap_or_default();
+        let filter = imp.library_filter.borrow().clone();
+
+        let grid = gtk::Grid::new();
+        grid.set_row_spacing(8);
+        grid.set_column_spacing(12);
+        grid.set_margin_top(12);
+        grid.set_margin_bottom(12);
+        grid.set_margin_start(12);
+        grid.set_margin_end(12);
+        let mut row = 0;
+        let mut attach = |label: &str, widget: &gtk::Widget| {
+            let title = gtk::Label::new(Some(label));
+            title.set_xalign(0.0);
+            title.add_css_class("dim-label");
+            grid.attach(&title, 0, row, 1, 1);
+            grid.attach(widget, 1, row, 1, 1);
+            row += 1;
+        };
+
+        // Progress
+        let progress_labels: Vec<&str> = std::iter::once("Any")
+            .chain(ProgressFilter::ALL.iter().map(|p| p.label()))
+            .collect();
+        let progress_dd = gtk::DropDown::from_strings(&progress_labels);
+        progress_dd.set_selected(
+            filter
+                .progress
+                .and_then(|p| ProgressFilter::ALL.iter().position(|x| *x == p))
+                .map(|i| i as u32 + 1)
+                .unwrap_or(0),
+        );
+        let win = self.clone();
+        progress_dd.connect_selected_notify(move |dd| {
+            let progress = (dd.selected() as usize)
+                .checked_sub(1)
+                .and_then(|i| ProgressFilter::ALL.get(i).copied());
+            win.update_library_filter(|f| f.progress = progress);
+        });
+        attach("Progress", progress_dd.upcast_ref());
+
+        // Genre, tag, narrator, language, publisher
+        type TextField = fn(&mut LibraryFilter) -> &mut Option<String>;
+        let text_filters: [(&str, &Vec<String>, &Option<String>, TextField); 5] = [
+            ("Genre", &data.genres, &filter.genre, |f| &mut f.genre),
+            ("Tag", &data.tags, &filter.tag, |f| &mut f.tag),
+            ("Narrator", &data.narrators, &filter.narrator, |f| {
+                &mut f.narrator
+            }),
+            ("Language", &data.languages, &filter.language, |f| {
+                &mut f.language
+            }),
+            ("Publisher", &data.publishers, &filter.publisher, |f| {
+                &mut f.publisher
+            }),
+        ];
+        for (label, values, current, field) in text_filters {
+            let mut options: Vec<String> = values.clone();
+            // Keep a persisted value selectable even before filter data arrives.
+            if let Some(current) = current {
+                if !options.contains(current) {
+                    options.insert(0, current.clone());
+                }
+            }
+            let strings: Vec<&str> = std::iter::once("Any")
+                .chain(options.iter().map(String::as_str))
+                .collect();
+            let dd = gtk::DropDown::from_strings(&strings);
+            dd.set_enable_search(options.len() > 10);
+            dd.set_search_match_mode(gtk::StringFilterMatchMode::Substring);
+            dd.set_expression(Some(gtk::PropertyExpression::new(
+                gtk::StringObject::static_type(),
+                None::<&gtk::Expression>,
+                "string",
+            )));
+            dd.set_selected(
+                current
+                    .as_ref()
+                    .and_then(|c| options.iter().position(|o| o == c))
+                    .map(|i| i as u32 + 1)
+                    .unwrap_or(0),
+            );
+            let win = self.clone();
+            dd.connect_selected_notify(move |dd| {
+                let value = (dd.selected() as usize)
+                    .checked_sub(1)
+                    .and_then(|i| options.get(i).cloned());
+                win.update_library_filter(|f| *field(f) = value);
+            });
+            attach(label, dd.upcast_ref());
+        }
+
+        // Ranges: 0 means "no bound"
+        let year_range = self.filter_range_row(
+            2100.0,
+            [
+                (filter.year_min, |f| &mut f.year_min),
+                (filter.year_max, |f| &mut f.year_max),
+            ],
+        );
+        attach("Published", year_range.upcast_ref());
+        let duration_range = self.filter_range_row(
+            500.0,
+            [
+                (filter.duration_min, |f| &mut f.duration_min),
+                (filter.duration_max, |f| &mut f.duration_max),
+            ],
+        );
+        attach("Length (hours)", duration_range.upcast_ref());
+
+        let ebook_check = gtk::CheckButton::with_label("Has ebook");
+        ebook_check.set_active(filter.has_ebook);
+        let win = self.clone();
+        ebook_check.connect_toggled(move |check| {
+            let active = check.is_active();
+            win.update_library_filter(|f| f.has_ebook = active);
+        });
+        grid.attach(&ebook_check, 1, row, 1, 1);
+
+        popover.set_child(Some(&grid));
+    }
+
+    /// Minimum and maximum spin buttons for one range filter.
+    fn filter_range_row(&self, max: f64, bounds: [(Option<u32>, FilterBound); 2]) -> gtk::Box {
+        let range_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        for ((value, field), tooltip) in bounds
+            .into_iter()
+            .zip(["Minimum (0 for none)", "Maximum (0 for none)"])
+        {
+            let spin = gtk::SpinButton::with_range(0.0, max, 1.0);
+            spin.set_value(value.unwrap_or(0) as f64);
+            spin.set_tooltip_text(Some(tooltip));
+            let win = self.clone();
+            spin.connect_value_changed(move |spin| {
+                let value = Some(spin.value() as u32).filter(|v| *v > 0);
+                win.update_library_filter(|f| *field(f) = value);
+            });
+            range_box.append(&spin);
+        }
+        range_box
+    }
+
+    // ─── DETAIL PAGE ───────────────────────────────────────────────────────
+
+    fn open_audiobook_detail(&self, item_id: &str) {
+        let imp = self.imp();
+
+        let nav_view = match imp.nav_view.borrow().clone() {
+            Some(v) => v,
+            None => return,
+        };
+
+        // Pop back to library root (handles re-opening from detail page)
+        while nav_view.pop() {}
+
+        // Build detail content
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_hscrollbar_policy(gtk::PolicyType::Never);
+        scrolled.set_vscrollbar_policy(gtk::PolicyType::Automatic);
+
+        let clamp = adw::Clamp::new();
+        clamp.set_maximum_size(800);
+        clamp.set_margin_top(24);
+        clamp.set_margin_bottom(24);
+        clamp.set_margin_start(24);
+        clamp.set_margin_end(24);
+
+        let detail_box = gtk::Box::new(gtk::Orientation::Vertical, 24);
+        detail_box.set_valign(gtk::Align::Start);
+        clamp.set_child(Some(&detail_box));
+        scrolled.set_child(Some(&clamp));
+
+        let toolbar_view = adw::ToolbarView::new();
+        let header = adw::HeaderBar::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&scrolled));
+
+        // Store widget refs before async fetch
+        *imp.detail_content.borrow_mut() = Some(detail_box);
+        *imp.detail_play_btn.borrow_mut() = None;
+        *imp.detail_play_item_id.borrow_mut() = Some(item_id.to_string());
+        *imp.detail_top_box.borrow_mut() = None;
+        *imp.detail_cover_image.borrow_mut() = None;
+
+        let detail_nav_page = adw::NavigationPage::builder()
+            .title("Yükleniyor...")
+            .child(&toolbar_view)
+            .build();
+        let detail_nav_page_ref = detail_nav_page.clone();
+        nav_view.push(&detail_nav_page);
+
+        let client = imp.client.clone();
+        let win = self.clone();
+        let id = item_id.to_string();
+
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let item_id = id.clone();
+
+            std::thread::spawn(move || {
+                let result = client.get_library_item(&item_id);
+                let _ = tx.send_blocking(result);
+            });
+
+            match rx.recv().await {
+                Ok(Ok(item)) => {
+                    let book_title = item
+                        .media
+                        .as_ref()
+                        .and_then(|m| m.metadata.as_ref())
+                        .and_then(|m| m.title.as_deref())
+                        .unwrap_or("Kitap Detayı");
+                    detail_nav_page_ref.set_title(book_title);
+                    win.populate_detail(&item);
+                }
+                Ok(Err(e)) => {
+                    log::error!("Failed to load book: {}", e);
+                }
+                Err(_) => {
+                    log::error!("Channel error");
+                }
+            }
+        });
+    }
+
+    fn populate_detail(&self, item: &LibraryItemExpanded) {
+        let imp = self.imp();
+        let detail_box = imp.detail_content.borrow();
+        let detail_box = detail_box.as_ref().unwrap();
+
+        while let Some(child) = detail_box.first_child() {
+            detail_box.remove(&child);
+        }
+        *imp.detail_play_btn.borrow_mut() = None;
+        *imp.detail_play_item_id.borrow_mut() = Some(item.id.clone());
+        *imp.detail_top_box.borrow_mut() = None;
+        *imp.detail_cover_image.borrow_mut() = None;
+        imp.chapter_indicators.borrow_mut().clear();
+        imp.bookmarks.borrow_mut().clear();
+        *imp.bookmarks_group.borrow_mut() = None;
+        *imp.bookmarks_section.borrow_mut() = None;
+
+        let metadata = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+
+        // Top: cover + info
+        let top_box = gtk::Box::new(gtk::Orientation::Horizontal, 24);
+
+        let cover_frame = gtk::Frame::new(None);
+        cover_frame.add_css_class("card");
+        cover_frame.add_css_class("book-cover-frame");
+        cover_frame.set_valign(gtk::Align::Start);
+        cover_frame.set_overflow(gtk::Overflow::Hidden);
+        let cover_image = gtk::Image::from_icon_name("audio-x-generic-symbolic");
+        cover_image.set_pixel_size(220);
+        cover_image.set_size_request(220, 220);
+        cover_frame.set_child(Some(&cover_image));
+        top_box.append(&cover_frame);
+        *imp.detail_top_box.borrow_mut() = Some(top_box.clone());
+        *imp.detail_cover_image.borrow_mut() = Some(cover_image.clone());
+
+        // Load cover
+        let client = imp.client.clone();
+        let item_id = item.id.clone();
+        let img = cover_image.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                let result = client.download_cover(&item_id);
+                let _ = tx.send_blocking(result);
+            });
+            if let Ok(Ok(bytes)) = rx.recv().await {
+                let gbytes = glib::Bytes::from(&bytes);
+                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
+                if let Ok(pixbuf) =
+                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
+                {
+                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
+                    img.set_paintable(Some(&texture));
+                    img.set_size_request(220, -1);
+                }
+            }
+        });
+
+        let info_box = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        info_box.set_valign(gtk::Align::Center);
+        info_box.set_hexpand(true);
+
+        let title = metadata
+            .and_then(|m| m.title.as_deref())
+            .unwrap_or("Unknown Book");
+        let title_label = gtk::Label::new(Some(title));
+        title_label.add_css_class("title-1");
+        title_label.set_halign(gtk::Align::Start);
+        title_label.set_wrap(true);
+        info_box.append(&title_label);
+
+        if let Some(subtitle) = metadata.and_then(|m| m.subtitle.as_deref()) {
+            let sub_label = gtk::Label::new(Some(subtitle));
+            sub_label.add_css_class("title-3");
+            sub_label.add_css_class("dim-label");
+            sub_label.set_halign(gtk::Align::Start);
+            sub_label.set_wrap(true);
+            info_box.append(&sub_label);
+        }
+
+        if let Some(authors) = metadata.and_then(|m| m.authors.as_ref()) {
+            if !authors.is_empty() {
+                let authors_flow = gtk::FlowBox::new();
+                authors_flow.set_selection_mode(gtk::SelectionMode::None);
+                authors_flow.set_column_spacing(6);
+                authors_flow.set_row_spacing(6);
+                authors_flow.set_max_children_per_line(20);
+                authors_flow.set_halign(gtk::Align::Start);
+                for author in authors {
+                    let btn = gtk::Button::with_label(&author.name);
+                    btn.add_css_class("pill");
+                    btn.add_css_class("flat");
+                    btn.add_css_class("author-chip");
+                    btn.set_tooltip_text(Some(&format!("Show books by {}", author.name)));
+                    let name = author.name.clone();
+                    let id = author.id.clone();
+                    let win = self.clone();
+                    btn.connect_clicked(move |_| {
+                        win.show_author_books(id.as_deref(), &name);
+                    });
+                    authors_flow.append(&btn);
+                }
+                info_box.append(&authors_flow);
+            }
+        }
+
+        if let Some(narrators) = metadata.and_then(|m| m.narrators.as_ref()) {
+            if !narrators.is_empty() {
+                // Each narrator links to their browse page
+                let links = narrators
+                    .iter()
+                    .map(|n| {
+                        let escaped = glib::markup_escape_text(n);
+                        format!("<a href=\"{}\">{}</a>", escaped, escaped)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let narrator_label = gtk::Label::new(None);
+                narrator_label.set_markup(&format!("Narrated by: {}", links));
+                narrator_label.add_css_class("dim-label");
+                narrator_label.set_halign(gtk::Align::Start);
+                narrator_label.set_wrap(true);
+                let win = self.clone();
+                narrator_label.connect_activate_link(move |_, narrator| {
+                    win.show_browse_entity(BrowseKind::Narrator, narrator);
+                    glib::Propagation::Stop
+                });
+                info_box.append(&narrator_label);
+            }
+        }
+
+        if let Some(duration) = item.media.as_ref().and_then(|m| m.duration) {
+            let hours = (duration / 3600.0) as u32;
+            let mins = ((duration % 3600.0) / 60.0) as u32;
+            let dur_label = gtk::Label::new(Some(&format!("Duration: {} h {} min", hours, mins)));
+            dur_label.add_css_class("dim-label");
+            dur_label.set_halign(gtk::Align::Start);
+            info_box.append(&dur_label);
+        }
+
+        if let Some(series) = metadata.and_then(|m| m.series.as_ref()) {
+            for s in series {
+                let mut text = s.name.clone().unwrap_or_default();
+                if let Some(seq) = &s.sequence {
+                    text = format!("{} #{}", text, seq);
+                }
+                if !text.is_empty() {
+                    let series_label = gtk::Label::new(Some(&format!("Series: {}", text)));
+                    series_label.add_css_class("dim-label");
+                    series_label.set_halign(gtk::Align::Start);
+                    info_box.append(&series_label);
+                }
+            }
+        }
+
+        if let Some(genres) = metadata.and_then(|m| m.genres.as_ref()) {
+            if !genres.is_empty() {
+                let genre_flow = gtk::FlowBox::new();
+                genre_flow.set_selection_mode(gtk::SelectionMode::None);
+                genre_flow.set_max_children_per_line(10);
+                genre_flow.set_column_spacing(4);
+                genre_flow.set_row_spacing(4);
+                for genre in genres {
+                    let badge = gtk::Button::with_label(genre);
+                    badge.add_css_class("caption");
+                    badge.add_css_class("card");
+                    badge.add_css_class("flat");
+                    badge.set_margin_start(4);
+                    badge.set_margin_end(4);
+                    badge.set_margin_top(2);
+                    badge.set_margin_bottom(2);
+                    badge.set_tooltip_text(Some(&format!("Show {} books", genre)));
+                    let win = self.clone();
+                    let genre = genre.clone();
+                    badge.connect_clicked(move |_| {
+                        win.show_browse_entity(BrowseKind::Genre, &genre);
+                    });
+                    genre_flow.append(&badge);
+                }
+                info_box.append(&genre_flow);
+            }
+        }
+
+        top_box.append(&info_box);
+        detail_box.append(&top_box);
+        // Apply the current compact state to the freshly built detail widgets.
+        self.set_compact_layout(self.imp().compact_mode.get());
+
+        // Play button (Gelly tarzı dairesel büyük buton)
+        let play_button = gtk::Button::from_icon_name("media-playback-start-symbolic");
+        play_button.add_css_class("suggested-action");
+        play_button.add_css_class("circular");
+        play_button.add_css_class("detail-play-btn");
+        play_button.set_size_request(64, 64);
+        play_button.set_valign(gtk::Align::Center);
+        *imp.detail_play_btn.borrow_mut() = Some(play_button.clone());
+
+        let item_id = item.id.clone();
+        let win = self.clone();
+        play_button.connect_clicked(move |_| {
+            let imp = win.imp();
+            let same_item = imp.current_item_id.borrow().as_deref() == Some(item_id.as_str());
+            if same_item && imp.is_playing.get() {
+                win.stop_playback();
+                win.hide_player();
+                win.refresh_detail_play_button();
+            } else {
+                win.start_playback(&item_id);
+            }
+        });
+
+        // Mark as read/unread toggle button
+        let is_finished_init = item
+            .user_media_progress
+            .as_ref()
+            .and_then(|p| p.is_finished)
+            .unwrap_or(false);
+        let saved_progress_time = item
+            .user_media_progress
+            .as_ref()
+            .and_then(|p| p.current_time)
+            .unwrap_or(0.0);
+        imp.detail_is_finished.set(is_finished_init);
+
+        let mark_button = gtk::Button::new();
+        mark_button.add_css_class("pill");
+        mark_button.set_valign(gtk::Align::Center);
+        let mark_content = adw::ButtonContent::new();
+        mark_button.set_child(Some(&mark_content));
+
+        let mark_state = Rc::new(Cell::new(is_finished_init));
+        let apply_mark_visual = |state: bool, content: &adw::ButtonContent| {
+            if state {
+                content.set_icon_name("edit-undo-symbolic");
+                content.set_label("Mark as Unfinished");
+            } else {
+                content.set_icon_name("object-select-symbolic");
+                content.set_label("Mark as Finished");
+            }
+        };
+        apply_mark_visual(mark_state.get(), &mark_content);
+
+        let win_mark = self.clone();
+        let item_id_mark = item.id.clone();
+        let mark_state_cb = mark_state.clone();
+        let mark_button_cb = mark_button.clone();
+        let mark_content_cb = mark_content.clone();
+        mark_button.connect_clicked(move |_| {
+            let new_state = !mark_state_cb.get();
+            mark_button_cb.set_sensitive(false);
+            let (sender, receiver) = async_channel::bounded::<Result<(), String>>(1);
+            let client = win_mark.imp().client.clone();
+            let item_id = item_id_mark.clone();
+            std::thread::spawn(move || {
+                let result = client
+                    .update_progress(&item_id, new_state)
+                    .map_err(|e| e.to_string());
+                let _ = sender.send_blocking(result);
+            });
+            let win_recv = win_mark.clone();
+            let mark_button_recv = mark_button_cb.clone();
+            let mark_content_recv = mark_content_cb.clone();
+            let mark_state_recv = mark_state_cb.clone();
+            glib::spawn_future_local(async move {
+                match receiver.recv().await {
+                    Ok(Ok(())) => {
+                        mark_state_recv.set(new_state);
+                        win_recv.imp().detail_is_finished.set(new_state);
+                        if new_state {
+                            mark_content_recv.set_icon_name("edit-undo-symbolic");
+                            mark_content_recv.set_label("Mark as Unfinished");
+                        } else {
+                            mark_content_recv.set_icon_name("object-select-symbolic");
+                            mark_content_recv.set_label("Mark as Finished");
+                        }
+                        let imp_recv = win_recv.imp();
+                        let detail_is_current = imp_recv.detail_play_item_id.borrow().as_deref()
+                            == imp_recv.current_item_id.borrow().as_deref();
+                        let current = if detail_is_current {
+                            *imp_recv.current_time.borrow()
+                        } else {
+                            saved_progress_time
+                        };
+                        win_recv.apply_chapter_indicators(current);
+                        let toast = adw::Toast::new(if new_state {
+                            "Marked as finished"
+                        } else {
+                            "Marked as unfinished"
+                        });
+                        win_recv.imp().toast_overlay.add_toast(toast);
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Update progress error: {}", err);
+                        let toast = adw::Toast::new(&format!("Failed to update progress: {}", err));
+                        win_recv.imp().toast_overlay.add_toast(toast);
+                    }
+                    Err(err) => {
+                        log::warn!("Update progress channel error: {}", err);
+                        let toast = adw::Toast::new("Failed to update progress");
+                        win_recv.imp().toast_overlay.add_toast(toast);
+                    }
+                }
+                mark_button_recv.set_sensitive(true);
+            });
+        });
+
+        let actions_row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        actions_row.set_halign(gtk::Align::Start);
+        actions_row.append(&play_button);
+        actions_row.append(&mark_button);
+        if self.current_user_can(User::can_update) {
+            let edit_button = gtk::Button::from_icon_name("document-edit-symbolic");
+            edit_button.add_css_class("circular");
+            edit_button.set_valign(gtk::Align::Center);
+            edit_button.set_tooltip_text(Some("Edit Details"));
+            let win_edit = self.clone();
+            let item_edit = item.clone();
+            edit_button.connect_clicked(move |_| win_edit.show_edit_metadata_dialog(&item_edit));
+            actions_row.append(&edit_button);
+
+            let match_button = gtk::Button::from_icon_name("system-search-symbolic");
+            match_button.add_css_class("circular");
+            match_button.set_valign(gtk::Align::Center);
+            match_button.set_tooltip_text(Some("Match with Metadata Provider"));
+            let win_match = self.clone();
+            let item_match = item.clone();
+            match_button.connect_clicked(move |_| win_match.show_match_dialog(&item_match));
+            actions_row.append(&match_button);
+        }
+        let more_button = gtk::MenuButton::new();
+        more_button.set_icon_name("view-more-symbolic");
+        more_button.add_css_class("circular");
+        more_button.set_valign(gtk::Align::Center);
+        more_button.set_tooltip_text(Some("More"));
+        more_button.set_menu_model(Some(&self.item_context_menu(&item.id)));
+        actions_row.append(&more_button);
+        detail_box.append(&actions_row);
+        self.refresh_detail_play_button();
+
+        // Description
+        if let Some(desc) = metadata.and_then(|m| m.description.as_deref()) {
+            if !desc.is_empty() {
+                let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
+                detail_box.append(&sep);
+
+                let desc_title = gtk::Label::new(Some("Description"));
+                desc_title.add_css_class("title-4");
+                desc_title.set_halign(gtk::Align::Start);
+                detail_box.append(&desc_title);
+
+                let desc_label = gtk::Label::new(None);
+                let pango_markup = markdown_to_pango(desc);
+                if gtk::pango::parse_markup(&pango_markup, '\0').is_ok() {
+                    desc_label.set_markup(&pango_markup);
+                } else {
+                    log::warn!("Description markup invalid, falling back to plain text");
+                    desc_label.set_label(desc);
+                }
+                desc_label.set_wrap(true);
+                desc_label.set_halign(gtk::Align::Start);
+                desc_label.set_xalign(0.0);
+                desc_label.set_selectable(true);
+                desc_label.add_css_class("body");
+                detail_box.append(&desc_label);
+            }
+        }
+
+        // Chapters
+        if let Some(chapters) = item.media.as_ref().and_then(|m| m.chapters.as_ref()) {
+            if !chapters.is_empty() {
+                let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
+                detail_box.append(&sep);
+
+                let ch_title = gtk::Label::new(Some("Chapters"));
+                ch_title.add_css_class("title-4");
+                ch_title.set_halign(gtk::Align::Start);
+                detail_box.append(&ch_title);
+
+                // Get current listening position
+                let listen_pos = item
+                    .user_media_progress
+                    .as_ref()
+                    .and_then(|p| p.current_time)
+                    .unwrap_or(0.0);
+                let is_finished = item
+                    .user_media_progress
+                    .as_ref()
+                    .and_then(|p| p.is_finished)
+                    .unwrap_or(false);
+
+                let chapters_group = adw::PreferencesGroup::new();
+                for (i, chapter) in chapters.iter().enumerate() {
+                    let ch_name = chapter.title.as_deref().unwrap_or("Chapter");
+                    let start = chapter.start.unwrap_or(0.0);
+                    let end = chapter.end.unwrap_or(0.0);
+                    let dur = end - start;
+                    let mins = (dur / 60.0) as u32;
+                    let secs = (dur % 60.0) as u32;
+
+                    let row = adw::ActionRow::new();
+                    row.set_title(ch_name);
+                    row.set_subtitle(&format!("{} min {} sec", mins, secs));
 
                     let prefix_box = gtk::Box::new(gtk::Orientation::Horizontal, 10);
                     prefix_box.set_valign(gtk::Align::Center);
 
-                    let indicator = gtk::Box::new(gtk::Orientation::Horizontal, 0);
-                    indicator.add_css_class("chapter-indicator");
-                    indicator.set_valign(gtk::Align::Center);
-                    indicator.set_halign(gtk::Align::Center);
+                    let indicator = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+                    indicator.add_css_class("chapter-indicator");
+                    indicator.set_valign(gtk::Align::Center);
+                    indicator.set_halign(gtk::Align::Center);
+
+                    if is_finished || listen_pos >= end {
+                        indicator.add_css_class("completed");
+                    } else if listen_pos >= start && listen_pos < end {
+                        indicator.add_css_class("playing");
+                        row.add_css_class("chapter-row-playing");
+                    } else {
+                        indicator.add_css_class("unplayed");
+                    }
+
+                    let num_label = gtk::Label::new(Some(&format!("{:02}", i + 1)));
+                    num_label.add_css_class("dim-label");
+                    num_label.add_css_class("monospace");
+                    num_label.add_css_class("caption");
+
+                    prefix_box.append(&indicator);
+                    prefix_box.append(&num_label);
+                    row.add_prefix(&prefix_box);
+
+                    imp.chapter_indicators.borrow_mut().push((
+                        start,
+                        end,
+                        indicator.clone(),
+                        row.clone(),
+                    ));
+
+                    let ch_play = gtk::Button::from_icon_name("media-playback-start-symbolic");
+                    ch_play.add_css_class("flat");
+                    ch_play.add_css_class("circular");
+                    ch_play.set_valign(gtk::Align::Center);
+                    let ch_start = start;
+                    let item_id = item.id.clone();
+                    let win = self.clone();
+                    ch_play.connect_clicked(move |_| {
+                        win.jump_to_chapter(&item_id, ch_start);
+                    });
+                    row.add_suffix(&ch_play);
+                    row.set_activatable_widget(Some(&ch_play));
+
+                    chapters_group.add(&row);
+                }
+                detail_box.append(&chapters_group);
+            }
+        }
+
+        // Bookmarks section (populated asynchronously)
+        let bookmarks_section = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        bookmarks_section.set_visible(false);
+        let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
+        bookmarks_section.append(&sep);
+        let bm_title = gtk::Label::new(Some("Bookmarks"));
+        bm_title.add_css_class("title-4");
+        bm_title.set_halign(gtk::Align::Start);
+        bookmarks_section.append(&bm_title);
+        let bookmarks_group = adw::PreferencesGroup::new();
+        bookmarks_section.append(&bookmarks_group);
+        detail_box.append(&bookmarks_section);
+
+        *imp.bookmarks_group.borrow_mut() = Some(bookmarks_group);
+        *imp.bookmarks_section.borrow_mut() = Some(bookmarks_section);
+
+        self.load_bookmarks(&item.id);
+    }
+
+    // ─── METADATA EDITING ──────────────────────────────────────────────────
+
+    /// Re-fetches an item and rebuilds the detail page if it is still showing it.
+    fn refresh_detail_item(&self, item_id: &str) {
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        let id = item_id.to_string();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let result = client.get_library_item(&id);
+            let _ = tx.send_blocking(result);
+        });
+        let item_id = item_id.to_string();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(item)) => {
+                    let imp = win.imp();
+                    if imp.detail_play_item_id.borrow().as_deref() != Some(item_id.as_str()) {
+                        return;
+                    }
+                    if let Some(title) = item
+                        .media
+                        .as_ref()
+                        .and_then(|m| m.metadata.as_ref())
+                        .and_then(|m| m.title.as_deref())
+                    {
+                        let page = imp
+                            .nav_view
+                            .borrow()
+                            .as_ref()
+                            .and_then(|n| n.visible_page());
+                        if let Some(page) = page {
+                            page.set_title(title);
+                        }
+                    }
+                    win.populate_detail(&item);
+                }
+                Ok(Err(err)) => log::warn!("Refresh item failed: {}", err),
+                Err(err) => log::warn!("Refresh item channel error: {}", err),
+            }
+        });
+    }
+
+    fn show_edit_metadata_dialog(&self, item: &LibraryItemExpanded) {
+        let media = item.media.as_ref();
+        let metadata = media.and_then(|m| m.metadata.as_ref());
+        let duration = media.and_then(|m| m.duration).unwrap_or(0.0);
+
+        let dialog = adw::Dialog::new();
+        dialog.set_title("Edit Book");
+        dialog.set_content_width(640);
+        dialog.set_content_height(720);
+
+        let stack = adw::ViewStack::new();
+        let switcher = adw::ViewSwitcher::new();
+        switcher.set_stack(Some(&stack));
+        switcher.set_policy(adw::ViewSwitcherPolicy::Wide);
+
+        let header = adw::HeaderBar::new();
+        header.set_title_widget(Some(&switcher));
+        let save_btn = gtk::Button::with_label("Save");
+        save_btn.add_css_class("suggested-action");
+        header.pack_end(&save_btn);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&stack));
+        let toasts = adw::ToastOverlay::new();
+        toasts.set_child(Some(&toolbar_view));
+        dialog.set_child(Some(&toasts));
+
+        // Details
+        let details_page = adw::PreferencesPage::new();
+        let general_group = adw::PreferencesGroup::new();
+        details_page.add(&general_group);
+
+        let entry = |title: &str, value: &str| {
+            let row = adw::EntryRow::new();
+            row.set_title(title);
+            row.set_text(value);
+            general_group.add(&row);
+            row
+        };
+        let authors = metadata.and_then(|m| m.authors.clone()).unwrap_or_default();
+        let series = metadata.and_then(|m| m.series.clone()).unwrap_or_default();
+        let title_row = entry(
+            "Title",
+            metadata.and_then(|m| m.title.as_deref()).unwrap_or(""),
+        );
+        let subtitle_row = entry(
+            "Subtitle",
+            metadata.and_then(|m| m.subtitle.as_deref()).unwrap_or(""),
+        );
+        let authors_row = entry(
+            "Authors (comma separated)",
+            &authors
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        let narrators_row = entry(
+            "Narrators (comma separated)",
+            &metadata
+                .and_then(|m| m.narrators.as_ref())
+                .map(|n| n.join(", "))
+                .unwrap_or_default(),
+        );
+        let series_row = entry("Series (Name #Sequence; …)", &format_series(&series));
+        let genres_row = entry(
+            "Genres (comma separated)",
+            &metadata
+                .and_then(|m| m.genres.as_ref())
+                .map(|g| g.join(", "))
+                .unwrap_or_default(),
+        );
+        let tags_row = entry(
+            "Tags (comma separated)",
+            &media
+                .and_then(|m| m.tags.as_ref())
+                .map(|t| t.join(", "))
+                .unwrap_or_default(),
+        );
+
+        let publishing_group = adw::PreferencesGroup::new();
+        publishing_group.set_title("Publishing");
+        details_page.add(&publishing_group);
+        let entry = |title: &str, value: Option<&str>| {
+            let row = adw::EntryRow::new();
+            row.set_title(title);
+            row.set_text(value.unwrap_or(""));
+            publishing_group.add(&row);
+            row
+        };
+        let publisher_row = entry("Publisher", metadata.and_then(|m| m.publisher.as_deref()));
+        let year_row = entry(
+            "Published Year",
+            metadata.and_then(|m| m.published_year.as_deref()),
+        );
+        let language_row = entry("Language", metadata.and_then(|m| m.language.as_deref()));
+        let isbn_row = entry("ISBN", metadata.and_then(|m| m.isbn.as_deref()));
+        let asin_row = entry("ASIN", metadata.and_then(|m| m.asin.as_deref()));
+
+        let description_group = adw::PreferencesGroup::new();
+        description_group.set_title("Description");
+        details_page.add(&description_group);
+        let description_view = gtk::TextView::new();
+        description_view.set_wrap_mode(gtk::WrapMode::WordChar);
+        description_view.set_top_margin(8);
+        description_view.set_bottom_margin(8);
+        description_view.set_left_margin(8);
+        description_view.set_right_margin(8);
+        description_view.buffer().set_text(
+            metadata
+                .and_then(|m| m.description.as_deref())
+                .unwrap_or(""),
+        );
+        let description_scroll = gtk::ScrolledWindow::new();
+        description_scroll.set_hscrollbar_policy(gtk::PolicyType::Never);
+        description_scroll.set_min_content_height(180);
+        description_scroll.add_css_class("card");
+        description_scroll.set_child(Some(&description_view));
+        description_group.add(&description_scroll);
+
+        stack.add_titled_with_icon(
+            &details_page,
+            Some("details"),
+            "Details",
+            "document-edit-symbolic",
+        );
+
+        // Chapters
+        let chapters_page = adw::PreferencesPage::new();
+        let chapters_group = adw::PreferencesGroup::new();
+        chapters_group.set_title("Chapters");
+        chapters_group.set_description(Some(
+            "Start times as H:MM:SS; each chapter ends where the next one starts",
+        ));
+        let add_chapter_btn = gtk::Button::from_icon_name("list-add-symbolic");
+        add_chapter_btn.add_css_class("flat");
+        add_chapter_btn.set_tooltip_text(Some("Add chapter"));
+        chapters_group.set_header_suffix(Some(&add_chapter_btn));
+        chapters_page.add(&chapters_group);
+
+        let original_chapters = media.and_then(|m| m.chapters.clone()).unwrap_or_default();
+        let chapter_rows: Rc<RefCell<Vec<(adw::EntryRow, gtk::Entry)>>> =
+            Rc::new(RefCell::new(Vec::new()));
+        let add_chapter_row = {
+            let group = chapters_group.clone();
+            let rows = chapter_rows.clone();
+            move |title: &str, start: f64| {
+                let row = adw::EntryRow::new();
+                row.set_title("Chapter title");
+                row.set_text(title);
+                let start_entry = gtk::Entry::new();
+                start_entry.set_text(&format_time(start));
+                start_entry.set_width_chars(9);
+                start_entry.set_max_width_chars(9);
+                start_entry.set_valign(gtk::Align::Center);
+                start_entry.set_tooltip_text(Some("Start time"));
+                row.add_suffix(&start_entry);
+                let remove_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+                remove_btn.add_css_class("flat");
+                remove_btn.set_valign(gtk::Align::Center);
+                remove_btn.set_tooltip_text(Some("Remove chapter"));
+                row.add_suffix(&remove_btn);
+
+                let group_rm = group.clone();
+                let rows_rm = rows.clone();
+                let row_rm = row.clone();
+                remove_btn.connect_clicked(move |_| {
+                    group_rm.remove(&row_rm);
+                    rows_rm.borrow_mut().retain(|(r, _)| r != &row_rm);
+                });
+
+                group.add(&row);
+                rows.borrow_mut().push((row, start_entry));
+            }
+        };
+        for chapter in &original_chapters {
+            add_chapter_row(
+                chapter.title.as_deref().unwrap_or(""),
+                chapter.start.unwrap_or(0.0),
+            );
+        }
+        let rows_add = chapter_rows.clone();
+        add_chapter_btn.connect_clicked(move |_| {
+            let last_start = rows_add
+                .borrow()
+                .last()
+                .and_then(|(_, e)| parse_time(&e.text()))
+                .unwrap_or(0.0);
+            let number = rows_add.borrow().len() + 1;
+            add_chapter_row(&format!("Chapter {}", number), last_start);
+        });
+
+        stack.add_titled_with_icon(
+            &chapters_page,
+            Some("chapters"),
+            "Chapters",
+            "view-list-symbolic",
+        );
+
+        // Cover
+        let cover_page = adw::PreferencesPage::new();
+        let cover_group = adw::PreferencesGroup::new();
+        cover_group.set_title("Cover");
+        cover_page.add(&cover_group);
+
+        let cover_preview = gtk::Picture::new();
+        cover_preview.set_content_fit(gtk::ContentFit::Contain);
+        cover_preview.set_size_request(240, 240);
+        cover_preview.set_halign(gtk::Align::Center);
+        cover_preview.set_margin_bottom(12);
+        if let Some(image) = self.imp().detail_cover_image.borrow().as_ref() {
+            if let Some(paintable) = image.paintable() {
+                cover_preview.set_paintable(Some(&paintable));
+            }
+        }
+        cover_group.add(&cover_preview);
+
+        let cover_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
+        let choose_row = adw::ActionRow::new();
+        choose_row.set_title("Upload Image");
+        choose_row.set_subtitle("No file selected");
+        let choose_btn = gtk::Button::with_label("Choose…");
+        choose_btn.set_valign(gtk::Align::Center);
+        choose_row.add_suffix(&choose_btn);
+        choose_row.set_activatable_widget(Some(&choose_btn));
+        cover_group.add(&choose_row);
+
+        let cover_url_row = adw::EntryRow::new();
+        cover_url_row.set_title("Or download from URL");
+        cover_group.add(&cover_url_row);
+
+        let win = self.clone();
+        let cover_file_pick = cover_file.clone();
+        let choose_row_pick = choose_row.clone();
+        let preview_pick = cover_preview.clone();
+        choose_btn.connect_clicked(move |_| {
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some("Images"));
+            filter.add_mime_type("image/*");
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            filters.append(&filter);
+            let file_dialog = gtk::FileDialog::new();
+            file_dialog.set_title("Choose Cover Image");
+            file_dialog.set_filters(Some(&filters));
+
+            let cover_file = cover_file_pick.clone();
+            let choose_row = choose_row_pick.clone();
+            let preview = preview_pick.clone();
+            file_dialog.open(Some(&win), gio::Cancellable::NONE, move |result| {
+                let Ok(file) = result else {
+                    return;
+                };
+                if let Some(name) = file.basename() {
+                    choose_row.set_subtitle(&name.to_string_lossy());
+                }
+                preview.set_file(Some(&file));
+                *cover_file.borrow_mut() = Some(file);
+            });
+        });
+
+        stack.add_titled_with_icon(
+            &cover_page,
+            Some("cover"),
+            "Cover",
+            "image-x-generic-symbolic",
+        );
+
+        // Save
+        let win = self.clone();
+        let dialog_save = dialog.clone();
+        let toasts_save = toasts.clone();
+        let item_id = item.id.clone();
+        save_btn.connect_clicked(move |btn| {
+            // Keep existing author and series ids when names are unchanged so the
+            // server does not create duplicate entities.
+            let new_authors: Vec<Author> = split_list(&authors_row.text())
+                .into_iter()
+                .map(|name| Author {
+                    id: authors
+                        .iter()
+                        .find(|a| a.name == name)
+                        .and_then(|a| a.id.clone()),
+                    name,
+                })
+                .collect();
+            let new_series: Vec<SeriesItem> = parse_series(&series_row.text())
+                .into_iter()
+                .map(|mut s| {
+                    s.id = series
+                        .iter()
+                        .find(|o| o.name == s.name)
+                        .and_then(|o| o.id.clone());
+                    s
+                })
+                .collect();
+            let buffer = description_view.buffer();
+            let description = buffer
+                .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                .to_string();
+
+            let update = MediaUpdate {
+                metadata: Some(MetadataUpdate {
+                    title: Some(title_row.text().trim().to_string()),
+                    subtitle: Some(subtitle_row.text().trim().to_string()),
+                    authors: Some(new_authors),
+                    narrators: Some(split_list(&narrators_row.text())),
+                    series: Some(new_series),
+                    genres: Some(split_list(&genres_row.text())),
+                    published_year: Some(year_row.text().trim().to_string()),
+                    description: Some(description),
+                    publisher: Some(publisher_row.text().trim().to_string()),
+                    isbn: Some(isbn_row.text().trim().to_string()),
+                    asin: Some(asin_row.text().trim().to_string()),
+                    language: Some(language_row.text().trim().to_string()),
+                }),
+                tags: Some(split_list(&tags_row.text())),
+            };
+
+            let mut starts = Vec::new();
+            for (title_entry, start_entry) in chapter_rows.borrow().iter() {
+                match parse_time(&start_entry.text()) {
+                    Some(start) if duration <= 0.0 || start < duration => {
+                        starts.push((start, title_entry.text().trim().to_string()))
+                    }
+                    _ => {
+                        toasts_save.add_toast(adw::Toast::new(&format!(
+                            "Invalid start time for \"{}\"",
+                            title_entry.text()
+                        )));
+                        return;
+                    }
+                }
+            }
+            starts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            let chapters: Vec<Chapter> = starts
+                .iter()
+                .enumerate()
+                .map(|(i, (start, title))| Chapter {
+                    id: Some(i as u32),
+                    start: Some(*start),
+                    end: Some(starts.get(i + 1).map(|n| n.0).unwrap_or(duration)),
+                    title: Some(title.clone()),
+                })
+                .collect();
+            let chapters_changed = chapters.len() != original_chapters.len()
+                || chapters.iter().zip(&original_chapters).any(|(a, b)| {
+                    a.title != b.title
+                        || (a.start.unwrap_or(0.0) - b.start.unwrap_or(0.0)).abs() >= 1.0
+                });
+
+            let cover_path = cover_file.borrow().as_ref().and_then(|f| f.path());
+            let cover_url = cover_url_row.text().trim().to_string();
+
+            btn.set_sensitive(false);
+            let client = win.imp().client.clone();
+            let id = item_id.clone();
+            let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
+            std::thread::spawn(move || {
+                let result = (|| {
+                    client.update_item_media(&id, &update)?;
+                    if chapters_changed {
+                        client.update_item_chapters(&id, &chapters)?;
+                    }
+                    if let Some(path) = cover_path {
+                        let bytes = fs::read(&path).map_err(|e| ApiError::Io(e.to_string()))?;
+                        let (content_type, _) =
+                            gio::content_type_guess(Some(&path), &bytes[..bytes.len().min(512)]);
+                        let mime_type = gio::content_type_get_mime_type(&content_type)
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| "application/octet-stream".to_string());
+                        let filename = path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| "cover".to_string());
+                        client.upload_cover(&id, &filename, &mime_type, &bytes)?;
+                    } else if !cover_url.is_empty() {
+                        client.set_cover_from_url(&id, &cover_url)?;
+                    }
+                    Ok::<(), ApiError>(())
+                })()
+                .map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+
+            let win_recv = win.clone();
+            let dialog_recv = dialog_save.clone();
+            let toasts_recv = toasts_save.clone();
+            let btn_recv = btn.clone();
+            let item_id_recv = item_id.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(())) => {
+                        dialog_recv.close();
+                        let toast = adw::Toast::new("Changes saved");
+                        win_recv.imp().toast_overlay.add_toast(toast);
+                        win_recv.refresh_detail_item(&item_id_recv);
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Save metadata failed: {}", err);
+                        toasts_recv.add_toast(adw::Toast::new(&format!(
+                            "Failed to save changes: {}",
+                            err
+                        )));
+                    }
+                    Err(err) => log::warn!("Save metadata channel error: {}", err),
+                }
+                btn_recv.set_sensitive(true);
+            });
+        });
+
+        dialog.present(Some(self));
+    }
+
+    fn show_match_dialog(&self, item: &LibraryItemExpanded) {
+        let metadata = item.media.as_ref().and_then(|m| m.metadata.as_ref());
+
+        let dialog = adw::Dialog::new();
+        dialog.set_title("Match Book");
+        dialog.set_content_width(720);
+        dialog.set_content_height(680);
+
+        let nav = adw::NavigationView::new();
+        let toasts = adw::ToastOverlay::new();
+        toasts.set_child(Some(&nav));
+        dialog.set_child(Some(&toasts));
+
+        let page = adw::PreferencesPage::new();
+        let query_group = adw::PreferencesGroup::new();
+        query_group.set_description(Some(
+            "Search a metadata provider, then review the differences before applying them",
+        ));
+        page.add(&query_group);
+
+        let provider_row = adw::ComboRow::new();
+        provider_row.set_title("Provider");
+        let provider_model = gtk::StringList::new(&[]);
+        provider_row.set_model(Some(&provider_model));
+        provider_row.set_sensitive(false);
+        query_group.add(&provider_row);
+
+        let title_row = adw::EntryRow::new();
+        title_row.set_title("Title");
+        title_row.set_text(metadata.and_then(|m| m.title.as_deref()).unwrap_or(""));
+        query_group.add(&title_row);
+
+        let author_row = adw::EntryRow::new();
+        author_row.set_title("Author");
+        author_row.set_text(
+            &metadata
+                .and_then(|m| m.authors.as_ref())
+                .and_then(|a| a.first())
+                .map(|a| a.name.clone())
+                .unwrap_or_default(),
+        );
+        query_group.add(&author_row);
+
+        let search_row = adw::ButtonRow::new();
+        search_row.set_title("Search");
+        search_row.set_start_icon_name(Some("system-search-symbolic"));
+        search_row.set_sensitive(false);
+        query_group.add(&search_row);
+
+        let results_group = adw::PreferencesGroup::new();
+        results_group.set_title("Results");
+        results_group.set_visible(false);
+        let spinner = adw::Spinner::new();
+        spinner.set_visible(false);
+        results_group.set_header_suffix(Some(&spinner));
+        let results_list = gtk::ListBox::new();
+        results_list.add_css_class("boxed-list");
+        results_list.set_selection_mode(gtk::SelectionMode::None);
+        results_group.add(&results_list);
+        page.add(&results_group);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        toolbar_view.set_content(Some(&page));
+        nav.add(
+            &adw::NavigationPage::builder()
+                .title("Match Book")
+                .child(&toolbar_view)
+                .build(),
+        );
+
+        // Providers
+        let providers: Rc<RefCell<Vec<MetadataProvider>>> = Rc::new(RefCell::new(Vec::new()));
+        let client = self.imp().client.clone();
+        let (tx, rx) = async_channel::bounded::<Result<Vec<MetadataProvider>, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_book_providers().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        let providers_load = providers.clone();
+        let provider_row_load = provider_row.clone();
+        let search_row_load = search_row.clone();
+        let toasts_load = toasts.clone();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(list)) => {
+                    for provider in &list {
+                        provider_model.append(&provider.text);
+                    }
+                    let has_providers = !list.is_empty();
+                    *providers_load.borrow_mut() = list;
+                    provider_row_load.set_sensitive(has_providers);
+                    search_row_load.set_sensitive(has_providers);
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Load metadata providers failed: {}", err);
+                    toasts_load.add_toast(adw::Toast::new(&format!(
+                        "Failed to load providers: {}",
+                        err
+                    )));
+                }
+                Err(err) => log::warn!("Providers channel error: {}", err),
+            }
+        });
+
+        // Search
+        let win = self.clone();
+        let item = item.clone();
+        let dialog_search = dialog.clone();
+        let toasts_search = toasts.clone();
+        search_row.connect_activated(move |row| {
+            let Some(provider) = providers
+                .borrow()
+                .get(provider_row.selected() as usize)
+                .map(|p| p.value.clone())
+            else {
+                return;
+            };
+            let title = title_row.text().trim().to_string();
+            let author = author_row.text().trim().to_string();
+            if title.is_empty() {
+                toasts_search.add_toast(adw::Toast::new("Enter a title to search for"));
+                return;
+            }
+
+            row.set_sensitive(false);
+            spinner.set_visible(true);
+            results_group.set_visible(true);
+            results_list.remove_all();
+
+            let client = win.imp().client.clone();
+            let (tx, rx) = async_channel::bounded::<Result<Vec<BookMatch>, String>>(1);
+            std::thread::spawn(move || {
+                let result = client
+                    .search_books(&provider, &title, &author)
+                    .map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+
+            let win_recv = win.clone();
+            let item_recv = item.clone();
+            let nav_recv = nav.clone();
+            let dialog_recv = dialog_search.clone();
+            let toasts_recv = toasts_search.clone();
+            let row_recv = row.clone();
+            let spinner_recv = spinner.clone();
+            let results_recv = results_list.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(matches)) => {
+                        if matches.is_empty() {
+                            let empty = adw::ActionRow::new();
+                            empty.set_title("No matches found");
+                            results_recv.append(&empty);
+                        }
+                        for book in matches {
+                            let result_row = adw::ActionRow::new();
+                            result_row.set_title(&pango_escape(
+                                book.title.as_deref().unwrap_or("Untitled"),
+                            ));
+                            let mut subtitle = book.author.clone().unwrap_or_default();
+                            if let Some(year) = book.published_year() {
+                                subtitle = format!("{} · {}", subtitle, year);
+                            }
+                            result_row.set_subtitle(&pango_escape(&subtitle));
+                            result_row.set_activatable(true);
+                            result_row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+
+                            let win_row = win_recv.clone();
+                            let item_row = item_recv.clone();
+                            let nav_row = nav_recv.clone();
+                            let dialog_row = dialog_recv.clone();
+                            let toasts_row = toasts_recv.clone();
+                            result_row.connect_activated(move |_| {
+                                win_row.push_match_diff_page(
+                                    &dialog_row,
+                                    &toasts_row,
+                                    &nav_row,
+                                    &item_row,
+                                    &book,
+                                );
+                            });
+                            results_recv.append(&result_row);
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Metadata search failed: {}", err);
+                        toasts_recv.add_toast(adw::Toast::new(&format!("Search failed: {}", err)));
+                    }
+                    Err(err) => log::warn!("Metadata search channel error: {}", err),
+                }
+                spinner_recv.set_visible(false);
+                row_recv.set_sensitive(true);
+            });
+        });
+
+        dialog.present(Some(self));
+    }
+
+    fn push_match_diff_page(
+        &self,
+        dialog: &adw::Dialog,
+        toasts: &adw::ToastOverlay,
+        nav: &adw::NavigationView,
+        item: &LibraryItemExpanded,
+        book: &BookMatch,
+    ) {
+        let media = item.media.as_ref();
+        let metadata = media.and_then(|m| m.metadata.as_ref());
+        let current =
+            |f: fn(&MetadataExpanded) -> Option<String>| metadata.and_then(f).unwrap_or_default();
+        let joined = |list: Option<&Vec<String>>| list.map(|l| l.join(", ")).unwrap_or_default();
+
+        let series = book.series_items();
+        // (key, label, current value, proposed value)
+        let fields: Vec<(&'static str, &'static str, String, String)> = vec![
+            (
+                "title",
+                "Title",
+                current(|m| m.title.clone()),
+                book.title.clone().unwrap_or_default(),
+            ),
+            (
+                "subtitle",
+                "Subtitle",
+                current(|m| m.subtitle.clone()),
+                book.subtitle.clone().unwrap_or_default(),
+            ),
+            (
+                "authors",
+                "Authors",
+                current(|m| {
+                    m.authors.as_ref().map(|a| {
+                        a.iter()
+                            .map(|a| a.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                }),
+                book.author.clone().unwrap_or_default(),
+            ),
+            (
+                "narrators",
+                "Narrators",
+                current(|m| m.narrators.as_ref().map(|n| n.join(", "))),
+                book.narrator.clone().unwrap_or_default(),
+            ),
+            (
+                "series",
+                "Series",
+                current(|m| m.series.as_deref().map(format_series)),
+                format_series(&series),
+            ),
+            (
+                "genres",
+                "Genres",
+                current(|m| m.genres.as_ref().map(|g| g.join(", "))),
+                joined(book.genres.as_ref()),
+            ),
+            (
+                "publisher",
+                "Publisher",
+                current(|m| m.publisher.clone()),
+                book.publisher.clone().unwrap_or_default(),
+            ),
+            (
+                "published_year",
+                "Published Year",
+                current(|m| m.published_year.clone()),
+                book.published_year().unwrap_or_default(),
+            ),
+            (
+                "language",
+                "Language",
+                current(|m| m.language.clone()),
+                book.language.clone().unwrap_or_default(),
+            ),
+            (
+                "isbn",
+                "ISBN",
+                current(|m| m.isbn.clone()),
+                book.isbn.clone().unwrap_or_default(),
+            ),
+            (
+                "asin",
+                "ASIN",
+                current(|m| m.asin.clone()),
+                book.asin.clone().unwrap_or_default(),
+            ),
+            (
+                "description",
+                "Description",
+                current(|m| m.description.clone()),
+                book.description.clone().unwrap_or_default(),
+            ),
+            (
+                "cover",
+                "Cover",
+                String::new(),
+                book.cover.clone().unwrap_or_default(),
+            ),
+        ];
+
+        let page = adw::PreferencesPage::new();
+        let group = adw::PreferencesGroup::new();
+        group.set_title("Select Fields to Apply");
+        group.set_description(Some(
+            "Changed fields are selected; current values are on the left",
+        ));
+        page.add(&group);
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        group.add(&list);
+
+        let value_label = |text: &str, placeholder: &str| {
+            let label = gtk::Label::new(Some(if text.is_empty() { placeholder } else { text }));
+            if text.is_empty() {
+                label.add_css_class("dim-label");
+            }
+            label.set_wrap(true);
+            label.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            label.set_lines(4);
+            label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            label.set_xalign(0.0);
+            label.set_hexpand(true);
+            label.set_width_chars(20);
+            label.set_max_width_chars(30);
+            label
+        };
+
+        let mut checks: Vec<(&'static str, gtk::CheckButton)> = Vec::new();
+        for (key, label, current_value, proposed) in &fields {
+            if proposed.is_empty() {
+                continue;
+            }
+            let check = gtk::CheckButton::with_label(label);
+            check.set_active(current_value != proposed);
+            check.set_valign(gtk::Align::Start);
+            check.set_width_request(150);
+
+            let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+            row_box.set_margin_top(8);
+            row_box.set_margin_bottom(8);
+            row_box.set_margin_start(12);
+            row_box.set_margin_end(12);
+            row_box.append(&check);
+            let current_text = if *key == "cover" {
+                "Current cover"
+            } else {
+                current_value.as_str()
+            };
+            row_box.append(&value_label(current_text, "Empty"));
+            let arrow = gtk::Image::from_icon_name("go-next-symbolic");
+            arrow.set_valign(gtk::Align::Start);
+            arrow.add_css_class("dim-label");
+            row_box.append(&arrow);
+            row_box.append(&value_label(proposed, ""));
+
+            let row = gtk::ListBoxRow::new();
+            row.set_activatable(false);
+            row.set_child(Some(&row_box));
+            list.append(&row);
+            checks.push((key, check));
+        }
+
+        let apply_btn = gtk::Button::with_label("Apply");
+        apply_btn.add_css_class("suggested-action");
+        let header = adw::HeaderBar::new();
+        header.pack_end(&apply_btn);
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&page));
+
+        let win = self.clone();
+        let dialog_apply = dialog.clone();
+        let toasts_apply = toasts.clone();
+        let item_id = item.id.clone();
+        let page_title = book.title.clone().unwrap_or_else(|| "Match".to_string());
+        let book = book.clone();
+        apply_btn.connect_clicked(move |btn| {
+            let selected = |key: &str| checks.iter().any(|(k, c)| *k == key && c.is_active());
+            let pick = |key: &str, value: &Option<String>| {
+                if selected(key) {
+                    value.clone()
+                } else {
+                    None
+                }
+            };
+
+            let metadata = MetadataUpdate {
+                title: pick("title", &book.title),
+                subtitle: pick("subtitle", &book.subtitle),
+                authors: pick("authors", &book.author).map(|a| {
+                    split_list(&a)
+                        .into_iter()
+                        .map(|name| Author { id: None, name })
+                        .collect()
+                }),
+                narrators: pick("narrators", &book.narrator).map(|n| split_list(&n)),
+                series: selected("series").then(|| series.clone()),
+                genres: if selected("genres") {
+                    book.genres.clone()
+                } else {
+                    None
+                },
+                published_year: pick("published_year", &book.published_year()),
+                description: pick("description", &book.description),
+                publisher: pick("publisher", &book.publisher),
+                isbn: pick("isbn", &book.isbn),
+                asin: pick("asin", &book.asin),
+                language: pick("language", &book.language),
+            };
+            let cover_url = pick("cover", &book.cover);
+            if metadata.is_empty() && cover_url.is_none() {
+                toasts_apply.add_toast(adw::Toast::new("No fields selected"));
+                return;
+            }
+
+            btn.set_sensitive(false);
+            let client = win.imp().client.clone();
+            let id = item_id.clone();
+            let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
+            std::thread::spawn(move || {
+                let result = (|| {
+                    if !metadata.is_empty() {
+                        let update = MediaUpdate {
+                            metadata: Some(metadata),
+                            tags: None,
+                        };
+                        client.update_item_media(&id, &update)?;
+                    }
+                    if let Some(url) = cover_url {
+                        client.set_cover_from_url(&id, &url)?;
+                    }
+                    Ok::<(), ApiError>(())
+                })()
+                .map_err(|e| e.to_string());
+                let _ = tx.send_blocking(result);
+            });
+
+            let win_recv = win.clone();
+            let dialog_recv = dialog_apply.clone();
+            let toasts_recv = toasts_apply.clone();
+            let btn_recv = btn.clone();
+            let item_id_recv = item_id.clone();
+            glib::spawn_future_local(async move {
+                match rx.recv().await {
+                    Ok(Ok(())) => {
+                        dialog_recv.close();
+                        let toast = adw::Toast::new("Match applied");
+                        win_recv.imp().toast_overlay.add_toast(toast);
+                        win_recv.refresh_detail_item(&item_id_recv);
+                    }
+                    Ok(Err(err)) => {
+                        log::warn!("Apply match failed: {}", err);
+                        toasts_recv
+                            .add_toast(adw::Toast::new(&format!("Failed to apply match: {}", err)));
+                    }
+                    Err(err) => log::warn!("Apply match channel error: {}", err),
+                }
+                btn_recv.set_sensitive(true);
+            });
+        });
+
+        nav.push(
+            &adw::NavigationPage::builder()
+                .title(page_title)
+                .child(&toolbar_view)
+                .build(),
+        );
+    }
+
+    // ─── UPLOADS ───────────────────────────────────────────────────────────
+
+    fn show_upload_dialog(&self, paths: Vec<PathBuf>) {
+        if !self.current_user_can(User::can_upload) {
+            return;
+        }
+
+        let dialog = adw::Dialog::new();
+        dialog.set_title("Upload Books");
+        dialog.set_content_width(600);
+        dialog.set_content_height(700);
+
+        let header = adw::HeaderBar::new();
+        let upload_btn = gtk::Button::with_label("Upload");
+        upload_btn.add_css_class("suggested-action");
+        upload_btn.set_sensitive(false);
+        header.pack_end(&upload_btn);
+        let cancel_btn = gtk::Button::with_label("Cancel Upload");
+        cancel_btn.add_css_class("destructive-action");
+        cancel_btn.set_visible(false);
+        header.pack_end(&cancel_btn);
+
+        let page = adw::PreferencesPage::new();
+
+        let target_group = adw::PreferencesGroup::new();
+        target_group.set_title("Destination");
+        page.add(&target_group);
+        let library_row = adw::ComboRow::new();
+        library_row.set_title("Library");
+        let library_model = gtk::StringList::new(&[]);
+        library_row.set_model(Some(&library_model));
+        target_group.add(&library_row);
+        let folder_row = adw::ComboRow::new();
+        folder_row.set_title("Folder");
+        let folder_model = gtk::StringList::new(&[]);
+        folder_row.set_model(Some(&folder_model));
+        target_group.add(&folder_row);
+
+        let book_group = adw::PreferencesGroup::new();
+        book_group.set_title("Book");
+        book_group.set_description(Some("Prefilled from the file tags where available"));
+        page.add(&book_group);
+        let title_row = adw::EntryRow::new();
+        title_row.set_title("Title");
+        book_group.add(&title_row);
+        let author_row = adw::EntryRow::new();
+        author_row.set_title("Author");
+        book_group.add(&author_row);
+        let series_row = adw::EntryRow::new();
+        series_row.set_title("Series");
+        book_group.add(&series_row);
+
+        let files_group = adw::PreferencesGroup::new();
+        files_group.set_title("Files");
+        let add_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let add_files_btn = gtk::Button::from_icon_name("document-open-symbolic");
+        add_files_btn.add_css_class("flat");
+        add_files_btn.set_tooltip_text(Some("Add Files"));
+        let add_folder_btn = gtk::Button::from_icon_name("folder-open-symbolic");
+        add_folder_btn.add_css_class("flat");
+        add_folder_btn.set_tooltip_text(Some("Add Folder"));
+        add_box.append(&add_files_btn);
+        add_box.append(&add_folder_btn);
+        files_group.set_header_suffix(Some(&add_box));
+        let files_list = gtk::ListBox::new();
+        files_list.add_css_class("boxed-list");
+        files_list.set_selection_mode(gtk::SelectionMode::None);
+        files_list.set_placeholder(Some(
+            &adw::StatusPage::builder()
+                .icon_name("folder-music-symbolic")
+                .title("No Files")
+                .description("Drop audio files or folders here")
+                .build(),
+        ));
+        files_group.add(&files_list);
+        page.add(&files_group);
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header);
+        toolbar_view.set_content(Some(&page));
+        let toasts = adw::ToastOverlay::new();
+        toasts.set_child(Some(&toolbar_view));
+        dialog.set_child(Some(&toasts));
+
+        let files: Rc<RefCell<Vec<UploadFile>>> = Rc::new(RefCell::new(Vec::new()));
+        let file_rows: Rc<RefCell<Vec<(adw::ActionRow, gtk::ProgressBar)>>> =
+            Rc::new(RefCell::new(Vec::new()));
+        // Indices of files already on the server, skipped when retrying
+        let uploaded: Rc<RefCell<HashSet<usize>>> = Rc::new(RefCell::new(HashSet::new()));
+        let libraries: Rc<RefCell<Vec<Library>>> = Rc::new(RefCell::new(Vec::new()));
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let update_upload_sensitive = {
+            let upload_btn = upload_btn.clone();
+            let files = files.clone();
+            let libraries = libraries.clone();
+            move || {
+                upload_btn
+                    .set_sensitive(!files.borrow().is_empty() && !libraries.borrow().is_empty())
+            }
+        };
+        let update_upload_sensitive = Rc::new(update_upload_sensitive);
+
+        // Adding files: expanding folders and reading tags happens off the main thread.
+        let add_paths: Rc<dyn Fn(Vec<PathBuf>)> = {
+            let files = files.clone();
+            let file_rows = file_rows.clone();
+            let files_list = files_list.clone();
+            let title_row = title_row.clone();
+            let author_row = author_row.clone();
+            let toasts = toasts.clone();
+            let update_upload_sensitive = update_upload_sensitive.clone();
+            Rc::new(move |paths: Vec<PathBuf>| {
+                if paths.is_empty() {
+                    return;
+                }
+                let (tx, rx) = async_channel::bounded::<(Vec<UploadFile>, FileTags)>(1);
+                std::thread::spawn(move || {
+                    let found = upload::collect_files(&paths);
+                    let tags = upload::guess_tags(&paths, &found);
+                    let _ = tx.send_blocking((found, tags));
+                });
+                let files = files.clone();
+                let file_rows = file_rows.clone();
+                let files_list = files_list.clone();
+                let title_row = title_row.clone();
+                let author_row = author_row.clone();
+                let toasts = toasts.clone();
+                let update_upload_sensitive = update_upload_sensitive.clone();
+                glib::spawn_future_local(async move {
+                    let Ok((found, tags)) = rx.recv().await else {
+                        return;
+                    };
+                    if found.is_empty() {
+                        toasts.add_toast(adw::Toast::new("No supported files found"));
+                        return;
+                    }
+                    if title_row.text().is_empty() {
+                        title_row.set_text(tags.title.as_deref().unwrap_or(""));
+                    }
+                    if author_row.text().is_empty() {
+                        author_row.set_text(tags.author.as_deref().unwrap_or(""));
+                    }
+                    let mut files = files.borrow_mut();
+                    for file in found {
+                        if files.iter().any(|f| f.path == file.path) {
+                            continue;
+                        }
+                        let row = adw::ActionRow::new();
+                        row.set_title(&pango_escape(&file.name));
+                        row.set_subtitle(&glib::format_size(file.size));
+                        let bar = gtk::ProgressBar::new();
+                        bar.set_valign(gtk::Align::Center);
+                        bar.set_width_request(120);
+                        row.add_suffix(&bar);
+                        files_list.append(&row);
+                        file_rows.borrow_mut().push((row, bar));
+                        files.push(file);
+                    }
+                    drop(files);
+                    update_upload_sensitive();
+                });
+            })
+        };
+
+        for (btn, folders) in [(add_files_btn, false), (add_folder_btn, true)] {
+            let win = self.clone();
+            let add_paths = add_paths.clone();
+            btn.connect_clicked(move |_| {
+                let file_dialog = gtk::FileDialog::new();
+                let add_paths = add_paths.clone();
+                let collect = move |list: Result<gio::ListModel, glib::Error>| {
+                    let Ok(list) = list else {
+                        return;
+                    };
+                    let paths = (0..list.n_items())
+                        .filter_map(|i| list.item(i).and_downcast::<gio::File>())
+                        .filter_map(|f| f.path())
+                        .collect();
+                    add_paths(paths);
+                };
+                if folders {
+                    file_dialog.set_title("Add Folders");
+                    file_dialog.select_multiple_folders(
+                        Some(&win),
+                        gio::Cancellable::NONE,
+                        collect,
+                    );
+                } else {
+                    file_dialog.set_title("Add Files");
+                    file_dialog.open_multiple(Some(&win), gio::Cancellable::NONE, collect);
+                }
+            });
+        }
+
+        // Dropping onto the dialog adds files as well.
+        let drop_target = gtk::DropTarget::new(
+            gtk::gdk::FileList::static_type(),
+            gtk::gdk::DragAction::COPY,
+        );
+        let add_paths_drop = add_paths.clone();
+        drop_target.connect_drop(move |_, value, _, _| {
+            let Ok(list) = value.get::<gtk::gdk::FileList>() else {
+                return false;
+            };
+            add_paths_drop(list.files().iter().filter_map(|f| f.path()).collect());
+            true
+        });
+        page.add_controller(drop_target);
+
+        // Libraries and their folders
+        let current_library = self.imp().library_id.borrow().clone();
+        let client = self.imp().client.clone();
+        let (tx, rx) = async_channel::bounded::<Result<Vec<Library>, String>>(1);
+        std::thread::spawn(move || {
+            let result = client.get_libraries().map_err(|e| e.to_string());
+            let _ = tx.send_blocking(result);
+        });
+        let libraries_load = libraries.clone();
+        let library_row_load = library_row.clone();
+        let toasts_load = toasts.clone();
+        let update_upload_sensitive_load = update_upload_sensitive.clone();
+        let win_load = self.clone();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(list)) => {
+                    let books: Vec<Library> = win_load
+                        .accessible_libraries(list)
+                        .into_iter()
+                        .filter(|l| l.media_type.as_deref().unwrap_or("book") == "book")
+                        .filter(|l| l.folders.as_ref().is_some_and(|f| !f.is_empty()))
+                        .collect();
+                    for library in &books {
+                        library_model.append(&library.name);
+                    }
+                    let selected = books
+                        .iter()
+                        .position(|l| l.id == current_library)
+                        .unwrap_or(0);
+                    *libraries_load.borrow_mut() = books;
+                    // Setting the selection triggers the folder refresh below.
+                    library_row_load.set_selected(gtk::INVALID_LIST_POSITION);
+                    library_row_load.set_selected(selected as u32);
+                    update_upload_sensitive_load();
+                }
+                Ok(Err(err)) => {
+                    log::warn!("Upload: load libraries failed: {}", err);
+                    toasts_load.add_toast(adw::Toast::new(&format!(
+                        "Failed to load libraries: {}",
+                        err
+                    )));
+                }
+                Err(err) => log::warn!("Upload libraries channel error: {}", err),
+            }
+        });
+
+        let libraries_sel = libraries.clone();
+        library_row.connect_selected_notify(move |row| {
+            folder_model.splice(0, folder_model.n_items(), &[]);
+            let libraries = libraries_sel.borrow();
+            if let Some(library) = libraries.get(row.selected() as usize) {
+                for folder in library.folders.iter().flatten() {
+                    folder_model.append(folder.full_path.as_deref().unwrap_or(&folder.id));
+                }
+            }
+        });
+
+        // Closing the dialog stops a running upload.
+        let cancel_close = cancel.clone();
+        dialog.connect_closed(move |_| cancel_close.store(true, Ordering::Relaxed));
+
+        let cancel_click = cancel.clone();
+        cancel_btn.connect_clicked(move |btn| {
+            cancel_click.store(true, Ordering::Relaxed);
+            btn.set_sensitive(false);
+        });
+
+        let win = self.clone();
+        let dialog_upload = dialog.clone();
+        upload_btn.connect_clicked(move |btn| {
+            let title = title_row.text().trim().to_string();
+            if title.is_empty() {
+                toasts.add_toast(adw::Toast::new("A title is required"));
+                return;
+            }
+            let libraries = libraries.borrow();
+            let Some(library) = libraries.get(library_row.selected() as usize) else {
+                return;
+            };
+            let Some(folder) = library
+                .folders
+                .as_ref()
+                .and_then(|f| f.get(folder_row.selected() as usize))
+            else {
+                toasts.add_toast(adw::Toast::new("Choose a destination folder"));
+                return;
+            };
+            let request = UploadRequest {
+                library_id: library.id.clone(),
+                folder_id: folder.id.clone(),
+                title: title.clone(),
+                author: author_row.text().trim().to_string(),
+                series: series_row.text().trim().to_string(),
+            };
+            let pending: Vec<(usize, UploadFile)> = files
+                .borrow()
+                .iter()
+                .cloned()
+                .enumerate()
+                .filter(|(i, _)| !uploaded.borrow().contains(i))
+                .collect();
+            if pending.is_empty() {
+                return;
+            }
+
+            for widget in [
+                library_row.upcast_ref::<gtk::Widget>(),
+                folder_row.upcast_ref(),
+                title_row.upcast_ref(),
+                author_row.upcast_ref(),
+                series_row.upcast_ref(),
+                files_group.upcast_ref(),
+            ] {
+                widget.set_sensitive(false);
+            }
+            btn.set_visible(false);
+            cancel_btn.set_sensitive(true);
+            cancel_btn.set_visible(true);
+            cancel.store(false, Ordering::Relaxed);
+            for (i, _) in &pending {
+                if let Some((row, bar)) = file_rows.borrow().get(*i) {
+                    bar.set_fraction(0.0);
+                    row.remove_css_class("error");
+                }
+            }
+
+            let (tx, rx) = async_channel::unbounded::<UploadEvent>();
+            let client = win.imp().client.clone();
+            let cancel_worker = cancel.clone();
+            std::thread::spawn(move || {
+                upload::run_upload(client, request, pending, cancel_worker, tx);
+            });
+
+            let win_recv = win.clone();
+            let dialog_recv = dialog_upload.clone();
+            let toasts_recv = toasts.clone();
+            let btn_recv = btn.clone();
+            let cancel_btn_recv = cancel_btn.clone();
+            let file_rows_recv = file_rows.clone();
+            let files_recv = files.clone();
+            let uploaded_recv = uploaded.clone();
+            let inputs: Vec<gtk::Widget> = vec![
+                library_row.clone().upcast(),
+                folder_row.clone().upcast(),
+                title_row.clone().upcast(),
+                author_row.clone().upcast(),
+                series_row.clone().upcast(),
+                files_group.clone().upcast(),
+            ];
+            let library_id = library.id.clone();
+            glib::spawn_future_local(async move {
+                let mut failed = 0;
+                while let Ok(event) = rx.recv().await {
+                    match event {
+                        UploadEvent::Progress { index, sent, total } => {
+                            if let Some((_, bar)) = file_rows_recv.borrow().get(index) {
+                                bar.set_fraction(sent as f64 / total.max(1) as f64);
+                            }
+                        }
+                        UploadEvent::FileFinished { index, result } => {
+                            let rows = file_rows_recv.borrow();
+                            let Some((row, bar)) = rows.get(index) else {
+                                continue;
+                            };
+                            let size = files_recv
+                                .borrow()
+                                .get(index)
+                                .map(|f| glib::format_size(f.size).to_string())
+                                .unwrap_or_default();
+                            match result {
+                                Ok(()) => {
+                                    bar.set_fraction(1.0);
+                                    row.set_subtitle(&format!("{} · Uploaded", size));
+                                    uploaded_recv.borrow_mut().insert(index);
+                                }
+                                Err(err) => {
+                                    failed += 1;
+                                    row.add_css_class("error");
+                                    row.set_subtitle(&pango_escape(&format!("{} · {}", size, err)));
+                                }
+                            }
+                        }
+                        UploadEvent::Done { cancelled } => {
+                            let all_done =
+                                uploaded_recv.borrow().len() == files_recv.borrow().len();
+                            if all_done {
+                                dialog_recv.close();
+                                win_recv
+                                    .imp()
+                                    .toast_overlay
+                                    .add_toast(adw::Toast::new(&format!("Uploaded “{}”", title)));
+                                if *win_recv.imp().library_id.borrow() == library_id {
+                                    win_recv.load_library();
+                                }
+                                return;
+                            }
+                            let message = if cancelled {
+                                "Upload cancelled".to_string()
+                            } else {
+                                format!("{} file(s) failed to upload", failed)
+                            };
+                            toasts_recv.add_toast(adw::Toast::new(&message));
+                            break;
+                        }
+                    }
+                }
+
+                for widget in &inputs {
+                    widget.set_sensitive(true);
+                }
+                cancel_btn_recv.set_visible(false);
+                btn_recv.set_label(if uploaded_recv.borrow().is_empty() {
+                    "Upload"
+                } else {
+                    "Retry"
+                });
+                btn_recv.set_visible(true);
+            });
+        });
+
+        add_paths(paths);
+        dialog.present(Some(self));
+    }
+
+    // ─── PLAYBACK ──────────────────────────────────────────────────────────
+
+    fn start_playback(&self, item_id: &str) {
+        self.start_playback_at(item_id, -1.0);
+    }
+
+    fn refresh_ambient_for_item(&self, item_id: &str) {
+        let client = self.imp().client.clone();
+        let id = item_id.to_string();
+        let win = self.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                let result = client.download_cover(&id);
+                let _ = tx.send_blocking(result);
+            });
+            if let Ok(Ok(bytes)) = rx.recv().await {
+                let gbytes = glib::Bytes::from(&bytes);
+                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
+                if let Ok(pixbuf) =
+                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
+                {
+                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
+                    win.update_ambient_background_from_texture(&texture);
+                }
+            }
+        });
+    }
+
+    fn start_playback_at(&self, item_id: &str, seek_override: f64) {
+        // Without the server, downloaded books play from disk
+        if self.imp().offline_mode.get() || !self.connectivity().is_online() {
+            match downloads::manifest(item_id) {
+                Some(manifest) => self.start_local_playback(&manifest, seek_override),
+                None => self.show_toast("This book isn't downloaded"),
+            }
+            return;
+        }
+
+        // Stop existing playback, close old session
+        self.stop_playback();
+
+        // Kick off ambient color computation from cover for chapter highlight
+        // and Now Playing background.
+        self.refresh_ambient_for_item(item_id);
+
+        let imp = self.imp();
+        let client = imp.client.clone();
+        let id = item_id.to_string();
+        let win = self.clone();
+
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            let item_id = id.clone();
+
+            std::thread::spawn(move || {
+                let device = DeviceInfo::default();
+                let result = client.start_playback(&item_id, &device);
+                let _ = tx.send_blocking(result);
+            });
+
+            match rx.recv().await {
+                Ok(Ok(session)) => {
+                    log::info!(
+                        "Playback session started: {} - {}",
+                        session.display_title.as_deref().unwrap_or(""),
+                        session.id
+                    );
+
+                    let session_duration = session.duration.unwrap_or(0.0);
+                    let session_current = if seek_override >= 0.0 {
+                        seek_override
+                    } else {
+                        session.current_time.unwrap_or(0.0)
+                    };
+
+                    *win.imp().session_id.borrow_mut() = Some(session.id.clone());
+                    *win.imp().current_session.borrow_mut() = Some(session.clone());
+                    *win.imp().current_item_id.borrow_mut() = Some(id.clone());
+
+                    // Update player bar info
+                    win.update_player_info(
+                        session.display_title.as_deref().unwrap_or("Unknown Book"),
+                        session.display_author.as_deref().unwrap_or(""),
+                        session_duration,
+                        session_current,
+                    );
+
+                    // Load cover into player bar
+                    let client = win.imp().client.clone();
+                    let player_cover = win.imp().player_cover.clone();
+                    let item_id = id.clone();
+                    glib::spawn_future_local(async move {
+                        let (tx, rx) = async_channel::bounded(1);
+                        std::thread::spawn(move || {
+                            let result = client.download_cover(&item_id);
+                            let _ = tx.send_blocking(result);
+                        });
+                        if let Ok(Ok(bytes)) = rx.recv().await {
+                            let gbytes = glib::Bytes::from(&bytes);
+                            let stream = gio::MemoryInputStream::from_bytes(&gbytes);
+                            if let Ok(pixbuf) = gtk::gdk_pixbuf::Pixbuf::from_stream(
+                                &stream,
+                                gio::Cancellable::NONE,
+                            ) {
+                                let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
+                                player_cover.set_paintable(Some(&texture));
+                            }
+                        }
+                    });
+
+                    // Store chapters for MPRIS next/previous navigation
+                    let chapters: Vec<(f64, f64)> = session
+                        .chapters
+                        .as_ref()
+                        .map(|cs| {
+                            cs.iter()
+                                .map(|c| (c.start.unwrap_or(0.0), c.end.unwrap_or(0.0)))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    *win.imp().current_chapters.borrow_mut() = chapters;
+                    *win.imp().current_chapter_titles.borrow_mut() = session
+                        .chapters
+                        .as_ref()
+                        .map(|cs| {
+                            cs.iter()
+                                .map(|c| c.title.clone().unwrap_or_default())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    win.imp().current_chapter_index.set(None);
+                    win.update_seek_marks();
 
-                    if is_finished || listen_pos >= end {
-                        indicator.add_css_class("completed");
-                    } else if listen_pos >= start && listen_pos < end {
-                        indicator.add_css_class("playing");
-                        row.add_css_class("chapter-row-playing");
-                    } else {
-                        indicator.add_css_class("unplayed");
+                    win.reveal_player();
+                    win.play_audio(&session, session_current);
+                    win.start_progress_timer();
+                    win.start_sync_timer();
+
+                    // Publish to MPRIS
+                    win.update_mpris_metadata(
+                        session.display_title.as_deref().unwrap_or("Unknown Book"),
+                        session.display_author.as_deref().unwrap_or(""),
+                        &id,
+                        session_duration,
+                    );
+                    win.update_mpris_status();
+                }
+                Ok(Err(e)) => {
+                    log::error!("Failed to start playback: {}", e);
+                }
+                Err(_) => {
+                    log::error!("Channel error");
+                }
+            }
+        });
+    }
+
+    fn stop_playback(&self) {
+        use gstreamer::prelude::ElementExt;
+        let imp = self.imp();
+
+        // Stop timers
+        if let Some(id) = imp.progress_source.borrow_mut().take() {
+            id.remove();
+        }
+        if let Some(id) = imp.sync_source.borrow_mut().take() {
+            id.remove();
+        }
+
+        // Close session on server, or keep the position for later offline
+        let ct = *imp.current_time.borrow();
+        let dur = *imp.duration.borrow();
+        if let Some(session_id) = imp.session_id.borrow().as_ref() {
+            let client = imp.client.clone();
+            let sid = session_id.clone();
+            if self.connectivity().is_online() {
+                std::thread::spawn(move || {
+                    let _ = client.close_session(&sid, ct, dur);
+                });
+            } else if let Some(item_id) = imp.current_item_id.borrow().clone() {
+                self.keep_pending_sync(item_id, sid, ct, dur);
+            }
+        } else if let Some(item_id) = imp.current_item_id.borrow().clone() {
+            // Played from disk: there is no session, only progress to save
+            self.keep_pending_sync(item_id, String::new(), ct, dur);
+            if self.connectivity() == Connectivity::Online && !imp.offline_mode.get() {
+                self.flush_pending_sync();
+            }
+        }
+        *imp.session_id.borrow_mut() = None;
+        *imp.current_session.borrow_mut() = None;
+        if let Some(id) = imp.reconnect_source.borrow_mut().take() {
+            id.remove();
+        }
+        imp.reconnect_attempts.set(0);
+        imp.stalled_ticks.set(0);
+        self.set_reconnecting(false);
+
+        // Stop GStreamer
+        if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
+            let _ = pipeline.set_state(gstreamer::State::Null);
+        }
+        *imp.bus_guard.borrow_mut() = None;
+        *imp.pipeline.borrow_mut() = None;
+        imp.is_playing.set(false);
+        *imp.current_item_id.borrow_mut() = None;
+        imp.current_chapters.borrow_mut().clear();
+        imp.current_chapter_titles.borrow_mut().clear();
+        imp.current_chapter_index.set(None);
+        imp.local_files.borrow_mut().clear();
+        imp.local_file_index.set(0);
+        imp.position_scale.clear_marks();
+        self.update_play_pause_icon(false);
+        self.refresh_detail_play_button();
+        self.update_mpris_status();
+    }
+
+    fn play_audio(&self, session: &PlaybackSession, start_position: f64) {
+        let imp = self.imp();
+
+        let stream_url = session
+            .audio_tracks
+            .as_ref()
+            .and_then(|tracks| tracks.first())
+            .and_then(|t| t.content_url.as_ref())
+            .map(|url| imp.client.audio_stream_url(url));
+
+        let stream_url = match stream_url {
+            Some(url) => url,
+            None => {
+                log::error!("Audio stream URL not found");
+                return;
+            }
+        };
+
+        log::info!("Starting audio stream");
+        self.play_uri(&stream_url, start_position);
+    }
+
+    /// Plays a download file by file, mapping book positions onto them.
+    fn start_local_playback(&self, manifest: &downloads::DownloadManifest, seek_override: f64) {
+        let dir = downloads::item_dir(&manifest.item_id);
+        let mut files = Vec::new();
+        let mut start = 0.0;
+        for file in &manifest.files {
+            let path = dir.join(&file.filename);
+            match glib::filename_to_uri(&path, None) {
+                Ok(uri) => files.push((start, uri.to_string())),
+                Err(e) => {
+                    log::error!("Invalid download path {}: {}", path.display(), e);
+                    return;
+                }
+            }
+            start += file.duration;
+        }
+        if files.is_empty() {
+            self.show_toast("This download has no audio files");
+            return;
+        }
+
+        self.stop_playback();
+        let imp = self.imp();
+        let position = if seek_override >= 0.0 {
+            seek_override
+        } else {
+            self.cached_item(&manifest.item_id)
+                .and_then(|item| item.user_media_progress)
+                .and_then(|progress| progress.current_time)
+                .unwrap_or(0.0)
+        };
+        log::info!("Playing {} from disk at {:.0}s", manifest.item_id, position);
+
+        *imp.current_item_id.borrow_mut() = Some(manifest.item_id.clone());
+        self.update_player_info(
+            &manifest.title,
+            &manifest.author,
+            manifest.duration,
+            position,
+        );
+        let cover = dir.join("cover.jpg");
+        match gtk::gdk::Texture::from_filename(&cover) {
+            Ok(texture) => imp.player_cover.set_paintable(Some(&texture)),
+            Err(_) => imp
+                .player_cover
+                .set_icon_name(Some("audio-x-generic-symbolic")),
+        }
+        self.update_seek_marks();
+
+        self.reveal_player();
+        *imp.local_files.borrow_mut() = files;
+        let (index, offset) = self.locate_local_file(position).unwrap_or((0, 0.0));
+        imp.local_file_index.set(index);
+        *imp.current_time.borrow_mut() = position;
+        let uri = imp.local_files.borrow()[index].1.clone();
+        self.play_uri(&uri, offset);
+        self.start_progress_timer();
+        self.start_sync_timer();
+        self.update_mpris_metadata(
+            &manifest.title,
+            &manifest.author,
+            &manifest.item_id,
+            manifest.duration,
+        );
+        self.update_mpris_status();
+    }
+
+    /// Where the playing download file starts in the book; 0 while streaming.
+    fn local_file_offset(&self) -> f64 {
+        let imp = self.imp();
+        imp.local_files
+            .borrow()
+            .get(imp.local_file_index.get())
+            .map(|(start, _)| *start)
+            .unwrap_or(0.0)
+    }
+
+    /// The download file holding a book position, and the position in it
+    fn locate_local_file(&self, position: f64) -> Option<(usize, f64)> {
+        let files = self.imp().local_files.borrow();
+        let index = files
+            .iter()
+            .rposition(|(start, _)| *start <= position)
+            .unwrap_or(0);
+        files
+            .get(index)
+            .map(|(start, _)| (index, (position - start).max(0.0)))
+    }
+
+    /// Swaps the pipeline over to another file of the download, keeping
+    /// whether it plays.
+    fn play_local_file(&self, index: usize, offset: f64) {
+        use gstreamer::prelude::ElementExt;
+        let imp = self.imp();
+        let Some(uri) = imp
+            .local_files
+            .borrow()
+            .get(index)
+            .map(|(_, uri)| uri.clone())
+        else {
+            return;
+        };
+        if let Some(pipeline) = imp.pipeline.borrow_mut().take() {
+            let _ = pipeline.set_state(gstreamer::State::Null);
+        }
+        *imp.bus_guard.borrow_mut() = None;
+        imp.local_file_index.set(index);
+        imp.start_paused.set(!imp.is_playing.get());
+        self.play_uri(&uri, offset);
+    }
+
+    /// Continues with the next file when one of a download ends; false
+    /// after the last one.
+    fn play_next_local_file(&self) -> bool {
+        let imp = self.imp();
+        let next = imp.local_file_index.get() + 1;
+        if next >= imp.local_files.borrow().len() {
+            return false;
+        }
+        // Not from within the bus watch of the pipeline being replaced
+        let win = self.clone();
+        glib::idle_add_local_once(move || win.play_local_file(next, 0.0));
+        true
+    }
+
+    fn play_uri(&self, stream_url: &str, start_position: f64) {
+        use gstreamer::prelude::*;
+        let imp = self.imp();
+
+        let playbin = gstreamer::ElementFactory::make("playbin3")
+            .property("uri", stream_url)
+            .build()
+            .or_else(|_| {
+                gstreamer::ElementFactory::make("playbin")
+                    .property("uri", stream_url)
+                    .build()
+            })
+            .expect("Failed to create playbin");
+
+        // Buffering configuration
+        if playbin.find_property("buffer-duration").is_some() {
+            playbin.set_property("buffer-duration", 15_000_000_000i64);
+        }
+        if playbin.find_property("buffer-size").is_some() {
+            playbin.set_property("buffer-size", 10 * 1024 * 1024i32);
+        }
+
+        // Insert scaletempo so rate changes preserve pitch
+        if playbin.find_property("audio-filter").is_some() {
+            if let Ok(scaletempo) = gstreamer::ElementFactory::make("scaletempo").build() {
+                playbin.set_property("audio-filter", &scaletempo);
+            } else {
+                log::warn!("scaletempo element unavailable; rate changes will alter pitch");
+            }
+        }
+
+        // Authenticate the HTTP source with a header instead of a token in
+        // the URL, which would show up in GStreamer's debug output
+        if stream_url.starts_with("http://") || stream_url.starts_with("https://") {
+            let client = imp.client.clone();
+            let server_url = client.server_url();
+            let connection = client.connection();
+            playbin.connect("source-setup", false, move |args| {
+                let source = args.get(1)?.get::<gstreamer::Element>().ok()?;
+                // Trust what the API client trusts
+                if source.find_property("tls-database").is_some() {
+                    if let Some(database) = trust::tls_database(&server_url) {
+                        source.set_property("tls-database", &database);
+                    }
+                }
+                if source.find_property("tls-interaction").is_some() {
+                    if let Some(interaction) = connection.tls_interaction() {
+                        source.set_property("tls-interaction", &interaction);
+                    }
+                }
+                if let Some(proxy) = connection.proxy.as_deref() {
+                    if source.find_property("proxy").is_some() {
+                        source.set_property("proxy", proxy);
+                    }
+                }
+                if source.find_property("extra-headers").is_none() {
+                    log::warn!("HTTP source can't send headers; the stream may be refused");
+                    return None;
+                }
+                let mut headers = gstreamer::Structure::builder("extra-headers");
+                for header in &connection.headers {
+                    headers = headers.field(header.name.as_str(), header.value.as_str());
+                }
+                if let Some(basic) = connection.basic_authorization() {
+                    headers = headers.field("Proxy-Authorization", basic);
+                }
+                if let Some(authorization) = client.authorization_header() {
+                    headers = headers.field("Authorization", authorization);
+                }
+                source.set_property("extra-headers", headers.build());
+                None
+            });
+        }
+
+        let bus = playbin.bus().unwrap();
+        let pipeline_weak = playbin.downgrade();
+        let seek_target = if start_position > 1.0 {
+            Some(start_position)
+        } else {
+            None
+        };
+        let initial_seek_done = Rc::new(Cell::new(false));
+        let initial_seek_clone = initial_seek_done.clone();
+        let is_buffering = Rc::new(Cell::new(false));
+        let is_buffering_clone = is_buffering.clone();
+        let is_playing = imp.is_playing.clone();
+        let win_weak = self.downgrade();
+        let win_for_rate = self.clone();
+
+        let guard = bus
+            .add_watch_local(move |_, msg| {
+                use gstreamer::MessageView;
+                match msg.view() {
+                    MessageView::AsyncDone(_) => {
+                        if !initial_seek_clone.get() {
+                            initial_seek_clone.set(true);
+                            if let Some(win) = win_weak.upgrade() {
+                                win.set_reconnecting(false);
+                            }
+                            if let Some(pipeline) = pipeline_weak.upgrade() {
+                                let target_pos = seek_target
+                                    .map(|p| gstreamer::ClockTime::from_seconds(p as u64))
+                                    .unwrap_or(gstreamer::ClockTime::ZERO);
+                                let rate = win_for_rate.imp().current_speed.get();
+                                let _ = pipeline.seek(
+                                    rate,
+                                    gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+                                    gstreamer::SeekType::Set,
+                                    target_pos,
+                                    gstreamer::SeekType::None,
+                                    gstreamer::ClockTime::ZERO,
+                                );
+                                if let Some(pos) = seek_target {
+                                    log::info!(
+                                        "Seeked to position: {:.0}s (rate {:.2})",
+                                        pos,
+                                        rate
+                                    );
+                                } else if rate != 1.0 {
+                                    log::info!("Applied rate {:.2}× on start", rate);
+                                }
+                            }
+                        }
+                        glib::ControlFlow::Continue
+                    }
+                    MessageView::Buffering(buffering) => {
+                        let percent = buffering.percent();
+                        log::debug!("Buffering: {}%", percent);
+                        if let Some(pipeline) = pipeline_weak.upgrade() {
+                            if percent < 100 {
+                                if !is_buffering_clone.get() {
+                                    let _ = pipeline.set_state(gstreamer::State::Paused);
+                                    is_buffering_clone.set(true);
+                                }
+                            } else if is_buffering_clone.get() {
+                                let _ = pipeline.set_state(gstreamer::State::Playing);
+                                is_buffering_clone.set(false);
+                            }
+                        }
+                        glib::ControlFlow::Continue
                     }
-
-                    let num_label = gtk::Label::new(Some(&format!("{:02}", i + 1)));
-                    num_label.add_css_class("dim-label");
-                    num_label.add_css_class("monospace");
-                    num_label.add_css_class("caption");
-
-                    prefix_box.append(&indicator);
-                    prefix_box.append(&num_label);
-                    row.add_prefix(&prefix_box);
-
-                    imp.chapter_indicators
-                        .borrow_mut()
-                        .push((start, end, indicator.clone(), row.clone()));
-
-                    let ch_play = gtk::Button::from_icon_name("media-playback-start-symbolic");
-                    ch_play.add_css_class("flat");
-                    ch_play.add_css_class("circular");
-                    ch_play.set_valign(gtk::Align::Center);
-                    let ch_start = start;
-                    let item_id = item.id.clone();
-                    let win = self.clone();
-                    ch_play.connect_clicked(move |_| {
-                        win.jump_to_chapter(&item_id, ch_start);
-                    });
-                    row.add_suffix(&ch_play);
-                    row.set_activatable_widget(Some(&ch_play));
-
-                    chapters_group.add(&row);
+                    MessageView::Error(err) => {
+                        log::error!("GStreamer error: {} - {:?}", err.error(), err.debug());
+                        if let Some(win) = win_weak.upgrade() {
+                            // Network and stream errors are worth a reconnect;
+                            // anything else (missing plugins, …) won't improve
+                            let error = err.error();
+                            if error.is::<gstreamer::ResourceError>()
+                                || error.is::<gstreamer::StreamError>()
+                            {
+                                win.recover_stream(&error.to_string());
+                            } else {
+                                win.give_up_stream(&error.to_string());
+                            }
+                        }
+                        glib::ControlFlow::Break
+                    }
+                    MessageView::Eos(_) => {
+                        if win_weak
+                            .upgrade()
+                            .is_some_and(|win| win.play_next_local_file())
+                        {
+                            return glib::ControlFlow::Break;
+                        }
+                        log::info!("Audio stream ended");
+                        is_playing.set(false);
+                        if let Some(win) = win_weak.upgrade() {
+                            win.update_play_pause_icon(false);
+                            win.refresh_detail_play_button();
+                            win.notify_book_finished();
+                            win.play_next_in_queue();
+                        }
+                        glib::ControlFlow::Break
+                    }
+                    _ => glib::ControlFlow::Continue,
                 }
-                detail_box.append(&chapters_group);
-            }
-        }
-
-        // Bookmarks section (populated asynchronously)
-        let bookmarks_section = gtk::Box::new(gtk::Orientation::Vertical, 12);
-        bookmarks_section.set_visible(false);
-        let sep = gtk::Separator::new(gtk::Orientation::Horizontal);
-        bookmarks_section.append(&sep);
-        let bm_title = gtk::Label::new(Some("Bookmarks"));
-        bm_title.add_css_class("title-4");
-        bm_title.set_halign(gtk::Align::Start);
-        bookmarks_section.append(&bm_title);
-        let bookmarks_group = adw::PreferencesGroup::new();
-        bookmarks_section.append(&bookmarks_group);
-        detail_box.append(&bookmarks_section);
+            })
+            .expect("Failed to add bus watch");
 
-        *imp.bookmarks_group.borrow_mut() = Some(bookmarks_group);
-        *imp.bookmarks_section.borrow_mut() = Some(bookmarks_section);
+        *imp.bus_guard.borrow_mut() = Some(guard);
 
-        self.load_bookmarks(&item.id);
+        // A stream reopened after resume keeps its paused state
+        let playing = !imp.start_paused.replace(false);
+        let _ = playbin.set_state(if playing {
+            gstreamer::State::Playing
+        } else {
+            gstreamer::State::Paused
+        });
+        imp.is_playing.set(playing);
+        self.update_play_pause_icon(playing);
+        self.refresh_detail_play_button();
+        *imp.pipeline.borrow_mut() = Some(playbin);
     }
 
-    // ─── PLAYBACK ──────────────────────────────────────────────────────────
+    // ─── STREAM RECOVERY ───────────────────────────────────────────────────
 
-    fn start_playback(&self, item_id: &str) {
-        self.start_playback_at(item_id, -1.0);
+    const MAX_RECONNECT_ATTEMPTS: u32 = 6;
+    const STALL_TIMEOUT_SECS: u32 = 30;
+
+    fn set_reconnecting(&self, reconnecting: bool) {
+        let imp = self.imp();
+        imp.reconnecting.set(reconnecting);
+        imp.player_reconnecting.set_visible(reconnecting);
+        imp.player_author.set_visible(!reconnecting);
     }
 
-    fn refresh_ambient_for_item(&self, item_id: &str) {
-        let client = self.imp().client.clone();
-        let id = item_id.to_string();
+    /// Drops a dead or stalled stream and schedules reopening it at the
+    /// last known position, backing off between attempts.
+    fn recover_stream(&self, reason: &str) {
+        use gstreamer::prelude::ElementExt;
+        let imp = self.imp();
+        if imp.current_item_id.borrow().is_none() || imp.reconnect_source.borrow().is_some() {
+            return;
+        }
+        if imp.current_session.borrow().is_none() {
+            // Played from disk, there is nothing to reconnect to
+            self.give_up_stream(reason);
+            return;
+        }
+        let attempt = imp.reconnect_attempts.get() + 1;
+        if attempt > Self::MAX_RECONNECT_ATTEMPTS {
+            self.give_up_stream(reason);
+            return;
+        }
+        imp.reconnect_attempts.set(attempt);
+        imp.stalled_ticks.set(0);
+
+        // The pipeline stays so the progress timer keeps running
+        if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
+            let _ = pipeline.set_state(gstreamer::State::Null);
+        }
+        self.set_reconnecting(true);
+
+        let delay = 2u32.pow(attempt - 1).min(30);
+        log::warn!(
+            "Stream interrupted ({}), reconnecting in {}s (attempt {}/{})",
+            reason,
+            delay,
+            attempt,
+            Self::MAX_RECONNECT_ATTEMPTS
+        );
         let win = self.clone();
-        glib::spawn_future_local(async move {
-            let (tx, rx) = async_channel::bounded(1);
-            std::thread::spawn(move || {
-                let result = client.download_cover(&id);
-                let _ = tx.send_blocking(result);
-            });
-            if let Ok(Ok(bytes)) = rx.recv().await {
-                let gbytes = glib::Bytes::from(&bytes);
-                let stream = gio::MemoryInputStream::from_bytes(&gbytes);
-                if let Ok(pixbuf) =
-                    gtk::gdk_pixbuf::Pixbuf::from_stream(&stream, gio::Cancellable::NONE)
-                {
-                    let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
-                    win.update_ambient_background_from_texture(&texture);
-                }
-            }
+        let source = glib::timeout_add_seconds_local_once(delay, move || {
+            win.imp().reconnect_source.take();
+            win.reopen_stream();
         });
+        *imp.reconnect_source.borrow_mut() = Some(source);
     }
 
-    fn start_playback_at(&self, item_id: &str, seek_override: f64) {
-        // Stop existing playback, close old session
-        self.stop_playback();
-
-        // Kick off ambient color computation from cover for chapter highlight
-        // and Now Playing background.
-        self.refresh_ambient_for_item(item_id);
-
+    /// Rebuilds the pipeline at the current position, starting a new
+    /// playback session if the server no longer knows the old one.
+    fn reopen_stream(&self) {
         let imp = self.imp();
+        let Some(item_id) = imp.current_item_id.borrow().clone() else {
+            return;
+        };
+        let position = *imp.current_time.borrow();
+        let duration = *imp.duration.borrow();
+        let session_id = imp.session_id.borrow().clone();
         let client = imp.client.clone();
-        let id = item_id.to_string();
         let win = self.clone();
 
         glib::spawn_future_local(async move {
             let (tx, rx) = async_channel::bounded(1);
-            let item_id = id.clone();
-
+            let id = item_id.clone();
             std::thread::spawn(move || {
-                let device = DeviceInfo::default();
-                let result = client.start_playback(&item_id, &device);
+                let still_open = match session_id {
+                    Some(sid) => match client.sync_session(&sid, position, duration) {
+                        Ok(()) => Ok(true),
+                        Err(ApiError::Server(e)) => {
+                            log::info!("Playback session expired ({}), starting a new one", e);
+                            Ok(false)
+                        }
+                        Err(e) => Err(e),
+                    },
+                    None => Ok(false),
+                };
+                let result = still_open.and_then(|open| {
+                    if open {
+                        Ok(None)
+                    } else {
+                        client.start_playback(&id, &DeviceInfo::default()).map(Some)
+                    }
+                });
                 let _ = tx.send_blocking(result);
             });
 
-            match rx.recv().await {
-                Ok(Ok(session)) => {
-                    log::info!(
-                        "Playback session started: {} - {}",
-                        session.display_title.as_deref().unwrap_or(""),
-                        session.id
-                    );
-
-                    let session_duration = session.duration.unwrap_or(0.0);
-                    let session_current = if seek_override >= 0.0 {
-                        seek_override
-                    } else {
-                        session.current_time.unwrap_or(0.0)
+            let Ok(result) = rx.recv().await else {
+                return;
+            };
+            let imp = win.imp();
+            if imp.current_item_id.borrow().as_deref() != Some(item_id.as_str())
+                || !imp.reconnecting.get()
+            {
+                return;
+            }
+            match result {
+                Ok(new_session) => {
+                    if let Some(session) = new_session {
+                        *imp.session_id.borrow_mut() = Some(session.id.clone());
+                        *imp.current_session.borrow_mut() = Some(session);
+                    }
+                    let Some(session) = imp.current_session.borrow().clone() else {
+                        return;
                     };
-
-                    *win.imp().session_id.borrow_mut() = Some(session.id.clone());
-                    *win.imp().current_item_id.borrow_mut() = Some(id.clone());
-
-                    // Update player bar info
-                    win.update_player_info(
-                        session.display_title.as_deref().unwrap_or("Unknown Book"),
-                        session.display_author.as_deref().unwrap_or(""),
-                        session_duration,
-                        session_current,
-                    );
-
-                    // Load cover into player bar
-                    let client = win.imp().client.clone();
-                    let player_cover = win.imp().player_cover.clone();
-                    let item_id = id.clone();
-                    glib::spawn_future_local(async move {
-                        let (tx, rx) = async_channel::bounded(1);
-                        std::thread::spawn(move || {
-                            let result = client.download_cover(&item_id);
-                            let _ = tx.send_blocking(result);
-                        });
-                        if let Ok(Ok(bytes)) = rx.recv().await {
-                            let gbytes = glib::Bytes::from(&bytes);
-                            let stream = gio::MemoryInputStream::from_bytes(&gbytes);
-                            if let Ok(pixbuf) = gtk::gdk_pixbuf::Pixbuf::from_stream(
-                                &stream,
-                                gio::Cancellable::NONE,
-                            ) {
-                                let texture = gtk::gdk::Texture::for_pixbuf(&pixbuf);
-                                player_cover.set_paintable(Some(&texture));
-                            }
-                        }
-                    });
-
-                    // Store chapters for MPRIS next/previous navigation
-                    let chapters: Vec<(f64, f64)> = session
-                        .chapters
-                        .as_ref()
-                        .map(|cs| {
-                            cs.iter()
-                                .map(|c| (c.start.unwrap_or(0.0), c.end.unwrap_or(0.0)))
-                                .collect()
-                        })
-                        .unwrap_or_default();
-                    *win.imp().current_chapters.borrow_mut() = chapters;
-                    win.update_seek_marks();
-
-                    win.reveal_player();
-                    win.play_audio(&session, session_current);
-                    win.start_progress_timer();
-                    win.start_sync_timer();
-
-                    // Publish to MPRIS
-                    win.update_mpris_metadata(
-                        session.display_title.as_deref().unwrap_or("Unknown Book"),
-                        session.display_author.as_deref().unwrap_or(""),
-                        &id,
-                        session_duration,
-                    );
-                    win.update_mpris_status();
+                    imp.start_paused.set(!imp.is_playing.get());
+                    imp.seek_settle_target.set(position);
+                    imp.seek_settle_ticks.set(5);
+                    win.play_audio(&session, position);
                 }
-                Ok(Err(e)) => {
-                    log::error!("Failed to start playback: {}", e);
+                Err(ApiError::Auth(e)) => {
+                    win.give_up_stream(&format!("signed out by the server ({})", e));
                 }
-                Err(_) => {
-                    log::error!("Channel error");
+                Err(e) => {
+                    log::warn!("Reconnect failed: {}", e);
+                    win.recover_stream(&e.to_string());
                 }
             }
         });
     }
 
-    fn stop_playback(&self) {
-        use gstreamer::prelude::ElementExt;
+    /// Stops playback after an unrecoverable error, offering to retry
+    /// from the same position.
+    fn give_up_stream(&self, reason: &str) {
         let imp = self.imp();
+        let item_id = imp.current_item_id.borrow().clone();
+        let position = *imp.current_time.borrow();
+        log::warn!("Playback stopped: {}", reason);
+        self.stop_playback();
 
-        // Stop timers
-        if let Some(id) = imp.progress_source.borrow_mut().take() {
-            id.remove();
-        }
-        if let Some(id) = imp.sync_source.borrow_mut().take() {
-            id.remove();
-        }
-
-        // Close session on server
-        if let Some(session_id) = imp.session_id.borrow().as_ref() {
-            let client = imp.client.clone();
-            let sid = session_id.clone();
-            let ct = *imp.current_time.borrow();
-            let dur = *imp.duration.borrow();
-            std::thread::spawn(move || {
-                let _ = client.close_session(&sid, ct, dur);
+        let toast = adw::Toast::new("Playback stopped: couldn't reach the audio stream");
+        toast.set_timeout(0);
+        if let Some(item_id) = item_id {
+            toast.set_button_label(Some("Retry"));
+            let win = self.clone();
+            toast.connect_button_clicked(move |_| {
+                win.start_playback_at(&item_id, position);
             });
         }
-        *imp.session_id.borrow_mut() = None;
+        imp.toast_overlay.add_toast(toast);
+    }
 
-        // Stop GStreamer
-        if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
-            let _ = pipeline.set_state(gstreamer::State::Null);
+    // ─── SUSPEND ───────────────────────────────────────────────────────────
+
+    /// Keeps the system from suspending while a book plays, unless turned
+    /// off in the preferences.
+    fn update_suspend_inhibit(&self, playing: bool) {
+        let imp = self.imp();
+        let Some(app) = self.application() else {
+            return;
+        };
+        let inhibit = playing && PowerSettings::load().inhibit_suspend;
+        let cookie = imp.suspend_inhibit_cookie.get();
+        if inhibit && cookie == 0 {
+            let cookie = app.inhibit(
+                Some(self),
+                gtk::ApplicationInhibitFlags::SUSPEND,
+                Some("Playing an audiobook"),
+            );
+            imp.suspend_inhibit_cookie.set(cookie);
+        } else if !inhibit && cookie != 0 {
+            app.uninhibit(cookie);
+            imp.suspend_inhibit_cookie.set(0);
         }
-        *imp.bus_guard.borrow_mut() = None;
-        *imp.pipeline.borrow_mut() = None;
-        imp.is_playing.set(false);
-        *imp.current_item_id.borrow_mut() = None;
-        imp.current_chapters.borrow_mut().clear();
-        imp.position_scale.clear_marks();
-        self.update_play_pause_icon(false);
-        self.refresh_detail_play_button();
-        self.update_mpris_status();
     }
 
-    fn play_audio(&self, session: &PlaybackSession, start_position: f64) {
-        use gstreamer::prelude::*;
+    /// Applies a changed suspend preference to the current playback.
+    pub fn refresh_suspend_inhibit(&self) {
+        self.update_suspend_inhibit(self.imp().is_playing.get());
+    }
+
+    /// Pauses and closes the session with the current position, remembering
+    /// the book so it can be reopened after resume. The stream's HTTP
+    /// connection doesn't survive sleep, so it is not kept.
+    fn prepare_for_sleep(&self, monitor: &Rc<power::SleepMonitor>) {
         let imp = self.imp();
+        let Some(item_id) = imp.current_item_id.borrow().clone() else {
+            monitor.release_lock();
+            return;
+        };
+        let position = *imp.current_time.borrow();
+        let duration = *imp.duration.borrow();
+        *imp.resume_after_sleep.borrow_mut() = Some((item_id, position, imp.is_playing.get()));
 
-        let stream_url = session
-            .audio_tracks
-            .as_ref()
-            .and_then(|tracks| tracks.first())
-            .and_then(|t| t.content_url.as_ref())
-            .map(|url| imp.client.audio_stream_url(url));
+        // Close the session here, so sleep waits for the final sync
+        let session_id = imp.session_id.borrow_mut().take();
+        self.stop_playback();
+        let Some(session_id) = session_id else {
+            monitor.release_lock();
+            return;
+        };
+        let client = imp.client.clone();
+        let (tx, rx) = async_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send_blocking(client.close_session(&session_id, position, duration));
+        });
+        let monitor = monitor.clone();
+        glib::spawn_future_local(async move {
+            match rx.recv().await {
+                Ok(Ok(_)) => log::info!("Session closed at {:.0}s before sleep", position),
+                Ok(Err(e)) => log::warn!("Failed to close the session before sleep: {}", e),
+                Err(_) => {}
+            }
+            monitor.release_lock();
+        });
+    }
 
-        let stream_url = match stream_url {
-            Some(url) => url,
-            None => {
-                log::error!("Audio stream URL not found");
+    /// Reopens the book from before sleep once the network is back.
+    fn resume_from_sleep(&self) {
+        let Some((item_id, position, was_playing)) = self.imp().resume_after_sleep.take() else {
+            return;
+        };
+        let win = self.clone();
+        let reopen = Rc::new(move || {
+            log::info!("Reopening {} at {:.0}s after resume", item_id, position);
+            win.imp().start_paused.set(!was_playing);
+            win.start_playback_at(&item_id, position);
+        });
+
+        // Interfaces take a moment to come back after resume
+        glib::timeout_add_seconds_local_once(2, move || {
+            let network = gio::NetworkMonitor::default();
+            if network.is_network_available() {
+                reopen();
                 return;
             }
-        };
+            let handler: Rc<RefCell<Option<glib::SignalHandlerId>>> = Rc::default();
+            let handler_inner = handler.clone();
+            let id = network.connect_network_changed(move |network, available| {
+                if !available {
+                    return;
+                }
+                if let Some(id) = handler_inner.borrow_mut().take() {
+                    network.disconnect(id);
+                }
+                reopen();
+            });
+            *handler.borrow_mut() = Some(id);
+        });
+    }
 
-        log::info!("Starting audio stream");
+    // ─── CONNECTIVITY ──────────────────────────────────────────────────────
 
-        let playbin = gstreamer::ElementFactory::make("playbin3")
-            .property("uri", &stream_url)
-            .build()
-            .or_else(|_| {
-                gstreamer::ElementFactory::make("playbin")
-                    .property("uri", &stream_url)
-                    .build()
-            })
-            .expect("Failed to create playbin");
+    fn connectivity(&self) -> Connectivity {
+        self.imp()
+            .connectivity
+            .borrow()
+            .as_ref()
+            .map(|connectivity| connectivity.state())
+            .unwrap_or(Connectivity::Online)
+    }
 
-        // Buffering configuration
-        if playbin.find_property("buffer-duration").is_some() {
-            playbin.set_property("buffer-duration", 15_000_000_000i64);
+    /// Lets the connectivity monitor re-probe after a failed request.
+    fn report_api_error(&self, error: &ApiError) {
+        if let Some(connectivity) = self.imp().connectivity.borrow().as_ref() {
+            connectivity.report_error(error);
         }
-        if playbin.find_property("buffer-size").is_some() {
-            playbin.set_property("buffer-size", 10 * 1024 * 1024i32);
+    }
+
+    fn connectivity_changed(&self, previous: Connectivity, state: Connectivity) {
+        let imp = self.imp();
+        match state {
+            Connectivity::Offline => imp.offline_banner.set_title("You're offline"),
+            Connectivity::Unreachable => imp.offline_banner.set_title("Can't reach the server"),
+            Connectivity::Online | Connectivity::Metered => {}
+        }
+        imp.offline_banner.set_revealed(!state.is_online());
+        if state == Connectivity::Metered {
+            self.show_toast("Metered connection: covers, syncing and downloads are paused");
         }
 
-        // Insert scaletempo so rate changes preserve pitch
-        if playbin.find_property("audio-filter").is_some() {
-            if let Ok(scaletempo) = gstreamer::ElementFactory::make("scaletempo").build() {
-                playbin.set_property("audio-filter", &scaletempo);
-            } else {
-                log::warn!("scaletempo element unavailable; rate changes will alter pitch");
-            }
+        // Losing the server mid-session greys out books that can't play
+        let signed_in = imp.client.is_authenticated()
+            && imp.stack.visible_child_name().as_deref() == Some("library");
+        if !state.is_online() && !imp.offline_mode.get() && signed_in {
+            imp.offline_mode.set(true);
+            self.render_library();
+            self.render_continue_listening();
         }
 
-        let bus = playbin.bus().unwrap();
-        let pipeline_weak = playbin.downgrade();
-        let seek_target = if start_position > 1.0 {
-            Some(start_position)
-        } else {
-            None
+        // Reload what was missed, including covers skipped while metered
+        let revalidate = match state {
+            Connectivity::Online => previous != Connectivity::Online,
+            Connectivity::Metered => !previous.is_online(),
+            Connectivity::Offline | Connectivity::Unreachable => false,
         };
-        let initial_seek_done = Rc::new(Cell::new(false));
-        let initial_seek_clone = initial_seek_done.clone();
-        let is_buffering = Rc::new(Cell::new(false));
-        let is_buffering_clone = is_buffering.clone();
-        let is_playing = imp.is_playing.clone();
-        let win_weak = self.downgrade();
-        let win_for_rate = self.clone();
+        if revalidate {
+            imp.offline_mode.set(false);
+            if imp.restore_on_reconnect.replace(false) {
+                self.try_restore_session();
+            } else if imp.client.is_authenticated()
+                && imp.stack.visible_child_name().as_deref() == Some("library")
+            {
+                self.load_library();
+            }
+        }
 
-        let guard = bus
-            .add_watch_local(move |_, msg| {
-                use gstreamer::MessageView;
-                match msg.view() {
-                    MessageView::AsyncDone(_) => {
-                        if !initial_seek_clone.get() {
-                            initial_seek_clone.set(true);
-                            if let Some(pipeline) = pipeline_weak.upgrade() {
-                                let target_pos = seek_target
-                                    .map(|p| gstreamer::ClockTime::from_seconds(p as u64))
-                                    .unwrap_or(gstreamer::ClockTime::ZERO);
-                                let rate = win_for_rate.imp().current_speed.get();
-                                let _ = pipeline.seek(
-                                    rate,
-                                    gstreamer::SeekFlags::FLUSH
-                                        | gstreamer::SeekFlags::ACCURATE,
-                                    gstreamer::SeekType::Set,
-                                    target_pos,
-                                    gstreamer::SeekType::None,
-                                    gstreamer::ClockTime::ZERO,
-                                );
-                                if let Some(pos) = seek_target {
-                                    log::info!("Seeked to position: {:.0}s (rate {:.2})", pos, rate);
-                                } else if rate != 1.0 {
-                                    log::info!("Applied rate {:.2}× on start", rate);
-                                }
+        if state == Connectivity::Online {
+            self.flush_pending_sync();
+            let deferred = imp.deferred_downloads.take();
+            if !deferred.is_empty() {
+                self.download_items(deferred);
+            }
+        }
+    }
+
+    /// Remembers the latest position of a book until the server can be
+    /// reached, on disk so it survives a restart
+    fn keep_pending_sync(&self, item_id: String, session_id: String, current: f64, duration: f64) {
+        let mut pending = self.imp().pending_sync.borrow_mut();
+        pending.insert(
+            item_id,
+            cache::PendingSync {
+                session_id,
+                current_time: current,
+                duration,
+            },
+        );
+        cache::save_pending_sync(&pending);
+    }
+
+    /// Sends the last positions recorded while offline or metered, one per
+    /// book. A book played from disk (no session id) or a session the server
+    /// dropped in the meantime is written as progress. Positions that still
+    /// can't be sent are kept for the next try.
+    fn flush_pending_sync(&self) {
+        let pending: Vec<(String, cache::PendingSync)> = self
+            .imp()
+            .pending_sync
+            .borrow()
+            .iter()
+            .map(|(id, p)| (id.clone(), p.clone()))
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        let client = self.imp().client.clone();
+        let win = self.clone();
+        glib::spawn_future_local(async move {
+            let (tx, rx) = async_channel::bounded(1);
+            std::thread::spawn(move || {
+                let mut results = Vec::new();
+                for (item_id, p) in pending {
+                    let result = if p.session_id.is_empty() {
+                        client.set_progress_position(&item_id, p.current_time, p.duration)
+                    } else {
+                        match client.sync_session(&p.session_id, p.current_time, p.duration) {
+                            Err(ApiError::Server(_)) => {
+                                client.set_progress_position(&item_id, p.current_time, p.duration)
                             }
+                            result => result,
                         }
-                        glib::ControlFlow::Continue
-                    }
-                    MessageView::Buffering(buffering) => {
-                        let percent = buffering.percent();
-                        log::debug!("Buffering: {}%", percent);
-                        if let Some(pipeline) = pipeline_weak.upgrade() {
-                            if percent < 100 {
-                                if !is_buffering_clone.get() {
-                                    let _ = pipeline.set_state(gstreamer::State::Paused);
-                                    is_buffering_clone.set(true);
-                                }
-                            } else if is_buffering_clone.get() {
-                                let _ = pipeline.set_state(gstreamer::State::Playing);
-                                is_buffering_clone.set(false);
+                    };
+                    results.push((item_id, p, result));
+                }
+                let _ = tx.send_blocking(results);
+            });
+            let Ok(results) = rx.recv().await else {
+                return;
+            };
+            let mut last_error = None;
+            {
+                let mut pending = win.imp().pending_sync.borrow_mut();
+                for (item_id, sent, result) in results {
+                    // A newer position may have been kept while this one was sent
+                    let unchanged = pending.get(&item_id).is_some_and(|p| {
+                        p.session_id == sent.session_id && p.current_time == sent.current_time
+                    });
+                    match result {
+                        Ok(()) => {
+                            log::info!(
+                                "Synced progress kept while offline: {:.0}s",
+                                sent.current_time
+                            );
+                            if unchanged {
+                                pending.remove(&item_id);
                             }
                         }
-                        glib::ControlFlow::Continue
-                    }
-                    MessageView::Error(err) => {
-                        log::error!("GStreamer error: {} - {:?}", err.error(), err.debug());
-                        glib::ControlFlow::Break
-                    }
-                    MessageView::Eos(_) => {
-                        log::info!("Audio stream ended");
-                        is_playing.set(false);
-                        if let Some(win) = win_weak.upgrade() {
-                            win.update_play_pause_icon(false);
-                            win.refresh_detail_play_button();
+                        Err(e) => {
+                            log::warn!("Failed to sync progress kept while offline: {}", e);
+                            if unchanged && !matches!(e, ApiError::Network(_)) {
+                                pending.remove(&item_id);
+                            }
+                            last_error = Some(e);
                         }
-                        glib::ControlFlow::Break
                     }
-                    _ => glib::ControlFlow::Continue,
                 }
-            })
-            .expect("Failed to add bus watch");
+                cache::save_pending_sync(&pending);
+            }
+            if let Some(e) = last_error {
+                win.report_api_error(&e);
+            }
+        });
+    }
 
-        *imp.bus_guard.borrow_mut() = Some(guard);
+    // ─── NOTIFICATIONS ─────────────────────────────────────────────────────
 
-        let _ = playbin.set_state(gstreamer::State::Playing);
-        imp.is_playing.set(true);
-        self.update_play_pause_icon(true);
-        self.refresh_detail_play_button();
-        *imp.pipeline.borrow_mut() = Some(playbin);
+    /// Sends a desktop notification while the window is in the background;
+    /// in front, toasts and the player already show what happened.
+    fn notify(&self, kind: NotificationKind, title: &str, body: &str, buttons: &[(&str, &str)]) {
+        if self.is_active() {
+            return;
+        }
+        if let Some(app) = self.application() {
+            notifications::send(app.upcast_ref(), kind, title, body, buttons);
+        }
+    }
+
+    fn check_chapter_change(&self, current_time: f64) {
+        let imp = self.imp();
+        let index = imp
+            .current_chapters
+            .borrow()
+            .iter()
+            .position(|(start, end)| current_time >= *start && current_time < *end);
+        let previous = imp.current_chapter_index.replace(index);
+        // The first reading after playback starts isn't a change
+        let (Some(previous), Some(index)) = (previous, index) else {
+            return;
+        };
+        if previous == index {
+            return;
+        }
+        let chapter = imp
+            .current_chapter_titles
+            .borrow()
+            .get(index)
+            .cloned()
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| format!("Chapter {}", index + 1));
+        self.notify(
+            NotificationKind::ChapterChange,
+            &chapter,
+            &imp.player_title.text(),
+            &[],
+        );
+    }
+
+    fn notify_book_finished(&self) {
+        let imp = self.imp();
+        let next = imp
+            .play_queue
+            .borrow()
+            .front()
+            .and_then(|id| self.cached_item(id))
+            .map(|item| format!("Up next: {}", Self::item_title_for_sort(&item)));
+        self.notify(
+            NotificationKind::BookFinished,
+            &format!("Finished “{}”", imp.player_title.text()),
+            next.as_deref().unwrap_or(""),
+            &[],
+        );
+    }
+
+    /// Tells about syncs failing repeatedly, once until one succeeds again.
+    fn record_sync_result(&self, result: Result<(), String>) {
+        let imp = self.imp();
+        match result {
+            Ok(()) => {
+                if imp.sync_failures.replace(0) >= 2 {
+                    if let Some(app) = self.application() {
+                        notifications::withdraw(app.upcast_ref(), NotificationKind::SyncError);
+                    }
+                }
+            }
+            Err(err) => {
+                let failures = imp.sync_failures.get() + 1;
+                imp.sync_failures.set(failures);
+                if failures == 2 {
+                    let message = "Listening progress isn't reaching the server";
+                    if self.is_active() {
+                        self.show_toast(message);
+                    }
+                    self.notify(NotificationKind::SyncError, message, &err, &[]);
+                }
+            }
+        }
     }
 
     // ─── PROGRESS & SYNC TIMERS ────────────────────────────────────────────
@@ -5059,11 +12895,12 @@ the session may expire and require signing in again"
             use gstreamer::prelude::*;
             let imp = win.imp();
             if let Some(pipeline) = imp.pipeline.borrow().as_ref() {
-                if imp.is_playing.get() {
+                if imp.is_playing.get() && !imp.reconnecting.get() {
                     if let Some(pos) = pipeline.query_position::<gstreamer::ClockTime>() {
                         // Precise (not truncated) seconds so a position like 599.97
                         // isn't read as 599 and pushed back into the previous chapter.
-                        let secs = pos.nseconds() as f64 / 1_000_000_000.0;
+                        let secs =
+                            pos.nseconds() as f64 / 1_000_000_000.0 + win.local_file_offset();
 
                         // After a seek, gstreamer can briefly report the old
                         // position. Skip those readings until it settles near
@@ -5079,6 +12916,20 @@ the session may expire and require signing in again"
                             }
                         }
 
+                        // A position that stops moving while playing means
+                        // the stream stalled without reporting an error
+                        if (secs - *imp.current_time.borrow()).abs() < 0.01 {
+                            let ticks = imp.stalled_ticks.get() + 1;
+                            imp.stalled_ticks.set(ticks);
+                            if ticks >= Self::STALL_TIMEOUT_SECS {
+                                win.recover_stream("no progress");
+                                return glib::ControlFlow::Continue;
+                            }
+                        } else {
+                            imp.stalled_ticks.set(0);
+                            imp.reconnect_attempts.set(0);
+                        }
+
                         imp.updating_slider.set(true);
                         imp.position_scale.set_value(secs);
                         imp.updating_slider.set(false);
@@ -5088,6 +12939,7 @@ the session may expire and require signing in again"
                         win.refresh_now_playing_info();
                         win.update_mpris_position(secs);
                         win.check_chapter_end_sleep(secs);
+                        win.check_chapter_change(secs);
                     }
                 }
                 glib::ControlFlow::Continue
@@ -5113,17 +12965,46 @@ the session may expire and require signing in again"
             }
 
             let session_id = imp.session_id.borrow().clone();
-            if let Some(sid) = session_id {
+            let item_id = imp.current_item_id.borrow().clone();
+            if let (None, Some(item_id)) = (&session_id, &item_id) {
+                // Played from disk: progress is written directly once online
                 let current = *imp.current_time.borrow();
                 let duration = *imp.duration.borrow();
-                let client = imp.client.clone();
+                win.keep_pending_sync(item_id.clone(), String::new(), current, duration);
+                if win.connectivity() == Connectivity::Online && !imp.offline_mode.get() {
+                    win.flush_pending_sync();
+                }
+            }
+            if let (Some(sid), Some(item_id)) = (session_id, item_id) {
+                let current = *imp.current_time.borrow();
+                let duration = *imp.duration.borrow();
+
+                // Offline or metered, only the latest position is kept
+                if win.connectivity() != Connectivity::Online {
+                    win.keep_pending_sync(item_id, sid, current, duration);
+                    return glib::ControlFlow::Continue;
+                }
 
-                std::thread::spawn(move || match client.sync_session(&sid, current, duration) {
-                    Ok(_) => {
-                        log::debug!("Session synced: {:.0}s", current)
+                let client = imp.client.clone();
+                let pending = (item_id, sid.clone(), current, duration);
+                let (tx, rx) = async_channel::bounded(1);
+                std::thread::spawn(move || {
+                    let result = client.sync_session(&sid, current, duration);
+                    match &result {
+                        Ok(_) => log::debug!("Session synced: {:.0}s", current),
+                        Err(e) => log::warn!("Sync error: {}", e),
                     }
-                    Err(e) => {
-                        log::warn!("Sync error: {}", e)
+                    let _ = tx.send_blocking(result);
+                });
+                let win = win.clone();
+                glib::spawn_future_local(async move {
+                    if let Ok(result) = rx.recv().await {
+                        if let Err(e @ ApiError::Network(_)) = &result {
+                            let (item_id, sid, current, duration) = pending;
+                            win.keep_pending_sync(item_id, sid, current, duration);
+                            win.report_api_error(e);
+                        }
+                        win.record_sync_result(result.map_err(|e| e.to_string()));
                     }
                 });
             }
@@ -5291,6 +13172,107 @@ fn format_time(seconds: f64) -> String {
     }
 }
 
+/// Case-insensitive comparison that orders digit runs by value, so
+/// "Book 2" sorts before "Book 10".
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    fn take_number(it: &mut std::iter::Peekable<impl Iterator<Item = char>>) -> String {
+        let mut digits = String::new();
+        while let Some(c) = it.next_if(|c| c.is_ascii_digit()) {
+            digits.push(c);
+        }
+        digits
+    }
+
+    let mut a = a.chars().flat_map(char::to_lowercase).peekable();
+    let mut b = b.chars().flat_map(char::to_lowercase).peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_digits = take_number(&mut a);
+                let y_digits = take_number(&mut b);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ord = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ord != std::cmp::Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn parse_time(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        let value: f64 = part.trim().parse().ok()?;
+        if value < 0.0 {
+            return None;
+        }
+        total = total * 60.0 + value;
+    }
+    Some(total)
+}
+
+/// Splits a comma separated field into trimmed, non-empty values.
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn format_series(series: &[SeriesItem]) -> String {
+    series
+        .iter()
+        .filter_map(|s| {
+            let name = s.name.as_deref()?;
+            Some(match s.sequence.as_deref() {
+                Some(seq) if !seq.is_empty() => format!("{} #{}", name, seq),
+                _ => name.to_string(),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Parses the "Name #Sequence; Other Name" form produced by `format_series`.
+fn parse_series(text: &str) -> Vec<SeriesItem> {
+    text.split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (name, sequence) = match entry.rsplit_once(" #") {
+                Some((name, seq)) if !seq.trim().is_empty() => {
+                    (name.trim(), Some(seq.trim().to_string()))
+                }
+                _ => (entry, None),
+            };
+            SeriesItem {
+                id: None,
+                name: Some(name.to_string()),
+                sequence,
+            }
+        })
+        .collect()
+}
+
 fn extract_access_token(url: &str) -> Option<String> {
     extract_url_param(url, "access_token")
         .or_else(|| extract_url_param(url, "accessToken"))
@@ -5316,3 +13298,42 @@ fn extract_url_param(url: &str, key: &str) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn natural_cmp_orders_numbers_by_value() {
+        assert_eq!(natural_cmp("Book 2", "Book 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Book 10", "Book 2"), Ordering::Greater);
+        assert_eq!(natural_cmp("Book 9: Dune", "Book 10: Dune"), Ordering::Less);
+        assert_eq!(natural_cmp("Book", "Book 1"), Ordering::Less);
+    }
+
+    #[test]
+    fn natural_cmp_ignores_leading_zeros() {
+        assert_eq!(natural_cmp("Book 02", "Book 2"), Ordering::Equal);
+        assert_eq!(natural_cmp("Book 007", "Book 10"), Ordering::Less);
+        assert_eq!(natural_cmp("Book 0", "Book 000"), Ordering::Equal);
+    }
+
+    #[test]
+    fn natural_cmp_ignores_case() {
+        assert_eq!(natural_cmp("the hobbit", "The Hobbit"), Ordering::Equal);
+        assert_eq!(natural_cmp("apple", "Banana"), Ordering::Less);
+        assert_eq!(natural_cmp("Émile", "émile"), Ordering::Equal);
+    }
+
+    #[test]
+    fn natural_cmp_handles_numbers_longer_than_u64() {
+        let max = "Part 18446744073709551615";
+        let beyond = "Part 18446744073709551616";
+        let longer = "Part 100000000000000000000000000000";
+        assert_eq!(natural_cmp(max, beyond), Ordering::Less);
+        assert_eq!(natural_cmp(beyond, longer), Ordering::Less);
+        assert_eq!(natural_cmp(longer, "Part 9"), Ordering::Greater);
+        assert_eq!(natural_cmp(longer, longer), Ordering::Equal);
+    }
+}